@@ -6,7 +6,12 @@ use axum::{
     response::{IntoResponse, Response},
     Extension, Router, Server,
 };
-use silkenweb::{document::Document, dom::Dry, router, task};
+use silkenweb::{
+    document::{Document, HtmlDocument, WasmBootstrap},
+    dom::Dry,
+    elements::html::base,
+    router, task,
+};
 use ssr_full_app::app;
 use tokio_util::task::LocalPoolHandle;
 use tower_http::services::ServeDir;
@@ -45,15 +50,10 @@ async fn render(uri: Uri) -> impl IntoResponse {
     router::set_url_path(uri.path());
     task::render_now().await;
 
-    let page_html = format!(
-        include_str!("../../app/page.tmpl.html"),
-        head_html = Dry::head_inner_html(),
-        body_html = body.freeze(),
-        init_script = r#"
-            import init, {js_main} from '/pkg/ssr_full_axum_client.js';
-            init().then(js_main);
-        "#
-    );
+    let page_html = HtmlDocument::new()
+        .head_child(base().href("/"))
+        .bootstrap(WasmBootstrap::new("/pkg/ssr_full_axum_client.js").entry("js_main"))
+        .render(&body.freeze());
 
     Response::builder()
         .status(StatusCode::OK)
@@ -1,7 +1,12 @@
 use std::path::Path;
 
 use log::LevelFilter;
-use silkenweb::{document::Document, dom::Dry, router, task};
+use silkenweb::{
+    document::{Document, HtmlDocument, WasmBootstrap},
+    dom::Dry,
+    elements::html::base,
+    router, task,
+};
 use ssr_full_app::app;
 use xshell::Shell;
 use xtask_wasm::{
@@ -45,21 +50,17 @@ fn generate_pages(dist_dir: &Path) -> xshell::Result<()> {
         let (head, body) = app::<Dry>();
         Dry::mount_in_head("head", head);
         let body = body.freeze();
+        let document = HtmlDocument::new().head_child(base().href("/")).bootstrap(
+            WasmBootstrap::new("/ssr_example_pre_rendered_client.js")
+                .wasm("/ssr_example_pre_rendered_client_bg.wasm"),
+        );
         let sh = Shell::new()?;
 
         for page in ["index", "page_1", "page_2"] {
             router::set_url_path(format!("{page}.html").as_str());
             task::server::render_now_sync();
 
-            let page_html = format!(
-                include_str!("../../app/page.tmpl.html"),
-                init_script = r#"
-                import init from "/ssr_example_pre_rendered_client.js";
-                init(new URL('ssr_example_pre_rendered_client_bg.wasm', import.meta.url));
-            "#,
-                head_html = Dry::head_inner_html(),
-                body_html = body
-            );
+            let page_html = document.render(&body);
             let page_path = Path::new(dist_dir).join(page).with_extension("html");
 
             sh.write_file(page_path, page_html)?;
@@ -10,6 +10,10 @@ pub mod window {
         WINDOW.with(|win| win.request_animation_frame(callback).unwrap_throw());
     }
 
+    pub fn request_idle_callback(callback: &::js_sys::Function) -> Result<u32, JsValue> {
+        WINDOW.with(|win| win.request_idle_callback(callback))
+    }
+
     pub fn history() -> web_sys::History {
         WINDOW.with(|win| win.history().unwrap_throw())
     }
@@ -33,6 +37,34 @@ pub mod window {
     pub fn performance() -> Option<web_sys::Performance> {
         WINDOW.with(|w| w.performance())
     }
+
+    pub fn inner_width() -> f64 {
+        WINDOW.with(|w| w.inner_width().unwrap_throw().as_f64().unwrap_throw())
+    }
+
+    pub fn inner_height() -> f64 {
+        WINDOW.with(|w| w.inner_height().unwrap_throw().as_f64().unwrap_throw())
+    }
+
+    pub fn scroll_x() -> f64 {
+        WINDOW.with(|w| w.scroll_x().unwrap_throw())
+    }
+
+    pub fn scroll_y() -> f64 {
+        WINDOW.with(|w| w.scroll_y().unwrap_throw())
+    }
+
+    pub fn device_pixel_ratio() -> f64 {
+        WINDOW.with(|w| w.device_pixel_ratio())
+    }
+
+    pub fn is_online() -> bool {
+        WINDOW.with(|w| w.navigator().on_line())
+    }
+
+    pub fn match_media(query: &str) -> web_sys::MediaQueryList {
+        WINDOW.with(|w| w.match_media(query).unwrap_throw().unwrap_throw())
+    }
 }
 
 pub mod document {
@@ -73,6 +105,15 @@ pub mod document {
         DOCUMENT.with(|doc| doc.query_selector(selectors))
     }
 
+    pub fn query_selector_all(selectors: &str) -> Result<Vec<web_sys::Element>, JsValue> {
+        let nodes = DOCUMENT.with(|doc| doc.query_selector_all(selectors))?;
+
+        Ok((0..nodes.length())
+            .filter_map(|index| nodes.item(index))
+            .map(|node| node.unchecked_into())
+            .collect())
+    }
+
     pub fn head() -> Option<web_sys::HtmlHeadElement> {
         DOCUMENT.with(|doc| doc.head())
     }
@@ -80,6 +121,22 @@ pub mod document {
     pub fn body() -> Option<web_sys::HtmlElement> {
         DOCUMENT.with(|doc| doc.body())
     }
+
+    pub fn document_element() -> Option<web_sys::Element> {
+        DOCUMENT.with(|doc| doc.document_element())
+    }
+
+    pub fn hidden() -> bool {
+        DOCUMENT.with(|doc| doc.hidden())
+    }
+
+    pub fn elements_from_point(x: f32, y: f32) -> Vec<web_sys::Element> {
+        DOCUMENT
+            .with(|doc| doc.elements_from_point(x, y))
+            .iter()
+            .map(|elem| elem.unchecked_into())
+            .collect()
+    }
 }
 
 pub trait GlobalEventTarget {
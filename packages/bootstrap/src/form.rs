@@ -0,0 +1,38 @@
+use futures_signals::signal::SignalExt;
+use silkenweb::{
+    dom::Dom,
+    elements::{
+        html::{div, Div},
+        HtmlElement,
+    },
+    form::Field,
+    node::element::{Element, TextParentElement},
+    value::Sig,
+};
+
+use crate::css;
+
+/// Render bootstrap validation styles from the state of a [`Field`].
+pub trait Validated: Element {
+    /// Add the `is-invalid` class when `field` has a visible error.
+    fn validated<T: 'static>(self, field: &Field<T>) -> Self {
+        self.classes(Sig(field
+            .visible_error_signal()
+            .map(|error| error.map(|_| css::IS_INVALID))))
+    }
+}
+
+impl<T: HtmlElement> Validated for T {}
+
+/// Display the visible error from `field` as bootstrap invalid feedback.
+///
+/// The `id` is [`Field::error_id`], so the field's element is described by the
+/// feedback.
+pub fn invalid_feedback<D: Dom, T: 'static>(field: &Field<T>) -> Div<D> {
+    div()
+        .id(field.error_id())
+        .class(css::INVALID_FEEDBACK)
+        .text(Sig(field
+            .visible_error_signal()
+            .map(Option::unwrap_or_default)))
+}
@@ -10,6 +10,7 @@ pub mod badge;
 pub mod button;
 pub mod button_group;
 pub mod dropdown;
+pub mod form;
 pub mod icon;
 pub mod tab_bar;
 pub mod utility;
@@ -8,7 +8,7 @@ use axum::{
     BoxError, TypedHeader,
 };
 use serde::de::DeserializeOwned;
-use silkenweb::{dom::Dry, node::Node};
+use silkenweb::{dom::Dry, form, node::Node, serialize::HtmlSerializer};
 
 pub struct HtmxResponse(Node<Dry>);
 
@@ -20,10 +20,16 @@ impl HtmxResponse {
 
 impl IntoResponse for HtmxResponse {
     fn into_response(self) -> Response {
-        (TypedHeader(ContentType::html()), self.0.to_string()).into_response()
+        let html = HtmlSerializer::new().to_bytes(&self.0);
+
+        (TypedHeader(ContentType::html()), html).into_response()
     }
 }
 
+/// Extract a URL encoded request body with `serde_urlencoded`.
+///
+/// See [`HtmxFormRequest`] to decode the body in the same way as
+/// [`Form::values_as`](silkenweb::elements::html::Form::values_as).
 pub struct HtmxPostRequest<T>(pub T);
 
 #[async_trait]
@@ -38,11 +44,56 @@ where
     type Rejection = http::StatusCode;
 
     async fn from_request(req: Request<Body>, state: &State) -> Result<Self, Self::Rejection> {
-        let bytes = Bytes::from_request(req, state)
-            .await
-            .map_err(|_| http::StatusCode::BAD_REQUEST)?;
+        let bytes = body_bytes(req, state).await?;
+
         serde_urlencoded::from_bytes(&bytes)
             .map_err(|_| http::StatusCode::BAD_REQUEST)
             .map(HtmxPostRequest)
     }
 }
+
+/// Extract a submitted form, in the same way as
+/// [`Form::values_as`](silkenweb::elements::html::Form::values_as).
+///
+/// This means client and server can share a form struct. Unlike
+/// [`HtmxPostRequest`], repeated names can be deserialized into a sequence, the
+/// last value is used where a single value is expected, and booleans accept
+/// `on` and `off`. See [`form::from_entries`].
+pub struct HtmxFormRequest<T>(pub T);
+
+#[async_trait]
+impl<State, Body, T> FromRequest<State, Body> for HtmxFormRequest<T>
+where
+    State: Send + Sync,
+    Body: HttpBody + Send + 'static,
+    Body::Data: Send,
+    Body::Error: Into<BoxError>,
+    T: DeserializeOwned,
+{
+    type Rejection = http::StatusCode;
+
+    async fn from_request(req: Request<Body>, state: &State) -> Result<Self, Self::Rejection> {
+        let bytes = body_bytes(req, state).await?;
+        let entries: Vec<(String, String)> =
+            serde_urlencoded::from_bytes(&bytes).map_err(|_| http::StatusCode::BAD_REQUEST)?;
+
+        form::from_entries(entries)
+            .map_err(|_| http::StatusCode::BAD_REQUEST)
+            .map(HtmxFormRequest)
+    }
+}
+
+async fn body_bytes<State, Body>(
+    req: Request<Body>,
+    state: &State,
+) -> Result<Bytes, http::StatusCode>
+where
+    State: Send + Sync,
+    Body: HttpBody + Send + 'static,
+    Body::Data: Send,
+    Body::Error: Into<BoxError>,
+{
+    Bytes::from_request(req, state)
+        .await
+        .map_err(|_| http::StatusCode::BAD_REQUEST)
+}
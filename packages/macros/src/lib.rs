@@ -203,8 +203,21 @@ pub fn derive_element(item: TokenStream) -> TokenStream {
                 Self{#target: self.#target.spawn_future(future) #other_fields}
             }
 
-            fn on(self, name: &'static str, f: impl FnMut(::silkenweb::macros::JsValue) + 'static) -> Self {
-                Self{#target: self.#target.on(name, f) #other_fields}
+            fn on_with_options(
+                self,
+                name: &'static str,
+                options: ::silkenweb::event::ListenerOptions,
+                f: impl FnMut(::silkenweb::macros::JsValue) + 'static,
+            ) -> Self {
+                Self{#target: self.#target.on_with_options(name, options, f) #other_fields}
+            }
+
+            fn on_mount(self, f: impl FnMut(&Self::DomElement) + 'static) -> Self {
+                Self{#target: self.#target.on_mount(f) #other_fields}
+            }
+
+            fn on_unmount(self, f: impl FnMut(&Self::DomElement) + 'static) -> Self {
+                Self{#target: self.#target.on_unmount(f) #other_fields}
             }
         }
     )
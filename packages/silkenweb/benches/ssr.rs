@@ -1,4 +1,4 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use futures_signals::signal::{Mutable, SignalExt};
 use silkenweb::{
     dom::{Dom, Dry},
@@ -7,6 +7,7 @@ use silkenweb::{
         ElementEvents,
     },
     node::element::{Const, ParentElement, TextParentElement},
+    serialize::HtmlSerializer,
     task::{server::render_now_sync, sync_scope},
     value::Sig,
 };
@@ -47,5 +48,40 @@ pub fn ssr(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, ssr);
+fn large_page() -> Main<Dry, Const> {
+    html::main()
+        .child(h1().text("A large page & lots of counters"))
+        .children((0..1000).map(counter))
+        .freeze()
+}
+
+/// Compare serializing a large, already rendered page with `to_string` and
+/// [`HtmlSerializer`].
+pub fn ssr_serialize(c: &mut Criterion) {
+    sync_scope(|| {
+        let node = large_page();
+        render_now_sync();
+
+        let mut group = c.benchmark_group("ssr_serialize");
+        group.bench_function("to_string", |b| b.iter(|| black_box(node.to_string())));
+
+        let mut serializer = HtmlSerializer::new();
+        group.bench_function("serializer", |b| {
+            b.iter(|| black_box(serializer.serialize(&node).len()))
+        });
+
+        let mut output = Vec::new();
+        group.bench_function("serializer_io", |b| {
+            b.iter(|| {
+                output.clear();
+                serializer.write(&node, &mut output).unwrap();
+                black_box(output.len())
+            })
+        });
+
+        group.finish();
+    })
+}
+
+criterion_group!(benches, ssr, ssr_serialize);
 criterion_main!(benches);
@@ -0,0 +1,107 @@
+//! Pass values down the element tree.
+//!
+//! Elements are built from the inside out, so a value is provided for the
+//! duration of a closure that builds the children. Any code run inside that
+//! closure can look the value up by type with [`get`] or [`with`], without it
+//! being passed down explicitly through each component.
+//!
+//! Context is stored per [`task::scope`], so concurrent server side renders
+//! don't see each others values.
+//!
+//! Children that are built later, for example from a signal, won't be inside
+//! the closure passed to [`provide`]. Use [`Context::current`] to capture the
+//! current context, and [`Context::enter`] to build the children with it.
+//!
+//! # Example
+//!
+//! ```
+#![doc = function_body!("tests/doc/context.rs", module_example, [])]
+//! ```
+//! 
+//! [`task::scope`]: crate::task::scope
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use include_doc::function_body;
+
+use crate::task;
+
+/// Make `value` available to anything built by `f`.
+///
+/// If a value of the same type has already been provided, it is shadowed for
+/// the duration of `f`.
+pub fn provide<T: 'static, R>(value: T, f: impl FnOnce() -> R) -> R {
+    let parent = Context::current();
+    let context = Context(Some(Rc::new(Frame {
+        value: Rc::new(value),
+        parent: parent.0,
+    })));
+
+    context.enter(f)
+}
+
+/// Get a clone of the innermost provided value of type `T`.
+pub fn get<T: Clone + 'static>() -> Option<T> {
+    with(|value: Option<&T>| value.cloned())
+}
+
+/// Call `f` with a reference to the innermost provided value of type `T`.
+pub fn with<T: 'static, R>(f: impl FnOnce(Option<&T>) -> R) -> R {
+    Context::current().with(f)
+}
+
+/// A snapshot of all provided values.
+///
+/// This is cheap to clone.
+#[derive(Clone, Default)]
+pub struct Context(Option<Rc<Frame>>);
+
+impl Context {
+    /// Capture the current context.
+    pub fn current() -> Self {
+        task::local::with(|local| local.context.0.borrow().clone())
+    }
+
+    /// Run `f` with `self` as the current context.
+    ///
+    /// The previous context is restored when `f` returns.
+    pub fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+        let previous = task::local::with(|local| local.context.0.replace(self.clone()));
+        let _restore = Restore(Some(previous));
+
+        f()
+    }
+
+    /// Call `f` with the innermost value of type `T` in this context.
+    pub fn with<T: 'static, R>(&self, f: impl FnOnce(Option<&T>) -> R) -> R {
+        let mut frame = self.0.as_deref();
+
+        while let Some(current) = frame {
+            if let Some(value) = current.value.downcast_ref() {
+                return f(Some(value));
+            }
+
+            frame = current.parent.as_deref();
+        }
+
+        f(None)
+    }
+}
+
+struct Frame {
+    value: Rc<dyn Any>,
+    parent: Option<Rc<Frame>>,
+}
+
+/// Restore the previous context, even if the closure panics.
+struct Restore(Option<Context>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            task::local::with(|local| local.context.0.replace(previous));
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TaskLocal(RefCell<Context>);
@@ -27,9 +27,13 @@ use crate::{
 };
 
 mod dry;
+mod html_document;
 mod hydro;
 mod wet;
 
+pub use html_document::{HtmlDocument, WasmBootstrap};
+pub(crate) use hydro::mount_islands;
+
 /// Manage an event handler.
 ///
 /// This will remove the event handler when dropped.
@@ -274,10 +278,18 @@ fn wet_insert_mounted(id: &str, element: GenericElement<Wet, Const>) {
     );
 }
 
+fn wet_insert_island(element: GenericElement<Wet, Const>) {
+    WET_ISLANDS.with(|islands| islands.borrow_mut().push(element));
+}
+
 fn wet_unmount() {
     for element in WET_MOUNTED.take().into_values() {
         element.dom_element().remove()
     }
+
+    for element in WET_ISLANDS.take() {
+        element.dom_element().remove()
+    }
 }
 
 struct MountedInHead<D: Dom>(RefCell<HashMap<String, ChildVecHandle<D, ParentShared>>>);
@@ -324,6 +336,7 @@ impl<D: Dom> MountedInHead<D> {
 
 thread_local! {
     static WET_MOUNTED: RefCell<HashMap<String, GenericElement<Wet, Const>>> = RefCell::new(HashMap::new());
+    static WET_ISLANDS: RefCell<Vec<GenericElement<Wet, Const>>> = const { RefCell::new(Vec::new()) };
     static WET_MOUNTED_IN_HEAD: MountedInHead<Wet> = MountedInHead::new();
     static HYDRO_MOUNTED_IN_HEAD: MountedInHead<Hydro> = MountedInHead::new();
 }
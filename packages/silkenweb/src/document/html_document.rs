@@ -0,0 +1,249 @@
+use std::fmt::Write;
+
+use include_doc::function_body;
+
+use super::Document;
+use crate::{
+    dom::Dry,
+    node::Node,
+    serialize::{write_attribute_value, FmtWrite, HtmlWrite, ToHtml},
+    state_transfer,
+};
+
+/// A complete HTML document, for server side rendering.
+///
+/// This writes the `<!DOCTYPE>`, `<html>`, `<head>` and `<body>` elements
+/// around the app. The `<head>` contains:
+///
+/// - a `<meta charset="utf-8">` element.
+/// - any children added with [`HtmlDocument::head_child`].
+/// - everything mounted with [`Document::mount_in_head`], at the time the
+///   document is written.
+/// - any state registered with [`state_transfer`](crate::state_transfer), at
+///   the time the document is written.
+/// - a script to load the client, if one was set with
+///   [`HtmlDocument::bootstrap`].
+///
+/// A document can be used as the shell of an
+/// [`HtmlStream`](crate::streaming::HtmlStream) with
+/// [`HtmlStream::document`](crate::streaming::HtmlStream::document).
+///
+/// # Example
+///
+/// ```
+#[doc = function_body!("tests/doc/document.rs", html_document, [])]
+/// ```
+#[derive(Clone, Default)]
+pub struct HtmlDocument {
+    lang: Option<String>,
+    head_html: String,
+    body_attributes: Vec<(String, String)>,
+    body_classes: Vec<String>,
+    bootstrap: Option<WasmBootstrap>,
+}
+
+impl HtmlDocument {
+    /// An empty document, with no `lang`, head children, body attributes or
+    /// bootstrap script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the `lang` attribute on `<html>`.
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    /// Add a child to `<head>`.
+    ///
+    /// `child` is rendered straight away, so it should be static. Use
+    /// [`Document::mount_in_head`] for reactive content.
+    pub fn head_child(mut self, child: impl Into<Node<Dry>>) -> Self {
+        child
+            .into()
+            .write_html(&mut FmtWrite(&mut self.head_html))
+            .expect("Writing to a `String` shouldn't fail");
+        self
+    }
+
+    /// Set an attribute on `<body>`.
+    ///
+    /// This replaces any previous value of `name`, except for `class`, where
+    /// `value` is added to the classes from [`HtmlDocument::body_class`].
+    pub fn body_attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        let value = value.into();
+
+        if name == CLASS_ATTR {
+            return self.body_class(value);
+        }
+
+        if let Some((_, existing)) = self
+            .body_attributes
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            *existing = value;
+        } else {
+            self.body_attributes.push((name, value));
+        }
+
+        self
+    }
+
+    /// Add a class to `<body>`.
+    pub fn body_class(mut self, class: impl Into<String>) -> Self {
+        self.body_classes.push(class.into());
+        self
+    }
+
+    /// Load and start the client with `bootstrap`.
+    pub fn bootstrap(mut self, bootstrap: WasmBootstrap) -> Self {
+        self.bootstrap = Some(bootstrap);
+        self
+    }
+
+    /// Render the document, with `body` as the content of `<body>`.
+    ///
+    /// This should be called after [`render_now`](crate::task::render_now),
+    /// so `body` and the content mounted in `<head>` are up to date.
+    pub fn render(&self, body: &impl ToHtml) -> String {
+        let mut html = String::new();
+        self.write(body, &mut FmtWrite(&mut html))
+            .expect("Writing to a `String` shouldn't fail");
+        html
+    }
+
+    /// Write the document into `w`, with `body` as the content of `<body>`.
+    ///
+    /// See [`HtmlDocument::render`].
+    pub fn write<W: HtmlWrite>(&self, body: &impl ToHtml, w: &mut W) -> Result<(), W::Error> {
+        self.write_open(w)?;
+        body.write_html(w)?;
+        self.write_close(w)
+    }
+
+    /// Write everything up to and including the `<body>` tag.
+    pub(crate) fn write_open<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        w.write_str("<!DOCTYPE html><html")?;
+
+        if let Some(lang) = &self.lang {
+            write_attr(w, "lang", lang)?;
+        }
+
+        w.write_str(r#"><head><meta charset="utf-8">"#)?;
+        w.write_str(&self.head_html)?;
+        w.write_str(&Dry::head_inner_html())?;
+        state_transfer::write_script(w)?;
+
+        if let Some(bootstrap) = &self.bootstrap {
+            w.write_str(r#"<script type="module">"#)?;
+            w.write_str(&bootstrap.script())?;
+            w.write_str("</script>")?;
+        }
+
+        w.write_str("</head><body")?;
+
+        if !self.body_classes.is_empty() {
+            write_attr(w, CLASS_ATTR, &self.body_classes.join(" "))?;
+        }
+
+        for (name, value) in &self.body_attributes {
+            write_attr(w, name, value)?;
+        }
+
+        w.write_str(">")
+    }
+
+    /// Write everything after the content of `<body>`.
+    pub(crate) fn write_close<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        w.write_str("</body></html>")
+    }
+}
+
+/// A script to load and start a client built with `wasm-bindgen --target web`.
+#[derive(Clone)]
+pub struct WasmBootstrap {
+    js_url: String,
+    wasm_url: Option<String>,
+    entry: Option<String>,
+}
+
+impl WasmBootstrap {
+    /// Load the client from the JavaScript module at `js_url`.
+    pub fn new(js_url: impl Into<String>) -> Self {
+        Self {
+            js_url: js_url.into(),
+            wasm_url: None,
+            entry: None,
+        }
+    }
+
+    /// Set the URL of the `.wasm` file.
+    ///
+    /// By default, `wasm-bindgen` looks for it next to the JavaScript module.
+    pub fn wasm(mut self, wasm_url: impl Into<String>) -> Self {
+        self.wasm_url = Some(wasm_url.into());
+        self
+    }
+
+    /// Call the exported function `entry` once the module is initialized.
+    ///
+    /// This isn't required if the client uses `#[wasm_bindgen(start)]`.
+    pub fn entry(mut self, entry: impl Into<String>) -> Self {
+        self.entry = Some(entry.into());
+        self
+    }
+
+    fn script(&self) -> String {
+        let mut script = format!(
+            "import init, * as client from {};init(",
+            js_string(&self.js_url)
+        );
+
+        if let Some(wasm_url) = &self.wasm_url {
+            write!(script, "{{module_or_path: {}}}", js_string(wasm_url)).unwrap();
+        }
+
+        script.push(')');
+
+        if let Some(entry) = &self.entry {
+            write!(script, ".then(() => client[{}]())", js_string(entry)).unwrap();
+        }
+
+        script.push(';');
+        script
+    }
+}
+
+const CLASS_ATTR: &str = "class";
+
+fn write_attr<W: HtmlWrite>(w: &mut W, name: &str, value: &str) -> Result<(), W::Error> {
+    w.write_str(" ")?;
+    w.write_str(name)?;
+    w.write_str("=\"")?;
+    write_attribute_value(value, w)?;
+    w.write_str("\"")
+}
+
+/// Quote `s` as a JavaScript string that's safe to use in a `<script>`.
+fn js_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '<' | '>' | '\u{2028}' | '\u{2029}' => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
@@ -2,8 +2,8 @@ use futures::channel::oneshot;
 use silkenweb_task::spawn_local;
 
 use super::{
-    children_with_id, document_head, wet_insert_mounted, wet_unmount, Document, MountHydro,
-    MountHydroHead,
+    children_with_id, document_head, wet_insert_island, wet_insert_mounted, wet_unmount, Document,
+    MountHydro, MountHydroHead,
 };
 use crate::{
     document::HYDRO_MOUNTED_IN_HEAD,
@@ -12,7 +12,7 @@ use crate::{
     mount_point,
     node::element::{
         child_vec::{ChildVec, ParentShared},
-        Const, GenericElement, Namespace,
+        lifecycle, Const, GenericElement, Namespace,
     },
 };
 
@@ -31,12 +31,12 @@ impl Document for Hydro {
         let element = element.into();
         let id = id.to_string();
 
+        let mut stats = HydrationStats::new();
         let (send, receive) = oneshot::channel();
         spawn_local(async move {
-            let mut stats = HydrationStats::default();
-
             let mount_point = mount_point(&id);
             let wet_element = element.hydrate(&mount_point, &mut stats);
+            lifecycle::dom_changed();
             wet_insert_mounted(&id, wet_element);
             let _ = send.send(stats);
         });
@@ -52,9 +52,9 @@ impl Document for Hydro {
         let id = id.to_string();
         let head_elem = document_head();
 
+        let mut stats = HydrationStats::new();
         let (send, receive) = oneshot::channel();
         spawn_local(async move {
-            let mut stats = HydrationStats::default();
             hydro_head_elem.hydrate_in_head(head_elem, &id, &mut stats);
             let _ = send.send(stats);
         });
@@ -71,3 +71,27 @@ impl Document for Hydro {
         HYDRO_MOUNTED_IN_HEAD.with(|m| m.inner_html())
     }
 }
+
+/// Hydrate each element against its existing DOM root.
+///
+/// See [`Islands`](crate::hydration::Islands).
+pub(crate) fn mount_islands(
+    islands: Vec<(web_sys::Element, GenericElement<Hydro, Const>)>,
+) -> MountHydro {
+    #[cfg(debug_assertions)]
+    crate::log_panics();
+
+    let mut stats = HydrationStats::new();
+    let (send, receive) = oneshot::channel();
+    spawn_local(async move {
+        for (root, element) in islands {
+            wet_insert_island(element.hydrate(&root, &mut stats));
+        }
+
+        lifecycle::dom_changed();
+
+        let _ = send.send(stats);
+    });
+
+    MountHydro(receive)
+}
@@ -9,7 +9,7 @@ use crate::{
     mount_point,
     node::element::{
         child_vec::{ChildVec, ParentShared},
-        Const, GenericElement,
+        lifecycle, Const, GenericElement,
     },
 };
 
@@ -23,6 +23,7 @@ impl Document for Wet {
         mount_point(id)
             .replace_with_with_node_1(&element.dom_element())
             .unwrap_throw();
+        lifecycle::dom_changed();
         wet_insert_mounted(id, element);
     }
 
@@ -122,6 +122,8 @@ impl private::Dom for Wet {
     type Element = WetElement;
     type Node = WetNode;
     type Text = WetText;
+
+    const IS_WET: bool = true;
 }
 
 impl InstantiableDom for Wet {}
@@ -153,4 +155,6 @@ impl<Param: 'static, D: InstantiableDom> private::Dom for Template<Param, D> {
     type Element = TemplateElement<Param, D>;
     type Node = TemplateNode<Param, D>;
     type Text = TemplateText<D>;
+
+    const IS_WET: bool = D::IS_WET;
 }
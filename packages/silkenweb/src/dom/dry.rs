@@ -1,6 +1,9 @@
-use std::{cell::RefCell, fmt, rc::Rc};
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    fmt,
+    rc::Rc,
+};
 
-use html_escape::encode_text_minimal;
 use wasm_bindgen::JsValue;
 
 use super::{
@@ -8,7 +11,11 @@ use super::{
     wet::WetElement,
     Dry,
 };
-use crate::node::element::Namespace;
+use crate::{
+    event::ListenerOptions,
+    node::element::Namespace,
+    serialize::{write_text, write_xml_text, FmtWrite, HtmlWrite, PrettyPrinter, ToHtml},
+};
 
 mod shared_element;
 
@@ -22,6 +29,14 @@ impl DryElement {
     fn from_shared(shared: SharedDryElement<DryNode>) -> Self {
         Self(Rc::new(RefCell::new(shared)))
     }
+
+    pub fn shared(&self) -> Ref<'_, SharedDryElement<DryNode>> {
+        self.0.borrow()
+    }
+
+    pub fn shared_mut(&self) -> RefMut<'_, SharedDryElement<DryNode>> {
+        self.0.borrow_mut()
+    }
 }
 
 impl private::DomElement for DryElement {
@@ -78,10 +93,11 @@ impl private::DomElement for DryElement {
     fn on(
         &mut self,
         name: &'static str,
+        options: ListenerOptions,
         f: impl FnMut(JsValue) + 'static,
         events: &mut EventStore,
     ) {
-        self.0.borrow_mut().on(name, f, events)
+        self.0.borrow_mut().on(name, options, f, events)
     }
 
     fn dom_element(&self) -> web_sys::Element {
@@ -108,6 +124,10 @@ impl private::DomElement for DryElement {
     ) {
         self.0.borrow_mut().observe_attributes(f, events)
     }
+
+    fn select_option(&mut self, value: &str) {
+        self.0.borrow_mut().select_option(value)
+    }
 }
 
 impl private::InstantiableDomElement for DryElement {
@@ -126,6 +146,29 @@ impl fmt::Display for DryElement {
     }
 }
 
+impl ToHtml for DryElement {
+    fn write_html<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        self.0.borrow().write_html(w)
+    }
+
+    fn write_pretty_html<W: HtmlWrite>(
+        &self,
+        printer: &PrettyPrinter,
+        depth: usize,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        self.0.borrow().write_pretty_html(printer, depth, w)
+    }
+
+    fn write_xml<W: HtmlWrite>(
+        &self,
+        parent_namespace: Option<&Namespace>,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        self.0.borrow().write_xml(parent_namespace, w)
+    }
+}
+
 #[derive(Clone)]
 pub struct DryText(Rc<RefCell<SharedDryText<DryNode>>>);
 
@@ -133,6 +176,10 @@ impl DryText {
     pub fn clone_node(&self) -> Self {
         Self(Rc::new(RefCell::new(self.0.borrow().clone_node())))
     }
+
+    pub fn text(&self) -> Ref<'_, str> {
+        Ref::map(self.0.borrow(), SharedDryText::text)
+    }
 }
 
 impl fmt::Display for DryText {
@@ -141,6 +188,20 @@ impl fmt::Display for DryText {
     }
 }
 
+impl ToHtml for DryText {
+    fn write_html<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        self.0.borrow().write_html(w)
+    }
+
+    fn write_xml<W: HtmlWrite>(
+        &self,
+        parent_namespace: Option<&Namespace>,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        self.0.borrow().write_xml(parent_namespace, w)
+    }
+}
+
 impl private::DomText for DryText {
     fn new(text: &str) -> Self {
         Self(Rc::new(RefCell::new(SharedDryText::new(text.to_string()))))
@@ -211,9 +272,38 @@ impl From<DryText> for DryNode {
 
 impl fmt::Display for DryNode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_html(&mut FmtWrite(f))
+    }
+}
+
+impl ToHtml for DryNode {
+    fn write_html<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        match self {
+            DryNode::Element(element) => element.write_html(w),
+            DryNode::Text(text) => text.write_html(w),
+        }
+    }
+
+    fn write_pretty_html<W: HtmlWrite>(
+        &self,
+        printer: &PrettyPrinter,
+        depth: usize,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        match self {
+            DryNode::Element(element) => element.write_pretty_html(printer, depth, w),
+            DryNode::Text(text) => text.write_pretty_html(printer, depth, w),
+        }
+    }
+
+    fn write_xml<W: HtmlWrite>(
+        &self,
+        parent_namespace: Option<&Namespace>,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
         match self {
-            DryNode::Element(element) => element.fmt(f),
-            DryNode::Text(text) => text.fmt(f),
+            DryNode::Element(element) => element.write_xml(parent_namespace, w),
+            DryNode::Text(text) => text.write_xml(parent_namespace, w),
         }
     }
 }
@@ -226,6 +316,13 @@ impl DryChild for DryNode {
         }
     }
 
+    fn is_block(&self) -> bool {
+        match self {
+            DryNode::Element(element) => element.0.borrow().is_block(),
+            DryNode::Text(_) => false,
+        }
+    }
+
     fn set_next_sibling(&self, next_sibling: Option<&Self>) {
         let next_sibling = next_sibling.cloned();
 
@@ -234,12 +331,24 @@ impl DryChild for DryNode {
             DryNode::Text(text) => text.0.borrow_mut().set_next_sibling(next_sibling),
         }
     }
+
+    fn mark_selected_option(&self, value: &str) {
+        if let DryNode::Element(element) = self {
+            element.0.borrow_mut().mark_selected_option(value);
+        }
+    }
 }
 
 pub trait DryChild: Clone {
     fn clone_node(&self) -> Self;
 
     fn set_next_sibling(&self, next_sibling: Option<&Self>);
+
+    /// Is this a block level element? See [`SharedDryElement::is_block`].
+    fn is_block(&self) -> bool;
+
+    /// See [`SharedDryElement::mark_selected_option`].
+    fn mark_selected_option(&self, value: &str);
 }
 
 pub struct SharedDryText<Node> {
@@ -281,7 +390,21 @@ impl<Node> SharedDryText<Node> {
 
 impl<Node> fmt::Display for SharedDryText<Node> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        encode_text_minimal(&self.text).fmt(f)
+        self.write_html(&mut FmtWrite(f))
+    }
+}
+
+impl<Node> ToHtml for SharedDryText<Node> {
+    fn write_html<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        write_text(&self.text, w)
+    }
+
+    fn write_xml<W: HtmlWrite>(
+        &self,
+        _parent_namespace: Option<&Namespace>,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        write_xml_text(&self.text, w)
     }
 }
 
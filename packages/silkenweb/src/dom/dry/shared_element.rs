@@ -1,7 +1,6 @@
-use std::{collections::HashMap, convert::identity, fmt};
+use std::{borrow::Cow, collections::HashMap, convert::identity, fmt};
 
 use caseless::default_caseless_match_str;
-use html_escape::encode_double_quoted_attribute;
 use indexmap::IndexMap;
 use itertools::Itertools;
 use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
@@ -14,8 +13,10 @@ use crate::{
         private::{DomElement, EventStore, InstantiableDomElement},
         wet::{WetElement, WetNode},
     },
+    event::ListenerOptions,
     hydration::HydrationStats,
     node::element::Namespace,
+    serialize::{write_attribute_value, FmtWrite, HtmlWrite, PrettyPrinter, ToHtml},
     HEAD_ID_ATTRIBUTE,
 };
 
@@ -28,6 +29,7 @@ pub struct SharedDryElement<Node> {
     shadow_children: Vec<Node>,
     hydrate_actions: Vec<LazyElementAction>,
     next_sibling: Option<Node>,
+    selected_option: Option<String>,
 }
 
 impl<Node> SharedDryElement<Node> {
@@ -45,6 +47,48 @@ impl<Node> SharedDryElement<Node> {
                 .join(" "),
         )
     }
+
+    pub fn namespace(&self) -> &Namespace {
+        &self.namespace
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    /// Get an attribute value, including the `style` attribute.
+    pub fn get_attribute(&self, name: &str) -> Option<Cow<'_, str>> {
+        if name == STYLE_ATTR {
+            if let Some(style) = self.style_prop_text() {
+                return Some(Cow::Owned(style));
+            }
+        }
+
+        self.attributes
+            .get(name)
+            .map(|value| Cow::Borrowed(value.as_str()))
+    }
+
+    /// All the attributes, including the `style` attribute.
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, Cow<'_, str>)> {
+        self.attributes
+            .iter()
+            .map(|(name, value)| (name.as_str(), Cow::Borrowed(value.as_str())))
+            .chain(
+                self.style_prop_text()
+                    .map(|style| (STYLE_ATTR, Cow::Owned(style))),
+            )
+    }
+
+    /// Is this a block level element, so whitespace around it can be changed
+    /// without affecting rendering?
+    pub fn is_block(&self) -> bool {
+        !INLINE_TAGS.contains(&self.tag.as_str())
+    }
 }
 
 impl<Node: DryChild> SharedDryElement<Node> {
@@ -58,6 +102,7 @@ impl<Node: DryChild> SharedDryElement<Node> {
             shadow_children: Vec::new(),
             hydrate_actions: Vec::new(),
             next_sibling: None,
+            selected_option: None,
         }
     }
 
@@ -78,6 +123,7 @@ impl<Node: DryChild> SharedDryElement<Node> {
             last.set_next_sibling(Some(child));
         }
 
+        self.mark_selected_child(child);
         self.children.push(child.clone());
     }
 
@@ -88,6 +134,7 @@ impl<Node: DryChild> SharedDryElement<Node> {
 
         child.set_next_sibling(next_child);
 
+        self.mark_selected_child(child);
         self.children.insert(index, child.clone());
     }
 
@@ -100,6 +147,7 @@ impl<Node: DryChild> SharedDryElement<Node> {
 
         new_child.set_next_sibling(self.children.get(index + 1));
 
+        self.mark_selected_child(new_child);
         self.children[index] = new_child.clone();
     }
 
@@ -173,13 +221,15 @@ impl<Node: DryChild> SharedDryElement<Node> {
     pub fn on(
         &mut self,
         name: &'static str,
+        options: ListenerOptions,
         f: impl FnMut(JsValue) + 'static,
         events: &EventStore,
     ) {
         clone!(mut events);
 
-        self.hydrate_actions
-            .push(Box::new(move |element| element.on(name, f, &mut events)))
+        self.hydrate_actions.push(Box::new(move |element| {
+            element.on(name, options, f, &mut events)
+        }))
     }
 
     pub fn try_dom_element(&self) -> Option<web_sys::Element> {
@@ -207,6 +257,47 @@ impl<Node: DryChild> SharedDryElement<Node> {
         }))
     }
 
+    /// Mark the `<option>` children with a `value` attribute of `value` as
+    /// `selected`, including options added later.
+    ///
+    /// This is how a `<select>`'s value is written as HTML.
+    pub fn select_option(&mut self, value: &str) {
+        for child in &self.children {
+            child.mark_selected_option(value);
+        }
+
+        self.selected_option = Some(value.to_owned());
+    }
+
+    /// Set or remove the `selected` attribute if this is an `<option>`,
+    /// depending on whether its `value` attribute is `value`.
+    ///
+    /// The options in an `<optgroup>` are marked as well.
+    pub fn mark_selected_option(&mut self, value: &str) {
+        match self.tag.as_str() {
+            "option" => {
+                if self.attributes.get("value").is_some_and(|v| v == value) {
+                    self.attributes
+                        .insert(SELECTED_ATTR.to_owned(), String::new());
+                } else {
+                    self.attributes.shift_remove(SELECTED_ATTR);
+                }
+            }
+            "optgroup" => {
+                for child in &self.children {
+                    child.mark_selected_option(value);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn mark_selected_child(&self, child: &Node) {
+        if let Some(value) = &self.selected_option {
+            child.mark_selected_option(value);
+        }
+    }
+
     pub fn clone_node(&self) -> Self {
         Self {
             namespace: self.namespace.clone(),
@@ -217,6 +308,7 @@ impl<Node: DryChild> SharedDryElement<Node> {
             shadow_children: Self::clone_children(&self.shadow_children),
             hydrate_actions: Vec::new(),
             next_sibling: None,
+            selected_option: self.selected_option.clone(),
         }
     }
 
@@ -282,6 +374,7 @@ impl SharedDryElement<HydroNode> {
         {
             self.hydrate_element(dom_elem, tracker)
         } else {
+            tracker.element_replaced(dom_elem, new_tag);
             let new_dom_elem = new_namespace.create_element(new_tag);
 
             while let Some(child) = dom_elem.first_child() {
@@ -405,9 +498,9 @@ impl SharedDryElement<HydroNode> {
             Self::set_attribute(&mut dom_attr_map, STYLE_ATTR, &style, dom_elem, tracker)
         }
 
-        for name in dom_attr_map.into_keys() {
+        for (name, value) in dom_attr_map {
             if !name.starts_with("data-silkenweb") {
-                tracker.attribute_removed(dom_elem, &name);
+                tracker.attribute_removed(dom_elem, &name, &value);
                 dom_elem.remove_attribute(&name).unwrap_throw();
             }
         }
@@ -420,79 +513,263 @@ impl SharedDryElement<HydroNode> {
         dom_elem: &web_sys::Element,
         tracker: &mut HydrationStats,
     ) {
-        let set_attr = if let Some(existing_value) = dom_attr_map.remove(name) {
-            value != existing_value
-        } else {
-            true
-        };
+        let existing_value = dom_attr_map.remove(name);
 
-        if set_attr {
+        if existing_value.as_deref() != Some(value) {
+            tracker.attribute_set(dom_elem, name, value, existing_value.as_deref());
             dom_elem.set_attribute(name, value).unwrap_throw();
-            tracker.attribute_set(dom_elem, name, value);
         }
     }
 }
 
-impl<Node: fmt::Display> SharedDryElement<Node> {
-    #[cfg(feature = "declarative-shadow-dom")]
-    fn write_shadow_dom(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.shadow_children.is_empty() {
-            return Ok(());
+impl<Node: DryChild + ToHtml> ToHtml for SharedDryElement<Node> {
+    fn write_html<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        w.write_str("<")?;
+        w.write_str(&self.tag)?;
+        self.write_attributes(w)?;
+        w.write_str(">")?;
+
+        self.write_shadow_dom(w)?;
+
+        for child in &self.children {
+            child.write_html(w)?;
         }
 
-        f.write_str(r#"<template shadowroot="open">"#)?;
+        self.write_closing_tag(w)
+    }
 
-        for child in &self.shadow_children {
-            child.fmt(f)?;
+    fn write_pretty_html<W: HtmlWrite>(
+        &self,
+        printer: &PrettyPrinter,
+        depth: usize,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        w.write_str("<")?;
+        w.write_str(&self.tag)?;
+
+        let mut attributes: Vec<_> = self.attributes().collect();
+
+        if printer.sorts_attributes() {
+            attributes.sort_by_key(|(name, _)| *name);
         }
 
-        f.write_str("</template>")?;
+        for (name, mut value) in attributes {
+            if name == CLASS_ATTR && printer.sorts_classes() {
+                value = Cow::Owned(value.split_ascii_whitespace().sorted().join(" "));
+            }
 
-        Ok(())
-    }
+            write_attr_start(w, name)?;
+            write_attribute_value(&value, w)?;
+            w.write_str("\"")?;
+        }
 
-    #[cfg(not(feature = "declarative-shadow-dom"))]
-    fn write_shadow_dom(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        Ok(())
+        w.write_str(">")?;
+
+        let is_whitespace_sensitive = WHITESPACE_SENSITIVE_TAGS.contains(&self.tag.as_str());
+
+        if is_whitespace_sensitive || !self.children.iter().all(DryChild::is_block) {
+            self.write_shadow_dom(w)?;
+
+            for child in &self.children {
+                child.write_html(w)?;
+            }
+        } else {
+            let shadow_children = self.declarative_shadow_children();
+
+            if !shadow_children.is_empty() {
+                printer.new_line(depth + 1, w)?;
+                w.write_str(SHADOW_ROOT_TEMPLATE)?;
+                write_pretty_children(shadow_children, printer, depth + 1, w)?;
+                w.write_str("</template>")?;
+            }
+
+            for child in &self.children {
+                printer.new_line(depth + 1, w)?;
+                child.write_pretty_html(printer, depth + 1, w)?;
+            }
+
+            if !self.children.is_empty() || !shadow_children.is_empty() {
+                printer.new_line(depth, w)?;
+            }
+        }
+
+        self.write_closing_tag(w)
     }
-}
 
-impl<Node: fmt::Display> fmt::Display for SharedDryElement<Node> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "<{}", self.tag)?;
+    fn write_xml<W: HtmlWrite>(
+        &self,
+        parent_namespace: Option<&Namespace>,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        w.write_str("<")?;
+        w.write_str(&self.tag)?;
 
-        for (name, value) in &self.attributes {
-            fmt_attr(f, name, value)?;
+        if parent_namespace != Some(&self.namespace) {
+            write_attr_start(w, XMLNS_ATTR)?;
+            write_attribute_value(self.namespace.as_str(), w)?;
+            w.write_str("\"")?;
         }
 
-        if let Some(style) = self.style_prop_text() {
-            fmt_attr(f, STYLE_ATTR, &style)?;
+        let uses_xlink = self
+            .attributes
+            .keys()
+            .any(|name| name.starts_with(XLINK_PREFIX));
+
+        if uses_xlink && !self.attributes.contains_key(XMLNS_XLINK_ATTR) {
+            write_attr_start(w, XMLNS_XLINK_ATTR)?;
+            w.write_str(XLINK_NAMESPACE)?;
+            w.write_str("\"")?;
         }
 
-        f.write_str(">")?;
+        self.write_attributes(w)?;
+
+        let shadow_children = self.declarative_shadow_children();
+
+        if self.children.is_empty() && shadow_children.is_empty() {
+            return w.write_str("/>");
+        }
 
-        self.write_shadow_dom(f)?;
+        w.write_str(">")?;
+
+        if !shadow_children.is_empty() {
+            w.write_str(SHADOW_ROOT_TEMPLATE)?;
+
+            for child in shadow_children {
+                child.write_xml(Some(&self.namespace), w)?;
+            }
+
+            w.write_str("</template>")?;
+        }
 
         for child in &self.children {
-            child.fmt(f)?;
+            child.write_xml(Some(&self.namespace), w)?;
+        }
+
+        w.write_str("</")?;
+        w.write_str(&self.tag)?;
+        w.write_str(">")
+    }
+}
+
+impl<Node: DryChild + ToHtml> SharedDryElement<Node> {
+    fn write_attributes<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        for (name, value) in &self.attributes {
+            write_attr_start(w, name)?;
+            write_attribute_value(value, w)?;
+            w.write_str("\"")?;
+        }
+
+        if !self.styles.is_empty() {
+            debug_assert!(!self.attributes.contains_key(STYLE_ATTR));
+            write_attr_start(w, STYLE_ATTR)?;
+
+            for (index, (name, value)) in self.styles.iter().enumerate() {
+                if index > 0 {
+                    w.write_str(" ")?;
+                }
+
+                write_attribute_value(name, w)?;
+                w.write_str(": ")?;
+                write_attribute_value(value, w)?;
+                w.write_str(";")?;
+            }
+
+            w.write_str("\"")?;
         }
 
+        Ok(())
+    }
+
+    fn write_closing_tag<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
         let has_children = !self.children.is_empty();
         let requires_closing_tag = !NO_CLOSING_TAG.contains(&self.tag.as_str());
 
         if requires_closing_tag || has_children {
-            write!(f, "</{}>", self.tag)?;
+            w.write_str("</")?;
+            w.write_str(&self.tag)?;
+            w.write_str(">")?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "declarative-shadow-dom")]
+    fn declarative_shadow_children(&self) -> &[Node] {
+        &self.shadow_children
+    }
+
+    #[cfg(not(feature = "declarative-shadow-dom"))]
+    fn declarative_shadow_children(&self) -> &[Node] {
+        &[]
+    }
+
+    #[cfg(feature = "declarative-shadow-dom")]
+    fn write_shadow_dom<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        if self.shadow_children.is_empty() {
+            return Ok(());
         }
 
+        w.write_str(SHADOW_ROOT_TEMPLATE)?;
+
+        for child in &self.shadow_children {
+            child.write_html(w)?;
+        }
+
+        w.write_str("</template>")?;
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "declarative-shadow-dom"))]
+    fn write_shadow_dom<W: HtmlWrite>(&self, _w: &mut W) -> Result<(), W::Error> {
         Ok(())
     }
 }
 
-fn fmt_attr(f: &mut fmt::Formatter, name: &str, value: &str) -> Result<(), fmt::Error> {
-    write!(f, " {}=\"{}\"", name, encode_double_quoted_attribute(value))?;
+impl<Node: DryChild + ToHtml> fmt::Display for SharedDryElement<Node> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_html(&mut FmtWrite(f))
+    }
+}
+
+/// Write each child on its own line if they're all block level, otherwise write
+/// them unchanged.
+fn write_pretty_children<Node, W>(
+    children: &[Node],
+    printer: &PrettyPrinter,
+    depth: usize,
+    w: &mut W,
+) -> Result<(), W::Error>
+where
+    Node: DryChild + ToHtml,
+    W: HtmlWrite,
+{
+    if !children.iter().all(DryChild::is_block) {
+        for child in children {
+            child.write_html(w)?;
+        }
+
+        return Ok(());
+    }
+
+    for child in children {
+        printer.new_line(depth + 1, w)?;
+        child.write_pretty_html(printer, depth + 1, w)?;
+    }
+
+    if !children.is_empty() {
+        printer.new_line(depth, w)?;
+    }
+
     Ok(())
 }
 
+fn write_attr_start<W: HtmlWrite>(w: &mut W, name: &str) -> Result<(), W::Error> {
+    w.write_str(" ")?;
+    w.write_str(name)?;
+    w.write_str("=\"")
+}
+
 impl<Node: Into<WetNode>> From<SharedDryElement<Node>> for WetElement {
     fn from(dry: SharedDryElement<Node>) -> Self {
         let mut wet = WetElement::new(&dry.namespace, &dry.tag);
@@ -522,8 +799,24 @@ impl<Node: Into<WetNode>> From<SharedDryElement<Node>> for WetElement {
 }
 
 const STYLE_ATTR: &str = "style";
+const CLASS_ATTR: &str = "class";
+const SELECTED_ATTR: &str = "selected";
+const XMLNS_ATTR: &str = "xmlns";
+const XMLNS_XLINK_ATTR: &str = "xmlns:xlink";
+const XLINK_PREFIX: &str = "xlink:";
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+const SHADOW_ROOT_TEMPLATE: &str = r#"<template shadowroot="open">"#;
 
 const NO_CLOSING_TAG: &[&str] = &[
     "area", "base", "br", "col", "embed", "hr", "img", "input", "keygen", "link", "meta", "param",
     "source", "track", "wbr",
 ];
+
+const WHITESPACE_SENSITIVE_TAGS: &[&str] = &["pre", "textarea", "script", "style"];
+
+const INLINE_TAGS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "br", "button", "cite", "code", "data", "del", "dfn", "em",
+    "i", "img", "input", "ins", "kbd", "label", "mark", "meter", "output", "progress", "q", "s",
+    "samp", "select", "small", "span", "strong", "sub", "sup", "time", "u", "var", "wbr",
+];
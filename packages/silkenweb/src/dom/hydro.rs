@@ -12,7 +12,12 @@ use super::{
     wet::{WetElement, WetNode, WetText},
     Hydro,
 };
-use crate::{hydration::HydrationStats, node::element::Namespace};
+use crate::{
+    event::ListenerOptions,
+    hydration::HydrationStats,
+    node::element::Namespace,
+    serialize::{write_xml_text, HtmlWrite, PrettyPrinter, ToHtml},
+};
 
 #[derive(Clone)]
 pub struct HydroElement(Rc<RefCell<SharedHydroElement>>);
@@ -214,12 +219,13 @@ impl DomElement for HydroElement {
     fn on(
         &mut self,
         name: &'static str,
+        options: ListenerOptions,
         f: impl FnMut(JsValue) + 'static,
         events: &mut EventStore,
     ) {
         match &mut *self.borrow_mut() {
-            SharedHydroElement::Dry(dry) => dry.on(name, f, events),
-            SharedHydroElement::Wet(wet) => wet.on(name, f, events),
+            SharedHydroElement::Dry(dry) => dry.on(name, options, f, events),
+            SharedHydroElement::Wet(wet) => wet.on(name, options, f, events),
             SharedHydroElement::Unreachable => unreachable!(),
         }
     }
@@ -264,6 +270,14 @@ impl DomElement for HydroElement {
             SharedHydroElement::Unreachable => unreachable!(),
         }
     }
+
+    fn select_option(&mut self, value: &str) {
+        match &mut *self.borrow_mut() {
+            SharedHydroElement::Dry(dry) => dry.select_option(value),
+            SharedHydroElement::Wet(wet) => wet.select_option(value),
+            SharedHydroElement::Unreachable => unreachable!(),
+        }
+    }
 }
 
 impl InstantiableDomElement for HydroElement {
@@ -368,7 +382,7 @@ impl HydroText {
 
             let dom_text = new_text.dom_text();
             parent.insert_before(dom_text, Some(child)).unwrap_throw();
-            tracker.node_added(dom_text);
+            tracker.text_added(dom_text, child);
 
             new_text
         }
@@ -480,6 +494,24 @@ impl DryChild for HydroNode {
             Self::Wet(_) => (),
         }
     }
+
+    fn is_block(&self) -> bool {
+        match self {
+            Self::Element(elem) => match &*elem.borrow() {
+                SharedHydroElement::Dry(dry) => dry.is_block(),
+                SharedHydroElement::Wet(_) | SharedHydroElement::Unreachable => false,
+            },
+            Self::Text(_) | Self::Wet(_) => false,
+        }
+    }
+
+    fn mark_selected_option(&self, value: &str) {
+        if let Self::Element(elem) = self {
+            if let SharedHydroElement::Dry(dry) = &mut *elem.borrow_mut() {
+                dry.mark_selected_option(value);
+            }
+        }
+    }
 }
 
 impl From<HydroNode> for WetNode {
@@ -502,6 +534,58 @@ impl fmt::Display for HydroNode {
     }
 }
 
+impl ToHtml for HydroNode {
+    fn write_html<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        match self {
+            Self::Text(text) => match &*text.borrow() {
+                SharedHydroText::Dry(dry) => w.write_str(dry.text()),
+                SharedHydroText::Wet(wet) => w.write_str(&wet.text()),
+                SharedHydroText::Unreachable => unreachable!(),
+            },
+            Self::Element(elem) => match &*elem.borrow() {
+                SharedHydroElement::Dry(dry) => dry.write_html(w),
+                SharedHydroElement::Wet(wet) => w.write_str(&wet.to_string()),
+                SharedHydroElement::Unreachable => Ok(()),
+            },
+            Self::Wet(wet) => w.write_str(&wet.to_string()),
+        }
+    }
+
+    fn write_pretty_html<W: HtmlWrite>(
+        &self,
+        printer: &PrettyPrinter,
+        depth: usize,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        if let Self::Element(elem) = self {
+            if let SharedHydroElement::Dry(dry) = &*elem.borrow() {
+                return dry.write_pretty_html(printer, depth, w);
+            }
+        }
+
+        self.write_html(w)
+    }
+
+    fn write_xml<W: HtmlWrite>(
+        &self,
+        parent_namespace: Option<&Namespace>,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        match self {
+            Self::Text(text) => match &*text.borrow() {
+                SharedHydroText::Dry(dry) => dry.write_xml(parent_namespace, w),
+                SharedHydroText::Wet(wet) => write_xml_text(&wet.text(), w),
+                SharedHydroText::Unreachable => unreachable!(),
+            },
+            Self::Element(elem) => match &*elem.borrow() {
+                SharedHydroElement::Dry(dry) => dry.write_xml(parent_namespace, w),
+                SharedHydroElement::Wet(_) | SharedHydroElement::Unreachable => self.write_html(w),
+            },
+            Self::Wet(_) => self.write_html(w),
+        }
+    }
+}
+
 impl InstantiableDomNode for HydroNode {
     type DomType = Hydro;
 
@@ -1,13 +1,19 @@
 use std::fmt::Display;
 
-use wasm_bindgen::JsValue;
+use wasm_bindgen::{JsValue, UnwrapThrowExt};
 
-use crate::{attribute::Attribute, node::element::Namespace};
+use crate::{attribute::Attribute, event::ListenerOptions, node::element::Namespace};
 
 pub trait Dom: 'static {
     type Element: DomElement<Node = Self::Node>;
     type Text: DomText + Into<Self::Node>;
     type Node: Clone + Display + 'static;
+
+    /// Does this DOM only live in the browser?
+    ///
+    /// State that's only held in DOM properties, like a `<textarea>`'s value,
+    /// has to be written as HTML content or attributes for other DOMs.
+    const IS_WET: bool = false;
 }
 
 pub trait InstantiableDom:
@@ -45,7 +51,13 @@ pub trait DomElement: Display + Into<Self::Node> + Clone + 'static {
     where
         A: Attribute;
 
-    fn on(&mut self, name: &'static str, f: impl FnMut(JsValue) + 'static, events: &mut EventStore);
+    fn on(
+        &mut self,
+        name: &'static str,
+        options: ListenerOptions,
+        f: impl FnMut(JsValue) + 'static,
+        events: &mut EventStore,
+    );
 
     fn dom_element(&self) -> web_sys::Element;
 
@@ -60,6 +72,12 @@ pub trait DomElement: Display + Into<Self::Node> + Clone + 'static {
         f: impl FnMut(js_sys::Array, web_sys::MutationObserver) + 'static,
         events: &mut EventStore,
     );
+
+    /// Set the `selected` attribute on the `<option>` children with a `value`
+    /// attribute of `value`, and remove it from the others.
+    ///
+    /// This only affects the HTML, so it does nothing on a live DOM element.
+    fn select_option(&mut self, value: &str);
 }
 
 pub trait DomText: Clone + 'static {
@@ -84,9 +102,31 @@ pub trait InstantiableDomNode: Display + Clone {
     fn next_sibling(&self) -> Self;
 }
 
+/// Only build an options object for non default options, as most listeners
+/// use the defaults.
+fn add_event_listener(
+    element: &web_sys::Element,
+    name: &str,
+    callback: &js_sys::Function,
+    options: &ListenerOptions,
+) {
+    if options.is_default() {
+        element.add_event_listener_with_callback(name, callback)
+    } else {
+        element.add_event_listener_with_callback_and_add_event_listener_options(
+            name,
+            callback,
+            &options.to_js(),
+        )
+    }
+    .unwrap_throw();
+}
+
 #[cfg(feature = "weak-refs")]
 mod event {
-    use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
+    use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+
+    use crate::event::ListenerOptions;
 
     #[derive(Default, Clone)]
     pub struct EventStore {}
@@ -96,14 +136,15 @@ mod event {
             &mut self,
             element: &web_sys::Element,
             name: &'static str,
+            options: &ListenerOptions,
             f: impl FnMut(JsValue) + 'static,
         ) {
-            element
-                .add_event_listener_with_callback(
-                    name,
-                    Closure::new(f).into_js_value().unchecked_ref(),
-                )
-                .unwrap_throw();
+            super::add_event_listener(
+                element,
+                name,
+                Closure::new(f).into_js_value().unchecked_ref(),
+                options,
+            );
         }
 
         pub fn add_mutation_observer(
@@ -124,6 +165,10 @@ mod event {
 
     use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
 
+    #[cfg(feature = "delegated-events")]
+    use crate::event::delegate;
+    use crate::event::ListenerOptions;
+
     #[derive(Default, Clone)]
     pub struct EventStore(Rc<RefCell<(Vec<EventCallback>, Vec<Self>)>>);
 
@@ -135,16 +180,26 @@ mod event {
             &mut self,
             element: &web_sys::Element,
             name: &'static str,
+            options: &ListenerOptions,
             f: impl FnMut(JsValue) + 'static,
         ) {
+            #[cfg(feature = "delegated-events")]
+            if delegate::is_delegated(name, options) {
+                let handler = delegate::add(element, name, f);
+                self.0
+                    .borrow_mut()
+                    .0
+                    .push(EventCallback::Delegated(handler));
+                return;
+            }
+
             let callback = Closure::new(f);
-            element
-                .add_event_listener_with_callback(name, callback.as_ref().unchecked_ref())
-                .unwrap_throw();
+            super::add_event_listener(element, name, callback.as_ref().unchecked_ref(), options);
 
             self.0.borrow_mut().0.push(EventCallback::Event {
                 element: element.clone(),
                 name,
+                capture: options.is_capture(),
                 callback,
             });
         }
@@ -189,12 +244,15 @@ mod event {
         Event {
             element: web_sys::Element,
             name: &'static str,
+            capture: bool,
             callback: Closure<dyn FnMut(JsValue)>,
         },
         MutationObserver {
             observer: web_sys::MutationObserver,
             callback: Closure<dyn FnMut(js_sys::Array, web_sys::MutationObserver)>,
         },
+        #[cfg(feature = "delegated-events")]
+        Delegated(delegate::Handler),
     }
 
     impl Drop for EventCallback {
@@ -203,17 +261,21 @@ mod event {
                 Self::Event {
                     element,
                     name,
+                    capture,
                     callback,
                 } => element
-                    .remove_event_listener_with_callback(
+                    .remove_event_listener_with_callback_and_bool(
                         name,
                         callback.as_ref().as_ref().unchecked_ref(),
+                        *capture,
                     )
                     .unwrap_throw(),
                 Self::MutationObserver {
                     observer,
                     callback: _callback,
                 } => observer.disconnect(),
+                #[cfg(feature = "delegated-events")]
+                Self::Delegated(_handler) => (),
             }
         }
     }
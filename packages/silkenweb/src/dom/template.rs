@@ -8,6 +8,7 @@ use crate::{
         private::{DomElement, DomText, InstantiableDomElement, InstantiableDomNode},
         InstantiableDom,
     },
+    event::ListenerOptions,
     node::element::{GenericElement, Namespace},
 };
 
@@ -99,10 +100,11 @@ where
     fn on(
         &mut self,
         name: &'static str,
+        options: ListenerOptions,
         f: impl FnMut(JsValue) + 'static,
         events: &mut EventStore,
     ) {
-        self.element.on(name, f, events)
+        self.element.on(name, options, f, events)
     }
 
     fn dom_element(&self) -> web_sys::Element {
@@ -128,6 +130,10 @@ where
     ) {
         self.element.observe_attributes(f, events);
     }
+
+    fn select_option(&mut self, value: &str) {
+        self.element.select_option(value)
+    }
 }
 
 impl<Param, D> fmt::Display for TemplateElement<Param, D>
@@ -9,7 +9,11 @@ use super::{
     private::{DomElement, DomText, EventStore, InstantiableDomElement, InstantiableDomNode},
     Wet,
 };
-use crate::{node::element::Namespace, task::on_animation_frame};
+use crate::{
+    event::ListenerOptions,
+    node::element::{lifecycle, Namespace},
+    task::on_animation_frame,
+};
 
 #[derive(Clone)]
 pub struct WetElement {
@@ -47,6 +51,7 @@ impl DomElement for WetElement {
 
     fn append_child(&mut self, child: &WetNode) {
         self.element.append_child(child.dom_node()).unwrap_throw();
+        lifecycle::dom_changed();
     }
 
     fn insert_child_before(
@@ -58,20 +63,24 @@ impl DomElement for WetElement {
         self.element
             .insert_before(child.dom_node(), next_child.map(|c| c.dom_node()))
             .unwrap_throw();
+        lifecycle::dom_changed();
     }
 
     fn replace_child(&mut self, _index: usize, new_child: &WetNode, old_child: &WetNode) {
         self.element
             .replace_child(new_child.dom_node(), old_child.dom_node())
             .unwrap_throw();
+        lifecycle::dom_changed();
     }
 
     fn remove_child(&mut self, _index: usize, child: &WetNode) {
         self.element.remove_child(child.dom_node()).unwrap_throw();
+        lifecycle::dom_changed();
     }
 
     fn clear_children(&mut self) {
-        self.element.set_text_content(Some(""))
+        self.element.set_text_content(Some(""));
+        lifecycle::dom_changed();
     }
 
     fn add_class(&mut self, name: &str) {
@@ -97,10 +106,11 @@ impl DomElement for WetElement {
     fn on(
         &mut self,
         name: &'static str,
+        options: ListenerOptions,
         f: impl FnMut(JsValue) + 'static,
         events: &mut EventStore,
     ) {
-        events.add_listener(&self.element, name, f);
+        events.add_listener(&self.element, name, &options, f);
     }
 
     fn dom_element(&self) -> web_sys::Element {
@@ -135,6 +145,8 @@ impl DomElement for WetElement {
     ) {
         events.add_mutation_observer(&self.element, f);
     }
+
+    fn select_option(&mut self, _value: &str) {}
 }
 
 impl InstantiableDomElement for WetElement {
@@ -144,6 +156,8 @@ impl InstantiableDomElement for WetElement {
         for child in children {
             shadow_root.append_child(child.dom_node()).unwrap_throw();
         }
+
+        lifecycle::dom_changed();
     }
 
     fn clone_node(&self) -> Self {
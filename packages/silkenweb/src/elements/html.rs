@@ -6,6 +6,8 @@
 //
 // [Moxie DOM]: https://github.com/anp/moxie
 
+mod bind;
+
 html_element!(
     /// The [HTML `<a>` element (or *anchor* element)][mdn], along with its href
     /// attribute, creates a hyperlink to other web pages, files, locations
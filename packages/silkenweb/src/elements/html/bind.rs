@@ -0,0 +1,161 @@
+//! Two way bindings between form controls and [`Mutable`]s.
+//!
+//! The value is also written as HTML when rendering on the server, so server
+//! side rendered forms are pre-filled. `<input>`s use attributes, `<textarea>`s
+//! use their initial text, and `<select>`s mark the matching `<option>` as
+//! `selected`.
+use std::{fmt::Display, str::FromStr};
+
+use futures_signals::signal::Mutable;
+
+use super::{Details, Dialog, Input, Select, Textarea};
+use crate::{
+    dom::Dom,
+    elements::HtmlElementEvents,
+    node::element::{Element, TextParentElement},
+    value::Sig,
+};
+
+impl<D: Dom> Input<D> {
+    /// Bind the `value` of the `<input>` to `value`.
+    ///
+    /// `value` is set each time the text in the `<input>` parses as a `T`.
+    /// Text that doesn't parse is left alone, so the user can carry on
+    /// editing it.
+    pub fn bind_value<T>(self, value: Mutable<T>) -> Self
+    where
+        T: FromStr + Display + PartialEq + Clone + 'static,
+    {
+        self.value(Sig(value.signal_ref(T::to_string)))
+            .map_element_signal(value.signal_cloned(), |elem, new_value| {
+                if !parses_to(&elem.value(), &new_value) {
+                    elem.set_value(&new_value.to_string());
+                }
+            })
+            .on_input(move |_, elem| set_parsed(&value, &elem.value()))
+    }
+
+    /// Bind the `checked` state of a checkbox `<input>` to `checked`.
+    ///
+    /// Radios don't fire `change` when they're unchecked by another radio in
+    /// their group, so use [`Input::bind_group`] for them.
+    pub fn bind_checked(self, checked: Mutable<bool>) -> Self {
+        self.checked(Sig(checked.signal()))
+            .map_element_signal(checked.signal(), |elem, new_checked| {
+                if elem.checked() != new_checked {
+                    elem.set_checked(new_checked);
+                }
+            })
+            .on_change(move |_, elem| checked.set_neq(elem.checked()))
+    }
+
+    /// Bind a radio `<input>` with `value` to the value selected in its group.
+    ///
+    /// The radio is checked when `group` equals `value`, and checking it sets
+    /// `group` to `value`. Bind each radio in the group to the same `group`,
+    /// with its own `value`. The radio's `value` attribute is set to `value`.
+    pub fn bind_group<T>(self, value: T, group: Mutable<T>) -> Self
+    where
+        T: Display + PartialEq + Clone + 'static,
+    {
+        let is_selected = {
+            let value = value.clone();
+            move |selected: &T| *selected == value
+        };
+
+        self.value(value.to_string())
+            .checked(Sig(group.signal_ref(is_selected.clone())))
+            .map_element_signal(group.signal_ref(is_selected), |elem, new_checked| {
+                if elem.checked() != new_checked {
+                    elem.set_checked(new_checked);
+                }
+            })
+            .on_change(move |_, elem| {
+                if elem.checked() {
+                    group.set_neq(value.clone());
+                }
+            })
+    }
+}
+
+impl<D: Dom> Textarea<D> {
+    /// Bind the `value` of the `<textarea>` to `value`.
+    ///
+    /// When rendering HTML, the initial value is written as the text of the
+    /// `<textarea>`. After that, and on a live DOM, only the `value` property
+    /// is updated. See [`Input::bind_value`] for how parsing works.
+    pub fn bind_value<T>(self, value: Mutable<T>) -> Self
+    where
+        T: FromStr + Display + PartialEq + Clone + 'static,
+    {
+        // Hydration needs the same initial text as the server rendered HTML.
+        let elem = if D::IS_WET {
+            self
+        } else {
+            self.text(value.get_cloned().to_string())
+        };
+
+        elem.map_element_signal(value.signal_cloned(), |elem, new_value| {
+            if !parses_to(&elem.value(), &new_value) {
+                elem.set_value(&new_value.to_string());
+            }
+        })
+        .on_input(move |_, elem| set_parsed(&value, &elem.value()))
+    }
+}
+
+impl<D: Dom> Select<D> {
+    /// Bind the value of the selected `<option>` to `selected`.
+    ///
+    /// When rendering HTML, the `<option>` with a `value` attribute matching
+    /// `selected` is marked as `selected`. Options without a `value` attribute
+    /// are never marked.
+    pub fn bind_selected<T>(self, selected: Mutable<T>) -> Self
+    where
+        T: FromStr + Display + PartialEq + Clone + 'static,
+    {
+        let elem = if D::IS_WET {
+            self
+        } else {
+            Self(
+                self.0
+                    .select_option_signal(selected.signal_ref(T::to_string)),
+            )
+        };
+
+        elem.map_element_signal(selected.signal_cloned(), |elem, new_value| {
+            if !parses_to(&elem.value(), &new_value) {
+                elem.set_value(&new_value.to_string());
+            }
+        })
+        .on_change(move |_, elem| set_parsed(&selected, &elem.value()))
+    }
+}
+
+impl<D: Dom> Details<D> {
+    /// Bind the `open` state of the `<details>` to `open`.
+    pub fn bind_open(self, open: Mutable<bool>) -> Self {
+        self.open(Sig(open.signal()))
+            .on_toggle(move |_, elem| open.set_neq(elem.open()))
+    }
+}
+
+impl<D: Dom> Dialog<D> {
+    /// Bind the `open` state of the `<dialog>` to `open`.
+    ///
+    /// Setting `open` to `true` shows the dialog non-modally.
+    pub fn bind_open(self, open: Mutable<bool>) -> Self {
+        self.open(Sig(open.signal()))
+            .on_close(move |_, _| open.set_neq(false))
+    }
+}
+
+fn parses_to<T: FromStr + PartialEq>(text: &str, value: &T) -> bool {
+    text.parse::<T>().is_ok_and(|parsed| parsed == *value)
+}
+
+fn set_parsed<T: FromStr + PartialEq>(value: &Mutable<T>, text: &str) {
+    if let Ok(new_value) = text.parse() {
+        value.set_neq(new_value);
+    }
+}
@@ -1,4 +1,203 @@
+//! Event listener options and typed custom events.
+//!
+//! By default, event handlers are added as bubbling, non-passive listeners
+//! that stay registered until the element is dropped.
+//! [`Element::on_with_options`] and the `on_<event>_with_options` methods
+//! take [`ListenerOptions`] to change this.
+//!
+//! # Custom Events
+//!
+//! Components can emit their own events by implementing [`TypedEvent`] for the
+//! event detail. Send them with [`ElementHandle::dispatch`], and listen for
+//! them with [`Element::on_custom`]. The detail is converted to a Javascript
+//! value with [`serde_wasm_bindgen`], so it can be read by any listener,
+//! including listeners outside a [`Component`]'s shadow root.
+//!
+//! ```no_run
+#![doc = function_body!("tests/doc/event.rs", typed_event, [])]
+//! ```
+//! 
+//! [`Element::on_with_options`]: crate::node::element::Element::on_with_options
+//! [`Element::on_custom`]: crate::node::element::Element::on_custom
+//! [`ElementHandle::dispatch`]: crate::node::element::ElementHandle::dispatch
+//! [`Component`]: crate::node::Component
+use std::ops::Deref;
+
+use include_doc::function_body;
+use serde::{de::DeserializeOwned, Serialize};
 use silkenweb_macros::cfg_browser;
+use wasm_bindgen::UnwrapThrowExt;
+
+/// A custom event, with `Self` as the detail.
+pub trait TypedEvent: Serialize + DeserializeOwned + 'static {
+    /// The event name.
+    const NAME: &'static str;
+}
+
+/// A received [`TypedEvent`].
+pub struct TypedCustomEvent<E> {
+    event: web_sys::CustomEvent,
+    detail: E,
+}
+
+impl<E: TypedEvent> TypedCustomEvent<E> {
+    /// Convert from a [`web_sys::CustomEvent`].
+    ///
+    /// Returns [`None`] if the detail doesn't deserialize as an `E`.
+    pub fn from_event(event: web_sys::CustomEvent) -> Option<Self> {
+        let detail = serde_wasm_bindgen::from_value(event.detail()).ok()?;
+        Some(Self { event, detail })
+    }
+
+    /// The original event.
+    pub fn event(&self) -> &web_sys::CustomEvent {
+        &self.event
+    }
+
+    /// The event detail.
+    pub fn detail(&self) -> &E {
+        &self.detail
+    }
+
+    /// Take the event detail.
+    pub fn into_detail(self) -> E {
+        self.detail
+    }
+}
+
+impl<E> Deref for TypedCustomEvent<E> {
+    type Target = web_sys::CustomEvent;
+
+    fn deref(&self) -> &Self::Target {
+        &self.event
+    }
+}
+
+/// Options for dispatching a [`TypedEvent`].
+///
+/// By default, events bubble, but don't cross shadow root boundaries and
+/// aren't cancelable.
+#[derive(Clone, Debug)]
+pub struct DispatchOptions {
+    bubbles: bool,
+    composed: bool,
+    cancelable: bool,
+}
+
+impl Default for DispatchOptions {
+    fn default() -> Self {
+        Self {
+            bubbles: true,
+            composed: false,
+            cancelable: false,
+        }
+    }
+}
+
+impl DispatchOptions {
+    /// Default options: a bubbling, non-composed, non-cancelable event.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Should the event bubble up through ancestors?
+    pub fn bubbles(mut self, bubbles: bool) -> Self {
+        self.bubbles = bubbles;
+        self
+    }
+
+    /// Should the event propagate out of shadow roots?
+    pub fn composed(mut self, composed: bool) -> Self {
+        self.composed = composed;
+        self
+    }
+
+    /// Can listeners call `prevent_default`?
+    pub fn cancelable(mut self, cancelable: bool) -> Self {
+        self.cancelable = cancelable;
+        self
+    }
+
+    pub(crate) fn new_event<E: TypedEvent>(&self, detail: &E) -> web_sys::CustomEvent {
+        let init = web_sys::CustomEventInit::new();
+        init.set_bubbles(self.bubbles);
+        init.set_composed(self.composed);
+        init.set_cancelable(self.cancelable);
+        init.set_detail(
+            &detail
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .expect_throw("Custom event detail should serialize"),
+        );
+
+        web_sys::CustomEvent::new_with_event_init_dict(E::NAME, &init).unwrap_throw()
+    }
+}
+
+/// Options for adding an event listener.
+///
+/// See [MDN addEventListener] for details of each option.
+///
+/// [MDN addEventListener]: https://developer.mozilla.org/en-US/docs/Web/API/EventTarget/addEventListener#options
+#[derive(Clone, Default, Debug)]
+pub struct ListenerOptions {
+    passive: bool,
+    capture: bool,
+    once: bool,
+    signal: Option<web_sys::AbortSignal>,
+}
+
+impl ListenerOptions {
+    /// Default options: a non-passive, bubbling listener.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A passive listener promises not to call `prevent_default`, so the
+    /// browser can scroll without waiting for it.
+    pub fn passive(mut self, passive: bool) -> Self {
+        self.passive = passive;
+        self
+    }
+
+    /// Listen during the capture phase, before descendants see the event.
+    pub fn capture(mut self, capture: bool) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Remove the listener after the first event.
+    pub fn once(mut self, once: bool) -> Self {
+        self.once = once;
+        self
+    }
+
+    /// Remove the listener when `signal` is aborted.
+    pub fn signal(mut self, signal: web_sys::AbortSignal) -> Self {
+        self.signal = Some(signal);
+        self
+    }
+
+    pub(crate) fn is_capture(&self) -> bool {
+        self.capture
+    }
+
+    pub(crate) fn is_default(&self) -> bool {
+        !self.passive && !self.capture && !self.once && self.signal.is_none()
+    }
+
+    pub(crate) fn to_js(&self) -> web_sys::AddEventListenerOptions {
+        let options = web_sys::AddEventListenerOptions::new();
+        options.set_passive(self.passive);
+        options.set_capture(self.capture);
+        options.set_once(self.once);
+
+        if let Some(signal) = &self.signal {
+            options.set_signal(signal);
+        }
+
+        options
+    }
+}
 
 // Events that might bubble.
 //
@@ -48,6 +247,217 @@ macro_rules! bubbling_events {
 
 pub(crate) use bubbling_events;
 
+/// Delegate bubbling events to a single listener per event type.
+///
+/// Each element with a delegated handler is given an id, stored as a property
+/// on the DOM element. The root listener walks the event's composed path and
+/// calls the handlers for each element, stopping if propagation is stopped.
+#[cfg(feature = "delegated-events")]
+pub(crate) mod delegate {
+    use std::{
+        cell::RefCell,
+        collections::{HashMap, HashSet},
+        rc::Rc,
+    };
+
+    use silkenweb_base::document;
+    use wasm_bindgen::{intern, prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
+
+    use super::ListenerOptions;
+
+    /// Should a listener for `name` with `options` be delegated?
+    pub fn is_delegated(name: &str, options: &ListenerOptions) -> bool {
+        options.is_default() && is_bubbling(name)
+    }
+
+    /// Add a delegated handler.
+    ///
+    /// The handler is removed when the returned [`Handler`] is dropped.
+    pub fn add(
+        element: &web_sys::Element,
+        name: &'static str,
+        f: impl FnMut(JsValue) + 'static,
+    ) -> Handler {
+        DELEGATED.with(|delegated| {
+            let mut delegated = delegated.borrow_mut();
+            let element_id = delegated.element_id(element);
+            let id = delegated.next_handler_id;
+            delegated.next_handler_id += 1;
+
+            if delegated.roots.insert(name) {
+                listen_on_root(name);
+            }
+
+            delegated
+                .handlers
+                .entry((element_id, name))
+                .or_default()
+                .push((id, Rc::new(RefCell::new(f))));
+
+            Handler {
+                key: (element_id, name),
+                id,
+            }
+        })
+    }
+
+    /// The element whose delegated handler is currently processing `event`.
+    pub fn current_target(event: &JsValue) -> Option<web_sys::Element> {
+        CURRENT_TARGET.with(|current| {
+            current
+                .borrow()
+                .as_ref()
+                .filter(|(current_event, _)| current_event == event)
+                .map(|(_, element)| element.clone())
+        })
+    }
+
+    pub struct Handler {
+        key: HandlerKey,
+        id: u64,
+    }
+
+    impl Drop for Handler {
+        fn drop(&mut self) {
+            let removed = DELEGATED.with(|delegated| {
+                let mut delegated = delegated.borrow_mut();
+                let handlers = delegated.handlers.get_mut(&self.key)?;
+                let index = handlers.iter().position(|(id, _)| *id == self.id)?;
+                let (_, removed) = handlers.remove(index);
+
+                if handlers.is_empty() {
+                    delegated.handlers.remove(&self.key);
+                }
+
+                Some(removed)
+            });
+
+            // The callback might own other handlers, so drop it after we've released the
+            // borrow.
+            drop(removed);
+        }
+    }
+
+    fn is_bubbling(name: &str) -> bool {
+        macro_rules! events {
+            ($($event:ident: $typ:ty),* $(,)?) => {
+                [$(stringify!($event)),*].contains(&name)
+            };
+        }
+
+        bubbling_events!()
+    }
+
+    fn listen_on_root(name: &'static str) {
+        let root = document::document_element().expect_throw("Document must have a root element");
+        // Browsers make some listeners on the document or body passive by default, so
+        // we listen on the root element, and explicitly make it non-passive.
+        let options = ListenerOptions::new().to_js();
+        options.set_passive(false);
+
+        root.add_event_listener_with_callback_and_add_event_listener_options(
+            name,
+            Closure::<dyn FnMut(web_sys::Event)>::new(move |event| dispatch(name, &event, None))
+                .into_js_value()
+                .unchecked_ref(),
+            &options,
+        )
+        .unwrap_throw();
+    }
+
+    /// Call the delegated handlers for `event`, from its target up to and
+    /// including `root`.
+    ///
+    /// This is for events that don't propagate beyond `root`, so the root
+    /// listener won't see them.
+    pub fn dispatch_within(name: &'static str, event: &web_sys::Event, root: &web_sys::Element) {
+        dispatch(name, event, Some(root))
+    }
+
+    fn dispatch(name: &'static str, event: &web_sys::Event, root: Option<&web_sys::Element>) {
+        for target in event.composed_path().iter() {
+            if event.cancel_bubble() {
+                break;
+            }
+
+            let Some(element) = target.dyn_ref::<web_sys::Element>() else {
+                continue;
+            };
+
+            call_handlers(name, event, element);
+
+            if Some(element) == root {
+                break;
+            }
+        }
+    }
+
+    fn call_handlers(name: &'static str, event: &web_sys::Event, element: &web_sys::Element) {
+        let handlers: Vec<Callback> = DELEGATED.with(|delegated| {
+            let delegated = delegated.borrow();
+
+            Delegated::existing_element_id(element)
+                .and_then(|element_id| delegated.handlers.get(&(element_id, name)))
+                .map(|handlers| handlers.iter().map(|(_, f)| f.clone()).collect())
+                .unwrap_or_default()
+        });
+
+        if handlers.is_empty() {
+            return;
+        }
+
+        let previous = CURRENT_TARGET
+            .with(|current| current.replace(Some((event.clone().into(), element.clone()))));
+
+        for f in handlers {
+            f.borrow_mut()(event.clone().into());
+        }
+
+        CURRENT_TARGET.with(|current| current.replace(previous));
+    }
+
+    type HandlerKey = (u32, &'static str);
+
+    type Callback = Rc<RefCell<dyn FnMut(JsValue)>>;
+
+    #[derive(Default)]
+    struct Delegated {
+        roots: HashSet<&'static str>,
+        handlers: HashMap<HandlerKey, Vec<(u64, Callback)>>,
+        next_element_id: u32,
+        next_handler_id: u64,
+    }
+
+    impl Delegated {
+        fn element_id(&mut self, element: &web_sys::Element) -> u32 {
+            Self::existing_element_id(element).unwrap_or_else(|| {
+                let id = self.next_element_id;
+                self.next_element_id += 1;
+                js_sys::Reflect::set(element.as_ref(), &Self::id_property(), &id.into())
+                    .unwrap_throw();
+                id
+            })
+        }
+
+        fn existing_element_id(element: &web_sys::Element) -> Option<u32> {
+            js_sys::Reflect::get(element.as_ref(), &Self::id_property())
+                .ok()?
+                .as_f64()
+                .map(|id| id as u32)
+        }
+
+        fn id_property() -> JsValue {
+            intern("__silkenwebDelegatedId").into()
+        }
+    }
+
+    thread_local! {
+        static DELEGATED: RefCell<Delegated> = RefCell::default();
+        static CURRENT_TARGET: RefCell<Option<(JsValue, web_sys::Element)>> =
+            const { RefCell::new(None) };
+    }
+}
+
 #[cfg_browser(false)]
 mod arch {
     use std::marker::PhantomData;
@@ -128,4 +538,4 @@ mod arch {
     }
 }
 
-pub use arch::GlobalEventCallback;
+pub(crate) use arch::GlobalEventCallback;
@@ -0,0 +1,565 @@
+//! Form validation.
+//!
+//! A [`Field`] holds the value of a form control, along with its validators and
+//! state: whether it's dirty, touched, valid, or still validating. Fields can
+//! be bound to `<input>`, `<textarea>` and `<select>` elements, which will:
+//!
+//! - Set the element's `id` to the field's `id`.
+//! - Keep the element and the field's value in sync.
+//! - Mark the field as touched when the element loses focus.
+//! - Set `aria-invalid` and `aria-describedby` when a touched field has an
+//!   error. `aria-describedby` refers to [`Field::error_id`], which should be
+//!   the `id` of the element displaying [`Field::visible_error_signal`].
+//! - Call `setCustomValidity` on the element, so the browser's constraint
+//!   validation API sees the same errors.
+//!
+//! A [`FormState`] groups fields, and handles form submission.
+//!
+//! [`Form::values_as`] and [`Form::on_submit_values`] deserialize the
+//! contents of a `<form>` into a typed struct, using the same encoding as the
+//! browser uses to submit the form.
+//!
+//! # Example
+//!
+//! ```no_run
+#![doc = function_body!("tests/doc/form.rs", module_example, [])]
+//! ```
+use std::{cell::RefCell, fmt::Display, future::Future, pin::Pin, rc::Rc, str::FromStr};
+
+use discard::DiscardOnDrop;
+use futures::{future::LocalBoxFuture, FutureExt};
+use futures_signals::{
+    map_ref,
+    signal::{Mutable, Signal, SignalExt},
+    signal_vec::{MutableVec, SignalVecExt},
+    CancelableFutureHandle,
+};
+use include_doc::function_body;
+use wasm_bindgen::JsCast;
+
+use crate::{
+    dom::Dom,
+    elements::{
+        html::{Form, Input, Select, Textarea},
+        AriaElement, ElementEvents, HtmlElement,
+    },
+    node::element::{spawn_cancelable_future, Element},
+    value::Sig,
+};
+
+mod values;
+
+pub use values::{from_entries, FormValuesError};
+
+/// The value of a form control, along with its validation state.
+///
+/// Cloning a `Field` gives another reference to the same field.
+pub struct Field<T> {
+    id: Rc<str>,
+    initial: Rc<T>,
+    value: Mutable<T>,
+    validators: Rc<Validators<T>>,
+    state: FieldState,
+    _validation: Rc<DiscardOnDrop<CancelableFutureHandle>>,
+}
+
+impl<T> Clone for Field<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            initial: self.initial.clone(),
+            value: self.value.clone(),
+            validators: self.validators.clone(),
+            state: self.state.clone(),
+            _validation: self._validation.clone(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Field<T> {
+    /// Create a new field.
+    ///
+    /// `id` should be unique within the document, as it's used to derive
+    /// [`Self::error_id`].
+    pub fn new(id: impl Into<String>, initial: T) -> Self {
+        let initial = Rc::new(initial);
+        let value = Mutable::new(initial.as_ref().clone());
+        let validators = Rc::new(Validators::default());
+        let state = FieldState::default();
+
+        let validation = value
+            .signal_cloned()
+            .map_future({
+                let initial = initial.clone();
+                let validators = validators.clone();
+                let state = state.clone();
+
+                move |value: T| {
+                    state.dirty.set_neq(value != *initial);
+                    let validation = validators.validate(value);
+                    state.validating.set_neq(matches!(validation, Ok(Some(_))));
+
+                    async move {
+                        match validation {
+                            Ok(Some(pending)) => pending.await,
+                            Ok(None) => Ok(()),
+                            Err(error) => Err(error),
+                        }
+                    }
+                }
+            })
+            .for_each({
+                let state = state.clone();
+
+                move |result| {
+                    if let Some(result) = result {
+                        state.error.set_neq(result.err());
+                        state.validating.set_neq(false);
+                    }
+
+                    async {}
+                }
+            });
+
+        Self {
+            id: id.into().into(),
+            initial,
+            value,
+            validators,
+            state,
+            _validation: Rc::new(spawn_cancelable_future(validation)),
+        }
+    }
+
+    /// Add a synchronous validator.
+    ///
+    /// Validators are run in the order they're added, and the first error is
+    /// reported.
+    pub fn validator(self, f: impl Fn(&T) -> Result<(), String> + 'static) -> Self {
+        self.validators.sync.borrow_mut().push(Box::new(f));
+        self
+    }
+
+    /// Add an asynchronous validator.
+    ///
+    /// Asynchronous validators are only run once all the synchronous validators
+    /// have passed. Any pending validation is cancelled when the value
+    /// changes.
+    pub fn async_validator<Fut>(self, f: impl Fn(T) -> Fut + 'static) -> Self
+    where
+        Fut: Future<Output = Result<(), String>> + 'static,
+    {
+        self.validators
+            .asynchronous
+            .borrow_mut()
+            .push(Rc::new(move |value| f(value).boxed_local()));
+        self
+    }
+
+    /// Reset the field to its initial value, and mark it as untouched.
+    pub fn reset(&self) {
+        self.value.set_neq(self.initial.as_ref().clone());
+        self.state.touched.set_neq(false);
+    }
+}
+
+impl<T: 'static> Field<T> {
+    /// The field's `id`
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The `id` of the element that displays the field's error.
+    pub fn error_id(&self) -> String {
+        format!("{}-error", self.id)
+    }
+
+    /// The field's value.
+    pub fn value(&self) -> &Mutable<T> {
+        &self.value
+    }
+
+    /// Mark the field as touched.
+    ///
+    /// Fields are touched when their element loses focus, or the form is
+    /// submitted.
+    pub fn touch(&self) {
+        self.state.touched.set_neq(true);
+    }
+
+    /// Is the value different from the initial value?
+    pub fn dirty_signal(&self) -> impl Signal<Item = bool> {
+        self.state.dirty.signal()
+    }
+
+    /// Has the user interacted with the field?
+    pub fn touched_signal(&self) -> impl Signal<Item = bool> {
+        self.state.touched.signal()
+    }
+
+    /// Are there any asynchronous validators still running?
+    pub fn validating_signal(&self) -> impl Signal<Item = bool> {
+        self.state.validating.signal()
+    }
+
+    /// The current error, if any.
+    pub fn error_signal(&self) -> impl Signal<Item = Option<String>> {
+        self.state.error.signal_cloned()
+    }
+
+    /// The current error, once the field has been touched.
+    ///
+    /// This is usually what should be displayed to the user.
+    pub fn visible_error_signal(&self) -> impl Signal<Item = Option<String>> {
+        let touched = self.state.touched.signal();
+        let error = self.state.error.signal_cloned();
+
+        map_ref!(touched, error => touched.then(|| error.clone()).flatten())
+    }
+
+    /// Is the field valid?
+    ///
+    /// Fields aren't valid while they're validating.
+    pub fn valid_signal(&self) -> impl Signal<Item = bool> {
+        self.state.valid_signal()
+    }
+
+    /// Is the field currently valid?
+    pub fn is_valid(&self) -> bool {
+        self.state.is_valid()
+    }
+
+    /// Are there any asynchronous validators currently running?
+    pub fn is_validating(&self) -> bool {
+        self.state.validating.get()
+    }
+
+    fn bind_state<E>(&self, elem: E) -> E
+    where
+        E: AriaElement + ElementEvents,
+        E::DomElement: ConstraintValidation,
+    {
+        let error_id = self.error_id();
+
+        elem.aria_invalid(Sig(self
+            .visible_error_signal()
+            .map(|error| error.map(|_| "true"))))
+            .aria_describedby(Sig(self
+                .visible_error_signal()
+                .map(move |error| error.map(|_| error_id.clone()))))
+            .map_element_signal(self.error_signal(), |elem, error| {
+                elem.set_custom_validity(error.as_deref().unwrap_or_default())
+            })
+            .on_blur({
+                let touched = self.state.touched.clone();
+                move |_, _| touched.set_neq(true)
+            })
+            .on("invalid", {
+                let touched = self.state.touched.clone();
+                move |_| touched.set_neq(true)
+            })
+    }
+}
+
+impl<T> Field<T>
+where
+    T: FromStr + Display + PartialEq + Clone + 'static,
+{
+    /// Bind the field to an `<input>`.
+    ///
+    /// See [`Input::bind_value`].
+    pub fn input<D: Dom>(&self, input: Input<D>) -> Input<D> {
+        self.bind_state(input.id(self.id.to_string()))
+            .bind_value(self.value.clone())
+    }
+
+    /// Bind the field to a `<textarea>`.
+    ///
+    /// See [`Textarea::bind_value`].
+    pub fn textarea<D: Dom>(&self, textarea: Textarea<D>) -> Textarea<D> {
+        self.bind_state(textarea.id(self.id.to_string()))
+            .bind_value(self.value.clone())
+    }
+
+    /// Bind the field to a `<select>`.
+    ///
+    /// See [`Select::bind_selected`].
+    pub fn select<D: Dom>(&self, select: Select<D>) -> Select<D> {
+        self.bind_state(select.id(self.id.to_string()))
+            .bind_selected(self.value.clone())
+    }
+}
+
+impl Field<bool> {
+    /// Bind the field to a checkbox `<input>`.
+    ///
+    /// See [`Input::bind_checked`].
+    pub fn checkbox<D: Dom>(&self, input: Input<D>) -> Input<D> {
+        self.bind_state(input.id(self.id.to_string()))
+            .bind_checked(self.value.clone())
+    }
+}
+
+/// A group of fields that make up a form.
+///
+/// Cloning a `FormState` gives another reference to the same form.
+#[derive(Clone, Default)]
+pub struct FormState {
+    fields: MutableVec<Rc<dyn AnyField>>,
+}
+
+impl FormState {
+    /// Create a form with no fields.
+    ///
+    /// Add fields with [`Self::field`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a field to the form.
+    pub fn field<T: Clone + PartialEq + 'static>(self, field: &Field<T>) -> Self {
+        self.fields.lock_mut().push_cloned(Rc::new(field.clone()));
+        self
+    }
+
+    /// Are all the fields valid?
+    pub fn valid_signal(&self) -> impl Signal<Item = bool> {
+        self.fields
+            .signal_vec_cloned()
+            .map_signal(|field| field.valid_signal())
+            .to_signal_map(|valid| valid.iter().all(|valid| *valid))
+    }
+
+    /// Are any of the fields dirty?
+    pub fn dirty_signal(&self) -> impl Signal<Item = bool> {
+        self.fields
+            .signal_vec_cloned()
+            .map_signal(|field| field.dirty_signal())
+            .to_signal_map(|dirty| dirty.iter().any(|dirty| *dirty))
+    }
+
+    /// Are any of the fields validating?
+    pub fn validating_signal(&self) -> impl Signal<Item = bool> {
+        self.fields
+            .signal_vec_cloned()
+            .map_signal(|field| field.validating_signal())
+            .to_signal_map(|validating| validating.iter().any(|validating| *validating))
+    }
+
+    /// Are all the fields currently valid?
+    pub fn is_valid(&self) -> bool {
+        self.fields.lock_ref().iter().all(|field| field.is_valid())
+    }
+
+    /// Are any of the fields currently validating?
+    pub fn is_validating(&self) -> bool {
+        self.fields
+            .lock_ref()
+            .iter()
+            .any(|field| field.is_validating())
+    }
+
+    /// Mark all the fields as touched.
+    pub fn touch_all(&self) {
+        for field in self.fields.lock_ref().iter() {
+            field.touch();
+        }
+    }
+
+    /// Reset all the fields.
+    pub fn reset(&self) {
+        for field in self.fields.lock_ref().iter() {
+            field.reset();
+        }
+    }
+
+    /// Handle submission of `form`.
+    ///
+    /// The default action of the submit event is prevented and all fields are
+    /// marked as touched. `f` is only called if all fields are valid.
+    ///
+    /// If any asynchronous validation is still running, `f` is called once
+    /// it's finished, if all the fields are valid by then. Only the latest
+    /// submission is kept while waiting.
+    pub fn on_submit<D: Dom>(
+        &self,
+        form: Form<D>,
+        f: impl FnMut(web_sys::Event) + 'static,
+    ) -> Form<D> {
+        let f = Rc::new(RefCell::new(f));
+        let pending: Mutable<Option<web_sys::Event>> = Mutable::new(None);
+        let ready = map_ref! {
+            let submitted = pending.signal_ref(Option::is_some),
+            let validating = self.validating_signal() => *submitted && !*validating
+        };
+
+        form.spawn_future(ready.for_each({
+            let state = self.clone();
+            let f = f.clone();
+            let pending = pending.clone();
+
+            move |ready| {
+                if ready {
+                    if let Some(event) = pending.replace(None) {
+                        if state.is_valid() {
+                            f.borrow_mut()(event);
+                        }
+                    }
+                }
+
+                async {}
+            }
+        }))
+        .on_submit({
+            let state = self.clone();
+
+            move |event, _| {
+                event.prevent_default();
+                state.touch_all();
+
+                if state.is_validating() {
+                    pending.set(Some(event));
+                } else if state.is_valid() {
+                    f.borrow_mut()(event);
+                }
+            }
+        })
+    }
+}
+
+#[derive(Clone, Default)]
+struct FieldState {
+    dirty: Mutable<bool>,
+    touched: Mutable<bool>,
+    validating: Mutable<bool>,
+    error: Mutable<Option<String>>,
+}
+
+impl FieldState {
+    fn valid_signal(&self) -> impl Signal<Item = bool> {
+        let validating = self.validating.signal();
+        let error = self.error.signal_ref(Option::is_none);
+
+        map_ref!(validating, error => !validating && *error)
+    }
+
+    fn is_valid(&self) -> bool {
+        !self.validating.get() && self.error.lock_ref().is_none()
+    }
+}
+
+type SyncValidator<T> = Box<dyn Fn(&T) -> Result<(), String>>;
+
+type AsyncValidator<T> = Rc<dyn Fn(T) -> LocalBoxFuture<'static, Result<(), String>>>;
+
+struct Validators<T> {
+    sync: RefCell<Vec<SyncValidator<T>>>,
+    asynchronous: RefCell<Vec<AsyncValidator<T>>>,
+}
+
+impl<T> Default for Validators<T> {
+    fn default() -> Self {
+        Self {
+            sync: RefCell::default(),
+            asynchronous: RefCell::default(),
+        }
+    }
+}
+
+impl<T: Clone + 'static> Validators<T> {
+    /// Run the synchronous validators, and return the pending asynchronous
+    /// validation if there is any.
+    fn validate(
+        &self,
+        value: T,
+    ) -> Result<Option<LocalBoxFuture<'static, Result<(), String>>>, String> {
+        for validator in self.sync.borrow().iter() {
+            validator(&value)?;
+        }
+
+        let asynchronous = self.asynchronous.borrow().clone();
+
+        if asynchronous.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            async move {
+                for validator in asynchronous {
+                    validator(value.clone()).await?;
+                }
+
+                Ok(())
+            }
+            .boxed_local(),
+        ))
+    }
+}
+
+/// Type erased access to a [`Field`], for [`FormState`].
+trait AnyField {
+    fn valid_signal(&self) -> Pin<Box<dyn Signal<Item = bool>>>;
+
+    fn dirty_signal(&self) -> Pin<Box<dyn Signal<Item = bool>>>;
+
+    fn validating_signal(&self) -> Pin<Box<dyn Signal<Item = bool>>>;
+
+    fn is_valid(&self) -> bool;
+
+    fn is_validating(&self) -> bool;
+
+    fn touch(&self);
+
+    fn reset(&self);
+}
+
+impl<T: Clone + PartialEq + 'static> AnyField for Field<T> {
+    fn valid_signal(&self) -> Pin<Box<dyn Signal<Item = bool>>> {
+        Box::pin(self.valid_signal())
+    }
+
+    fn dirty_signal(&self) -> Pin<Box<dyn Signal<Item = bool>>> {
+        Box::pin(self.dirty_signal())
+    }
+
+    fn validating_signal(&self) -> Pin<Box<dyn Signal<Item = bool>>> {
+        Box::pin(self.validating_signal())
+    }
+
+    fn is_valid(&self) -> bool {
+        self.is_valid()
+    }
+
+    fn is_validating(&self) -> bool {
+        self.is_validating()
+    }
+
+    fn touch(&self) {
+        self.touch()
+    }
+
+    fn reset(&self) {
+        self.reset()
+    }
+}
+
+/// Elements that support `setCustomValidity`.
+trait ConstraintValidation: JsCast {
+    fn set_custom_validity(&self, error: &str);
+}
+
+macro_rules! constraint_validation {
+    ($($typ:ty),*) => {$(
+        impl ConstraintValidation for $typ {
+            fn set_custom_validity(&self, error: &str) {
+                <$typ>::set_custom_validity(self, error)
+            }
+        }
+    )*}
+}
+
+constraint_validation!(
+    web_sys::HtmlInputElement,
+    web_sys::HtmlTextAreaElement,
+    web_sys::HtmlSelectElement
+);
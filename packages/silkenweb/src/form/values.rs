@@ -0,0 +1,514 @@
+use std::{error, fmt, str::FromStr};
+
+use indexmap::IndexMap;
+use serde::{
+    de::{
+        self, value::StringDeserializer, DeserializeOwned, DeserializeSeed, IntoDeserializer,
+        MapAccess, SeqAccess, Visitor,
+    },
+    forward_to_deserialize_any,
+};
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+
+use crate::{dom::Dom, elements::html::Form, node::element::Element};
+
+/// The name/value pairs the browser would submit for `form`.
+fn entries(form: &web_sys::HtmlFormElement) -> Vec<(String, String)> {
+    let form_data = web_sys::FormData::new_with_form(form).unwrap_throw();
+    let entries = js_sys::try_iter(&form_data)
+        .unwrap_throw()
+        .expect_throw("`FormData` should be iterable");
+
+    entries
+        .filter_map(|entry| {
+            let entry: js_sys::Array = entry.unwrap_throw().unchecked_into();
+            Some((entry.get(0).as_string()?, entry.get(1).as_string()?))
+        })
+        .collect()
+}
+
+impl<D: Dom> Form<D> {
+    /// The name/value pairs the browser would submit for this form.
+    ///
+    /// Controls without a name, disabled controls, unchecked checkboxes and
+    /// radio buttons, and buttons are excluded. Multi-selects produce an
+    /// entry for each selected option. File inputs are skipped.
+    ///
+    /// # Panics
+    ///
+    /// If the form isn't a live DOM element. See
+    /// [`ElementHandle::dom_element`].
+    ///
+    /// [`ElementHandle::dom_element`]: crate::node::element::ElementHandle::dom_element
+    pub fn entries(&self) -> Vec<(String, String)> {
+        entries(&self.handle().dom_element())
+    }
+
+    /// Deserialize the form's entries into a `T`.
+    ///
+    /// See [`from_entries`] for how entries are mapped onto `T`, and
+    /// [`Self::entries`] for which controls are included.
+    ///
+    /// # Panics
+    ///
+    /// If the form isn't a live DOM element. See
+    /// [`ElementHandle::dom_element`].
+    ///
+    /// [`ElementHandle::dom_element`]: crate::node::element::ElementHandle::dom_element
+    pub fn values_as<T: DeserializeOwned>(&self) -> Result<T, FormValuesError> {
+        from_entries(self.entries())
+    }
+
+    /// Handle the `submit` event with the form's values.
+    ///
+    /// The default action is prevented, so the browser won't submit the form
+    /// itself. If the form was submitted with a named button, the button's
+    /// name and value are included, as they would be by the browser.
+    pub fn on_submit_values<T: DeserializeOwned>(
+        self,
+        mut f: impl FnMut(web_sys::SubmitEvent, Result<T, FormValuesError>) + 'static,
+    ) -> Self {
+        self.on("submit", move |event: JsValue| {
+            let event: web_sys::SubmitEvent = event.unchecked_into();
+            event.prevent_default();
+            let form: web_sys::HtmlFormElement =
+                event.current_target().unwrap_throw().unchecked_into();
+            let mut entries = entries(&form);
+
+            if let Some(submitter) = event.submitter() {
+                let name = submitter.get_attribute("name").unwrap_or_default();
+
+                if !name.is_empty() {
+                    let value = js_sys::Reflect::get(&submitter, &JsValue::from_str("value"))
+                        .ok()
+                        .and_then(|value| value.as_string())
+                        .unwrap_or_default();
+                    entries.push((name, value));
+                }
+            }
+
+            f(event, from_entries(entries))
+        })
+    }
+}
+
+/// Deserialize name/value pairs, as submitted by a `<form>`, into a `T`.
+///
+/// Entries are grouped by name, so repeated names (from multi-selects or
+/// checkboxes sharing a name) can be deserialized into a sequence, such as a
+/// `Vec`. Where a single value is expected, the last entry with that name is
+/// used.
+///
+/// Values are parsed from strings as needed. Booleans accept `true`, `on`,
+/// `false` and `off`. Unchecked checkboxes aren't submitted, so boolean fields
+/// will usually need `#[serde(default)]`. Empty values, like an empty
+/// `<input>`, deserialize to `None` for `Option` fields.
+pub fn from_entries<T: DeserializeOwned>(
+    entries: impl IntoIterator<Item = (String, String)>,
+) -> Result<T, FormValuesError> {
+    let mut grouped: IndexMap<String, Vec<String>> = IndexMap::new();
+
+    for (name, value) in entries {
+        grouped.entry(name).or_default().push(value);
+    }
+
+    T::deserialize(EntriesDeserializer(grouped.into_iter()))
+}
+
+/// An error deserializing form values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormValuesError(String);
+
+impl fmt::Display for FormValuesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for FormValuesError {}
+
+impl de::Error for FormValuesError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+type Entries = indexmap::map::IntoIter<String, Vec<String>>;
+
+struct EntriesDeserializer(Entries);
+
+impl<'de> de::Deserializer<'de> for EntriesDeserializer {
+    type Error = FormValuesError;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(EntriesAccess {
+            entries: self.0,
+            values: None,
+        })
+    }
+}
+
+struct EntriesAccess {
+    entries: Entries,
+    values: Option<Vec<String>>,
+}
+
+impl<'de> MapAccess<'de> for EntriesAccess {
+    type Error = FormValuesError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((name, values)) = self.entries.next() else {
+            return Ok(None);
+        };
+
+        self.values = Some(values);
+        seed.deserialize(StringDeserializer::new(name)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let values = self
+            .values
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+
+        seed.deserialize(ValuesDeserializer(values))
+    }
+}
+
+/// All the values for a name.
+struct ValuesDeserializer(Vec<String>);
+
+impl ValuesDeserializer {
+    fn last(mut self) -> ValueDeserializer {
+        ValueDeserializer(self.0.pop().unwrap_or_default())
+    }
+}
+
+macro_rules! forward_to_last {
+    ($($method:ident)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            self.last().$method(visitor)
+        }
+    )*}
+}
+
+impl<'de> de::Deserializer<'de> for ValuesDeserializer {
+    type Error = FormValuesError;
+
+    forward_to_last! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_unit deserialize_identifier deserialize_ignored_any
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.iter().all(String::is_empty) {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.last().deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(ValuesAccess(self.0.into_iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom(
+            "form values can't be deserialized as a map",
+        ))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom(
+            "form values can't be deserialized as a struct",
+        ))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.last().deserialize_enum(name, variants, visitor)
+    }
+}
+
+struct ValuesAccess(std::vec::IntoIter<String>);
+
+impl<'de> SeqAccess<'de> for ValuesAccess {
+    type Error = FormValuesError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(ValueDeserializer(value)))
+            .transpose()
+    }
+}
+
+/// A single value.
+struct ValueDeserializer(String);
+
+impl ValueDeserializer {
+    fn parse<T: FromStr>(&self) -> Result<T, FormValuesError>
+    where
+        T::Err: fmt::Display,
+    {
+        self.0
+            .trim()
+            .parse()
+            .map_err(|e| de::Error::custom(format!("invalid value \"{}\": {e}", self.0)))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),* $(,)?) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.$visit(self.parse()?)
+        }
+    )*}
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = FormValuesError;
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0.as_str() {
+            "true" | "on" => visitor.visit_bool(true),
+            "false" | "off" => visitor.visit_bool(false),
+            _ => Err(de::Error::custom(format!(
+                "invalid boolean value \"{}\"",
+                self.0
+            ))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(IntoDeserializer::<FormValuesError>::into_deserializer(
+            self.0,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::from_entries;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum Size {
+        Small,
+        Large,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Order {
+        name: String,
+        quantity: u32,
+        size: Size,
+        toppings: Vec<String>,
+        #[serde(default)]
+        gift: bool,
+        #[serde(default)]
+        express: bool,
+        note: Option<String>,
+    }
+
+    fn entries(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn values_as_struct() {
+        let order: Order = from_entries(entries(&[
+            ("name", "Alice"),
+            ("quantity", "2"),
+            ("size", "Large"),
+            ("toppings", "cheese"),
+            ("toppings", "olives"),
+            ("gift", "on"),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            order,
+            Order {
+                name: "Alice".to_string(),
+                quantity: 2,
+                size: Size::Large,
+                toppings: vec!["cheese".to_string(), "olives".to_string()],
+                gift: true,
+                express: false,
+                note: None,
+            }
+        );
+    }
+
+    #[test]
+    fn single_value_sequence() {
+        let order: Order = from_entries(entries(&[
+            ("name", "Bob"),
+            ("quantity", "1"),
+            ("size", "Small"),
+            ("toppings", "ham"),
+            ("note", "Ring the bell"),
+        ]))
+        .unwrap();
+
+        assert_eq!(order.toppings, vec!["ham".to_string()]);
+        assert_eq!(order.note.as_deref(), Some("Ring the bell"));
+    }
+
+    #[test]
+    fn repeated_name_uses_last_value() {
+        #[derive(Deserialize)]
+        struct Quantity {
+            quantity: u32,
+        }
+
+        let parsed: Quantity =
+            from_entries(entries(&[("quantity", "1"), ("quantity", "3")])).unwrap();
+        assert_eq!(parsed.quantity, 3);
+    }
+
+    #[test]
+    fn empty_optional_values() {
+        #[derive(Deserialize)]
+        struct Optional {
+            quantity: Option<u32>,
+            note: Option<String>,
+        }
+
+        let parsed: Optional = from_entries(entries(&[("quantity", ""), ("note", "")])).unwrap();
+        assert_eq!(parsed.quantity, None);
+        assert_eq!(parsed.note, None);
+
+        let parsed: Optional =
+            from_entries(entries(&[("quantity", "3"), ("note", "Ring")])).unwrap();
+        assert_eq!(parsed.quantity, Some(3));
+        assert_eq!(parsed.note.as_deref(), Some("Ring"));
+    }
+
+    #[test]
+    fn invalid_number() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Quantity {
+            quantity: u32,
+        }
+
+        let error = from_entries::<Quantity>(entries(&[("quantity", "lots")])).unwrap_err();
+        assert!(error.to_string().contains("lots"));
+    }
+}
@@ -0,0 +1,745 @@
+//! Pointer gestures.
+//!
+//! [`ElementGestures`] recognizes drag, swipe, pinch and long press gestures
+//! from pointer events, so they work with a mouse, pen or touch. Pointers are
+//! captured by the element once a drag, swipe or pinch is in progress, so the
+//! gesture continues if the pointer leaves the element. Pointers aren't
+//! captured when they go down, so clicks still reach children such as buttons
+//! and links.
+//!
+//! Browsers will scroll or zoom the page for touch gestures unless told not
+//! to, so you'll usually want to set the CSS `touch-action` property on the
+//! element. For example, `touch-action: none` for a map view, or `touch-action:
+//! pan-y` for a horizontal carousel.
+//!
+//! Positions are in CSS pixels, relative to the viewport, and velocities are in
+//! CSS pixels per millisecond.
+//!
+//! # Example
+//!
+//! ```no_run
+#![doc = function_body!("tests/doc/gesture.rs", module_example, [])]
+//! ```
+use std::{
+    cell::RefCell,
+    ops::{Add, Div, Mul, Sub},
+    rc::Rc,
+    time::Duration,
+};
+
+use include_doc::function_body;
+use wasm_bindgen::JsCast;
+
+use crate::{
+    clone,
+    node::element::{spawn_cancelable_future, Element},
+    time::sleep,
+};
+
+/// How far a pointer must move before a drag starts.
+const DRAG_THRESHOLD: f64 = 3.0;
+/// The minimum distance for a swipe.
+const SWIPE_MIN_DISTANCE: f64 = 30.0;
+/// The minimum average speed for a swipe.
+const SWIPE_MIN_SPEED: f64 = 0.3;
+/// How long a pointer must be held for a long press.
+const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+/// How far a pointer can move before a long press is cancelled.
+const LONG_PRESS_TOLERANCE: f64 = 10.0;
+
+/// Recognize gestures on an element.
+pub trait ElementGestures: Element {
+    /// Call `f` as the element is dragged with a single pointer.
+    ///
+    /// A drag starts once the pointer has moved a few pixels, so clicks don't
+    /// start a drag.
+    fn on_drag(self, mut f: impl FnMut(Drag, Self::DomElement) + 'static) -> Self {
+        let mut recognizer = DragRecognizer::default();
+
+        on_pointer(self, move |input, element| {
+            if let Some(drag) = recognizer.input(input) {
+                if drag.phase == Phase::Start {
+                    capture(&element, input.pointer.id);
+                }
+
+                f(drag, element.unchecked_into())
+            }
+        })
+    }
+
+    /// Call `f` when the element is swiped with a quick flick of a pointer.
+    fn on_swipe(self, mut f: impl FnMut(Swipe, Self::DomElement) + 'static) -> Self {
+        let mut recognizer = SwipeRecognizer::default();
+
+        on_pointer(self, move |input, element| {
+            if recognizer.is_moving(input.pointer) {
+                capture(&element, input.pointer.id);
+            }
+
+            if let Some(swipe) = recognizer.input(input) {
+                f(swipe, element.unchecked_into())
+            }
+        })
+    }
+
+    /// Call `f` as the element is pinched with 2 pointers.
+    fn on_pinch(self, mut f: impl FnMut(Pinch, Self::DomElement) + 'static) -> Self {
+        let mut recognizer = PinchRecognizer::default();
+
+        on_pointer(self, move |input, element| {
+            if let Some(pinch) = recognizer.input(input) {
+                if pinch.phase == Phase::Start {
+                    for pointer in &recognizer.pointers {
+                        capture(&element, pointer.id);
+                    }
+                }
+
+                f(pinch, element.unchecked_into())
+            }
+        })
+    }
+
+    /// Call `f` when a pointer is held still on the element.
+    fn on_long_press(self, f: impl FnMut(LongPress, Self::DomElement) + 'static) -> Self {
+        let recognizer = Rc::new(RefCell::new(LongPressRecognizer::default()));
+        let f = Rc::new(RefCell::new(f));
+        // Dropping the timer cancels it.
+        let mut timer = None;
+
+        on_pointer(self, move |input, element| {
+            match recognizer.borrow_mut().input(input) {
+                LongPressAction::Start => {
+                    clone!(recognizer, f);
+                    timer.replace(spawn_cancelable_future(async move {
+                        sleep(LONG_PRESS_DURATION).await;
+
+                        let press = recognizer.borrow_mut().fire();
+
+                        if let Some(press) = press {
+                            f.borrow_mut()(press, element.unchecked_into())
+                        }
+                    }));
+                }
+                LongPressAction::Cancel => {
+                    timer.take();
+                }
+                LongPressAction::None => (),
+            }
+        })
+    }
+}
+
+impl<T: Element> ElementGestures for T {}
+
+/// A position or offset.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// The distance from the origin.
+    pub fn length(self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    fn midpoint(self, other: Self) -> Self {
+        (self + other) / 2.0
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<f64> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Div<f64> for Point {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Self::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+/// The phase of a continuous gesture.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Start,
+    Move,
+    End,
+    /// The browser cancelled the gesture, for example, because it started
+    /// scrolling.
+    Cancel,
+}
+
+/// A drag gesture.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Drag {
+    pub phase: Phase,
+    /// Where the pointer went down.
+    pub start: Point,
+    pub position: Point,
+    /// The movement since the last drag event.
+    pub delta: Point,
+    /// The movement since the pointer went down.
+    pub offset: Point,
+    pub velocity: Point,
+}
+
+/// A swipe gesture.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Swipe {
+    /// The main direction of the swipe.
+    pub direction: Direction,
+    /// The movement from the start to the end of the swipe.
+    pub offset: Point,
+    /// The average velocity of the swipe.
+    pub velocity: Point,
+}
+
+/// The direction of a [`Swipe`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// A pinch gesture.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pinch {
+    pub phase: Phase,
+    /// The distance between the pointers, relative to the start of the pinch.
+    pub scale: f64,
+    /// The midpoint between the pointers.
+    pub center: Point,
+    /// How far the center has moved since the last pinch event.
+    pub delta: Point,
+}
+
+/// A long press gesture.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LongPress {
+    pub position: Point,
+}
+
+/// Add pointer listeners to `element`, and call `f` with each input.
+fn on_pointer<E: Element>(element: E, f: impl FnMut(Input, web_sys::Element) + 'static) -> E {
+    let f = Rc::new(RefCell::new(f));
+
+    [
+        ("pointerdown", InputKind::Down),
+        ("pointermove", InputKind::Move),
+        ("pointerup", InputKind::Up),
+        ("pointercancel", InputKind::Cancel),
+    ]
+    .into_iter()
+    .fold(element, |element, (name, kind)| {
+        clone!(f);
+
+        element.on(name, move |js_ev| {
+            let Some(target) = crate::macros::current_target(&js_ev) else {
+                return;
+            };
+            let target: web_sys::Element = target.unchecked_into();
+            let event: web_sys::PointerEvent = js_ev.unchecked_into();
+
+            let pointer = Pointer {
+                id: event.pointer_id(),
+                position: Point::new(event.client_x().into(), event.client_y().into()),
+                time: event.time_stamp(),
+            };
+
+            f.borrow_mut()(Input { kind, pointer }, target)
+        })
+    })
+}
+
+/// Capture `pointer`, so the gesture continues if it leaves `element`.
+fn capture(element: &web_sys::Element, pointer: i32) {
+    if !element.has_pointer_capture(pointer) {
+        // This can fail if the pointer has already been released, in which case
+        // there's nothing to capture.
+        let _ = element.set_pointer_capture(pointer);
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Pointer {
+    id: i32,
+    position: Point,
+    time: f64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum InputKind {
+    Down,
+    Move,
+    Up,
+    Cancel,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Input {
+    kind: InputKind,
+    pointer: Pointer,
+}
+
+#[derive(Default)]
+struct DragRecognizer(Option<DragState>);
+
+struct DragState {
+    start: Pointer,
+    last: Pointer,
+    velocity: Point,
+    started: bool,
+}
+
+impl DragRecognizer {
+    fn input(&mut self, Input { kind, pointer }: Input) -> Option<Drag> {
+        if kind == InputKind::Down {
+            self.0.get_or_insert(DragState {
+                start: pointer,
+                last: pointer,
+                velocity: Point::default(),
+                started: false,
+            });
+
+            return None;
+        }
+
+        let state = self
+            .0
+            .as_mut()
+            .filter(|state| state.start.id == pointer.id)?;
+        let delta = pointer.position - state.last.position;
+        let elapsed = pointer.time - state.last.time;
+
+        // Keep the velocity from the last move when the pointer is released, so it can
+        // be used for momentum.
+        if kind == InputKind::Move && elapsed > 0.0 {
+            state.velocity = delta / elapsed;
+        }
+
+        let phase = match kind {
+            InputKind::Down => unreachable!(),
+            InputKind::Move if state.started => Phase::Move,
+            InputKind::Move => {
+                if (pointer.position - state.start.position).length() < DRAG_THRESHOLD {
+                    return None;
+                }
+
+                state.started = true;
+                Phase::Start
+            }
+            InputKind::Up | InputKind::Cancel if !state.started => {
+                self.0 = None;
+                return None;
+            }
+            InputKind::Up => Phase::End,
+            InputKind::Cancel => Phase::Cancel,
+        };
+
+        let drag = Drag {
+            phase,
+            start: state.start.position,
+            position: pointer.position,
+            delta,
+            offset: pointer.position - state.start.position,
+            velocity: state.velocity,
+        };
+
+        state.last = pointer;
+
+        if matches!(phase, Phase::End | Phase::Cancel) {
+            self.0 = None;
+        }
+
+        Some(drag)
+    }
+}
+
+#[derive(Default)]
+struct SwipeRecognizer(Option<Pointer>);
+
+impl SwipeRecognizer {
+    /// Has `pointer` moved far enough from the start to be a swipe in
+    /// progress?
+    fn is_moving(&self, pointer: Pointer) -> bool {
+        self.0.is_some_and(|start| {
+            start.id == pointer.id && (pointer.position - start.position).length() >= DRAG_THRESHOLD
+        })
+    }
+
+    fn input(&mut self, Input { kind, pointer }: Input) -> Option<Swipe> {
+        match kind {
+            InputKind::Down => {
+                self.0.get_or_insert(pointer);
+                None
+            }
+            InputKind::Move => None,
+            InputKind::Cancel => {
+                self.0.take_if(|start| start.id == pointer.id);
+                None
+            }
+            InputKind::Up => {
+                let start = self.0.take_if(|start| start.id == pointer.id)?;
+                let offset = pointer.position - start.position;
+                let elapsed = pointer.time - start.time;
+                let distance = offset.length();
+
+                if distance < SWIPE_MIN_DISTANCE
+                    || elapsed <= 0.0
+                    || distance / elapsed < SWIPE_MIN_SPEED
+                {
+                    return None;
+                }
+
+                let direction = if offset.x.abs() >= offset.y.abs() {
+                    if offset.x > 0.0 {
+                        Direction::Right
+                    } else {
+                        Direction::Left
+                    }
+                } else if offset.y > 0.0 {
+                    Direction::Down
+                } else {
+                    Direction::Up
+                };
+
+                Some(Swipe {
+                    direction,
+                    offset,
+                    velocity: offset / elapsed,
+                })
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct PinchRecognizer {
+    pointers: Vec<Pointer>,
+    start_distance: f64,
+    center: Point,
+}
+
+impl PinchRecognizer {
+    fn input(&mut self, Input { kind, pointer }: Input) -> Option<Pinch> {
+        let index = self.pointers.iter().position(|p| p.id == pointer.id);
+
+        let phase = match (kind, index) {
+            (InputKind::Down, None) if self.pointers.len() < 2 => {
+                self.pointers.push(pointer);
+
+                if self.pointers.len() < 2 {
+                    return None;
+                }
+
+                self.start_distance = self.distance();
+                self.center = self.midpoint();
+                Phase::Start
+            }
+            (InputKind::Move, Some(index)) => {
+                self.pointers[index] = pointer;
+
+                if self.pointers.len() < 2 {
+                    return None;
+                }
+
+                Phase::Move
+            }
+            (InputKind::Up | InputKind::Cancel, Some(index)) => {
+                self.pointers[index] = pointer;
+                let pinching = self.pointers.len() == 2;
+                let pinch = pinching.then(|| {
+                    self.pinch(if kind == InputKind::Up {
+                        Phase::End
+                    } else {
+                        Phase::Cancel
+                    })
+                });
+                self.pointers.remove(index);
+                return pinch;
+            }
+            _ => return None,
+        };
+
+        Some(self.pinch(phase))
+    }
+
+    fn pinch(&mut self, phase: Phase) -> Pinch {
+        let center = self.midpoint();
+        let delta = center - self.center;
+        self.center = center;
+
+        Pinch {
+            phase,
+            scale: if self.start_distance > 0.0 {
+                self.distance() / self.start_distance
+            } else {
+                1.0
+            },
+            center,
+            delta,
+        }
+    }
+
+    fn distance(&self) -> f64 {
+        (self.pointers[0].position - self.pointers[1].position).length()
+    }
+
+    fn midpoint(&self) -> Point {
+        self.pointers[0]
+            .position
+            .midpoint(self.pointers[1].position)
+    }
+}
+
+#[derive(Default)]
+struct LongPressRecognizer(Option<Pointer>);
+
+#[derive(Debug, PartialEq, Eq)]
+enum LongPressAction {
+    Start,
+    Cancel,
+    None,
+}
+
+impl LongPressRecognizer {
+    fn input(&mut self, Input { kind, pointer }: Input) -> LongPressAction {
+        match kind {
+            InputKind::Down => {
+                if self.0.is_some() {
+                    // A second pointer means this isn't a long press.
+                    self.0 = None;
+                    LongPressAction::Cancel
+                } else {
+                    self.0 = Some(pointer);
+                    LongPressAction::Start
+                }
+            }
+            InputKind::Move => {
+                if self.0.is_some_and(|start| {
+                    start.id == pointer.id
+                        && (pointer.position - start.position).length() > LONG_PRESS_TOLERANCE
+                }) {
+                    self.0 = None;
+                    LongPressAction::Cancel
+                } else {
+                    LongPressAction::None
+                }
+            }
+            InputKind::Up | InputKind::Cancel => {
+                if self.0.take_if(|start| start.id == pointer.id).is_some() {
+                    LongPressAction::Cancel
+                } else {
+                    LongPressAction::None
+                }
+            }
+        }
+    }
+
+    /// The long press timer has fired.
+    fn fire(&mut self) -> Option<LongPress> {
+        self.0.take().map(|start| LongPress {
+            position: start.position,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Direction, DragRecognizer, Input, InputKind, LongPressAction, LongPressRecognizer, Phase,
+        PinchRecognizer, Point, Pointer, SwipeRecognizer,
+    };
+
+    fn input(kind: InputKind, id: i32, x: f64, y: f64, time: f64) -> Input {
+        Input {
+            kind,
+            pointer: Pointer {
+                id,
+                position: Point::new(x, y),
+                time,
+            },
+        }
+    }
+
+    use InputKind::{Cancel, Down, Move, Up};
+
+    #[test]
+    fn drag() {
+        let mut drag = DragRecognizer::default();
+
+        assert_eq!(drag.input(input(Down, 1, 10.0, 10.0, 0.0)), None);
+        assert_eq!(
+            drag.input(input(Move, 1, 11.0, 10.0, 10.0)),
+            None,
+            "Below threshold"
+        );
+        assert_eq!(
+            drag.input(input(Move, 2, 50.0, 50.0, 15.0)),
+            None,
+            "Other pointers are ignored"
+        );
+
+        let start = drag.input(input(Move, 1, 20.0, 10.0, 20.0)).unwrap();
+        assert_eq!(start.phase, Phase::Start);
+        assert_eq!(start.start, Point::new(10.0, 10.0));
+        assert_eq!(start.offset, Point::new(10.0, 0.0));
+        assert_eq!(start.delta, start.offset, "Deltas add up to the offset");
+
+        let moved = drag.input(input(Move, 1, 30.0, 20.0, 30.0)).unwrap();
+        assert_eq!(moved.phase, Phase::Move);
+        assert_eq!(moved.delta, Point::new(10.0, 10.0));
+        assert_eq!(moved.offset, Point::new(20.0, 10.0));
+        assert_eq!(moved.velocity, Point::new(1.0, 1.0));
+
+        let end = drag.input(input(Up, 1, 30.0, 20.0, 40.0)).unwrap();
+        assert_eq!(end.phase, Phase::End);
+        assert_eq!(
+            end.velocity,
+            Point::new(1.0, 1.0),
+            "Velocity from last move"
+        );
+
+        assert_eq!(drag.input(input(Move, 1, 40.0, 20.0, 50.0)), None);
+    }
+
+    #[test]
+    fn click_is_not_a_drag() {
+        let mut drag = DragRecognizer::default();
+
+        assert_eq!(drag.input(input(Down, 1, 10.0, 10.0, 0.0)), None);
+        assert_eq!(drag.input(input(Up, 1, 10.0, 10.0, 100.0)), None);
+    }
+
+    #[test]
+    fn drag_cancel() {
+        let mut drag = DragRecognizer::default();
+
+        drag.input(input(Down, 1, 0.0, 0.0, 0.0));
+        drag.input(input(Move, 1, 10.0, 0.0, 10.0));
+        let cancel = drag.input(input(Cancel, 1, 10.0, 0.0, 20.0)).unwrap();
+        assert_eq!(cancel.phase, Phase::Cancel);
+    }
+
+    #[test]
+    fn swipe() {
+        let mut swipe = SwipeRecognizer::default();
+
+        swipe.input(input(Down, 1, 100.0, 0.0, 0.0));
+        let left = swipe.input(input(Up, 1, 0.0, 10.0, 100.0)).unwrap();
+        assert_eq!(left.direction, Direction::Left);
+        assert_eq!(left.offset, Point::new(-100.0, 10.0));
+        assert_eq!(left.velocity, Point::new(-1.0, 0.1));
+
+        swipe.input(input(Down, 1, 0.0, 0.0, 0.0));
+        assert_eq!(
+            swipe.input(input(Up, 1, 0.0, 100.0, 1000.0)),
+            None,
+            "Too slow"
+        );
+
+        swipe.input(input(Down, 1, 0.0, 0.0, 0.0));
+        assert_eq!(
+            swipe.input(input(Up, 1, 0.0, 10.0, 10.0)),
+            None,
+            "Too short"
+        );
+
+        swipe.input(input(Down, 1, 0.0, 0.0, 0.0));
+        assert!(!swipe.is_moving(input(Move, 1, 1.0, 1.0, 10.0).pointer));
+        assert!(!swipe.is_moving(input(Move, 2, 50.0, 0.0, 10.0).pointer));
+        assert!(swipe.is_moving(input(Move, 1, 5.0, 0.0, 10.0).pointer));
+        swipe.input(input(Cancel, 1, 0.0, 0.0, 0.0));
+        assert_eq!(swipe.input(input(Up, 1, 0.0, -100.0, 10.0)), None);
+    }
+
+    #[test]
+    fn pinch() {
+        let mut pinch = PinchRecognizer::default();
+
+        assert_eq!(pinch.input(input(Down, 1, 0.0, 0.0, 0.0)), None);
+        assert_eq!(pinch.input(input(Move, 1, 0.0, 10.0, 0.0)), None);
+
+        let start = pinch.input(input(Down, 2, 20.0, 10.0, 0.0)).unwrap();
+        assert_eq!(start.phase, Phase::Start);
+        assert_eq!(start.scale, 1.0);
+        assert_eq!(start.center, Point::new(10.0, 10.0));
+
+        assert_eq!(
+            pinch.input(input(Down, 3, 50.0, 50.0, 0.0)),
+            None,
+            "Only 2 pointers"
+        );
+
+        let moved = pinch.input(input(Move, 2, 40.0, 10.0, 0.0)).unwrap();
+        assert_eq!(moved.phase, Phase::Move);
+        assert_eq!(moved.scale, 2.0);
+        assert_eq!(moved.center, Point::new(20.0, 10.0));
+        assert_eq!(moved.delta, Point::new(10.0, 0.0));
+
+        let end = pinch.input(input(Up, 1, 0.0, 10.0, 0.0)).unwrap();
+        assert_eq!(end.phase, Phase::End);
+        assert_eq!(pinch.input(input(Move, 2, 60.0, 10.0, 0.0)), None);
+    }
+
+    #[test]
+    fn long_press() {
+        let mut press = LongPressRecognizer::default();
+
+        assert_eq!(
+            press.input(input(Down, 1, 0.0, 0.0, 0.0)),
+            LongPressAction::Start
+        );
+        assert_eq!(
+            press.input(input(Move, 1, 5.0, 5.0, 0.0)),
+            LongPressAction::None,
+            "Within tolerance"
+        );
+        assert_eq!(press.fire().unwrap().position, Point::new(0.0, 0.0));
+        assert_eq!(press.fire(), None);
+
+        press.input(input(Down, 1, 0.0, 0.0, 0.0));
+        assert_eq!(
+            press.input(input(Move, 1, 20.0, 0.0, 0.0)),
+            LongPressAction::Cancel
+        );
+        assert_eq!(press.fire(), None);
+
+        press.input(input(Down, 1, 0.0, 0.0, 0.0));
+        assert_eq!(
+            press.input(input(Down, 2, 0.0, 0.0, 0.0)),
+            LongPressAction::Cancel,
+            "Multi touch"
+        );
+        assert_eq!(press.fire(), None);
+    }
+}
@@ -0,0 +1,778 @@
+//! Keyboard shortcuts.
+//!
+//! Hotkeys are written as chords of modifiers and a key, separated by `+`, like
+//! `"Ctrl+Shift+K"`. Sequences of chords are separated by spaces, like `"g
+//! i"`. The modifiers are `Ctrl`, `Shift`, `Alt` and `Meta`. `Mod` is `Meta` on
+//! Apple platforms and `Ctrl` elsewhere. Keys are matched case insensitively
+//! by the character the layout produces, so `Ctrl+A` is the key labelled "A"
+//! on AZERTY and Dvorak layouts too. The physical key is only used when the
+//! character isn't ASCII, so `Alt+K` still matches on layouts where `Alt`
+//! changes the character, and `Ctrl+A` matches on non-latin layouts.
+//!
+//! Use [`bind`] for hotkeys that apply to the whole document, and
+//! [`ElementHotkeys::hotkey`] for hotkeys that only apply while focus is
+//! within an element. Element hotkeys are removed when the element is dropped.
+//! If more than one binding matches, the one on the innermost element wins.
+//!
+//! Hotkeys without `Ctrl`, `Alt`, `Meta` or `Mod` don't fire while the user
+//! is typing in a text field.
+//!
+//! [`bindings`] lists the current bindings, for example to show in a help
+//! dialog.
+//!
+//! # Example
+//!
+//! ```no_run
+#![doc = function_body!("tests/doc/hotkey.rs", module_example, [])]
+//! ```
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    future,
+    rc::Rc,
+    str::FromStr,
+};
+
+use include_doc::function_body;
+use silkenweb_macros::cfg_browser;
+use wasm_bindgen::JsCast;
+use web_sys::KeyboardEvent;
+
+use crate::{node::element::Element, task, window};
+
+/// Bind `keys` for the whole document.
+///
+/// The binding is removed when the returned [`Binding`] is dropped.
+///
+/// # Panics
+///
+/// If `keys` isn't a valid [`Hotkey`].
+pub fn bind(
+    keys: &str,
+    description: impl Into<String>,
+    f: impl FnMut(KeyboardEvent) + 'static,
+) -> Binding {
+    Binding::new(keys, description.into(), None, f)
+}
+
+/// All the current bindings, in the order they were added.
+pub fn bindings() -> Vec<BindingInfo> {
+    task::local::with(|local| {
+        local
+            .hotkey
+            .0
+            .borrow()
+            .bindings
+            .iter()
+            .map(|entry| BindingInfo {
+                keys: entry.keys.clone(),
+                description: entry.description.clone(),
+                scoped: entry.scope.is_some(),
+            })
+            .collect()
+    })
+}
+
+/// Scope hotkeys to an element.
+pub trait ElementHotkeys: Element {
+    /// Bind `keys` while focus is within this element.
+    ///
+    /// The binding is removed when the element is dropped.
+    ///
+    /// # Panics
+    ///
+    /// If `keys` isn't a valid [`Hotkey`].
+    fn hotkey(
+        self,
+        keys: &str,
+        description: impl Into<String>,
+        f: impl FnMut(KeyboardEvent) + 'static,
+    ) -> Self {
+        let scope = self.handle();
+        let binding = Binding::new(
+            keys,
+            description.into(),
+            Some(Box::new(move || scope.try_element())),
+            f,
+        );
+
+        self.spawn_future(async move {
+            let _binding = binding;
+            future::pending().await
+        })
+    }
+}
+
+impl<T: Element> ElementHotkeys for T {}
+
+/// A hotkey binding.
+///
+/// The binding is removed when this is dropped.
+#[must_use = "The binding will be removed when it is dropped. Use the `perpetual` method to make it permanent."]
+pub struct Binding(Option<u64>);
+
+impl Binding {
+    fn new(
+        keys: &str,
+        description: String,
+        scope: Option<Scope>,
+        f: impl FnMut(KeyboardEvent) + 'static,
+    ) -> Self {
+        let keys = keys
+            .parse()
+            .unwrap_or_else(|e| panic!("Invalid hotkey \"{keys}\": {e}"));
+
+        task::local::with(|local| {
+            let mut registry = local.hotkey.0.borrow_mut();
+
+            if !registry.listening {
+                registry.listening = true;
+                window::on_keydown(on_keydown).perpetual();
+            }
+
+            let id = registry.next_id;
+            registry.next_id += 1;
+            registry.bindings.push(Entry {
+                id,
+                keys,
+                description,
+                scope,
+                handler: Rc::new(RefCell::new(f)),
+            });
+
+            Self(Some(id))
+        })
+    }
+
+    /// Make this binding permanent.
+    pub fn perpetual(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for Binding {
+    fn drop(&mut self) {
+        if let Some(id) = self.0 {
+            let removed = task::local::with(|local| {
+                let mut registry = local.hotkey.0.borrow_mut();
+                let index = registry.bindings.iter().position(|entry| entry.id == id)?;
+                Some(registry.bindings.remove(index))
+            });
+
+            // The handler might own other bindings, so drop it after we've released the
+            // borrow.
+            drop(removed);
+        }
+    }
+}
+
+/// Information about a binding, for display.
+#[derive(Clone, Debug)]
+pub struct BindingInfo {
+    keys: Hotkey,
+    description: String,
+    scoped: bool,
+}
+
+impl BindingInfo {
+    pub fn keys(&self) -> &Hotkey {
+        &self.keys
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Is the binding scoped to an element?
+    pub fn is_scoped(&self) -> bool {
+        self.scoped
+    }
+}
+
+/// A sequence of key chords.
+///
+/// See the [module-level documentation](self) for the syntax. [`Display`]
+/// shows the hotkey with `Mod` resolved for the current platform.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hotkey(Vec<Chord>);
+
+impl Hotkey {
+    fn matches(&self, presses: &[KeyPress], is_apple: bool) -> Match {
+        if presses.len() > self.0.len() {
+            return Match::None;
+        }
+
+        if !self
+            .0
+            .iter()
+            .zip(presses)
+            .all(|(chord, press)| chord.matches(press, is_apple))
+        {
+            return Match::None;
+        }
+
+        if presses.len() == self.0.len() {
+            Match::Full
+        } else {
+            Match::Prefix
+        }
+    }
+
+    fn has_command_modifier(&self) -> bool {
+        self.0.iter().any(|chord| {
+            let modifiers = chord.modifiers;
+            modifiers.ctrl || modifiers.alt || modifiers.meta || modifiers.primary
+        })
+    }
+
+    fn fmt_for(&self, f: &mut fmt::Formatter, is_apple: bool) -> fmt::Result {
+        for (index, chord) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+
+            chord.fmt_for(f, is_apple)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Hotkey {
+    type Err = ParseHotkeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chords = s
+            .split_whitespace()
+            .map(Chord::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if chords.is_empty() {
+            return Err(ParseHotkeyError("empty hotkey".to_string()));
+        }
+
+        Ok(Self(chords))
+    }
+}
+
+impl Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_for(f, is_apple())
+    }
+}
+
+/// An error parsing a [`Hotkey`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseHotkeyError(String);
+
+impl Display for ParseHotkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseHotkeyError {}
+
+#[derive(Clone, Debug)]
+struct Chord {
+    modifiers: Modifiers,
+    /// Lower case key name, for matching.
+    key: String,
+    /// Key name for display.
+    label: String,
+}
+
+impl PartialEq for Chord {
+    fn eq(&self, other: &Self) -> bool {
+        self.modifiers == other.modifiers && self.key == other.key
+    }
+}
+
+impl Eq for Chord {}
+
+impl Chord {
+    fn parse(s: &str) -> Result<Self, ParseHotkeyError> {
+        let mut modifiers = Modifiers::default();
+        let mut parts: Vec<&str> = s.split('+').collect();
+
+        // Allow "+" as a key, as in "Ctrl++"
+        if s.ends_with("++") {
+            parts.truncate(parts.len() - 2);
+            parts.push("+");
+        }
+
+        let (key, modifier_names) = parts
+            .split_last()
+            .ok_or_else(|| ParseHotkeyError("empty chord".to_string()))?;
+
+        for name in modifier_names {
+            let modifier = match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => &mut modifiers.ctrl,
+                "shift" => &mut modifiers.shift,
+                "alt" | "option" => &mut modifiers.alt,
+                "meta" | "cmd" | "command" | "super" => &mut modifiers.meta,
+                "mod" => &mut modifiers.primary,
+                _ => return Err(ParseHotkeyError(format!("unknown modifier \"{name}\""))),
+            };
+
+            *modifier = true;
+        }
+
+        if key.is_empty() {
+            return Err(ParseHotkeyError(format!("missing key in \"{s}\"")));
+        }
+
+        let key = normalize_key(key);
+        let label = key_label(&key, parts.last().copied().unwrap_or_default());
+
+        Ok(Self {
+            modifiers,
+            key,
+            label,
+        })
+    }
+
+    fn matches(&self, press: &KeyPress, is_apple: bool) -> bool {
+        let modifiers = self.modifiers;
+        let ctrl = modifiers.ctrl || (modifiers.primary && !is_apple);
+        let meta = modifiers.meta || (modifiers.primary && is_apple);
+        // The shift state is part of the key for symbols, like "?".
+        let check_shift = !is_symbol(&self.key);
+
+        press.ctrl == ctrl
+            && press.meta == meta
+            && press.alt == modifiers.alt
+            && (!check_shift || press.shift == modifiers.shift)
+            && pressed_key(press) == self.key
+    }
+
+    fn fmt_for(&self, f: &mut fmt::Formatter, is_apple: bool) -> fmt::Result {
+        let modifiers = self.modifiers;
+        let primary = if is_apple { "Meta" } else { "Ctrl" };
+
+        for (enabled, name) in [
+            (modifiers.ctrl, "Ctrl"),
+            (modifiers.primary, primary),
+            (modifiers.alt, "Alt"),
+            (modifiers.shift, "Shift"),
+            (modifiers.meta && !(modifiers.primary && is_apple), "Meta"),
+        ] {
+            if enabled {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        f.write_str(&self.label)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct Modifiers {
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    meta: bool,
+    /// `Mod`
+    primary: bool,
+}
+
+/// The parts of a `KeyboardEvent` we need for matching.
+#[derive(Clone, Debug)]
+struct KeyPress {
+    key: String,
+    code: String,
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    meta: bool,
+    time: f64,
+}
+
+impl KeyPress {
+    fn new(event: &KeyboardEvent) -> Self {
+        Self {
+            key: event.key(),
+            code: event.code(),
+            ctrl: event.ctrl_key(),
+            shift: event.shift_key(),
+            alt: event.alt_key(),
+            meta: event.meta_key(),
+            time: event.time_stamp(),
+        }
+    }
+
+    fn is_modifier(&self) -> bool {
+        matches!(
+            self.key.as_str(),
+            "Control" | "Shift" | "Alt" | "AltGraph" | "Meta" | "OS" | "CapsLock"
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Match {
+    None,
+    Prefix,
+    Full,
+}
+
+fn normalize_key(key: &str) -> String {
+    let key = key.to_lowercase();
+
+    match key.as_str() {
+        "space" | "spacebar" => " ".to_string(),
+        "esc" => "escape".to_string(),
+        "del" => "delete".to_string(),
+        "return" => "enter".to_string(),
+        "up" | "down" | "left" | "right" => format!("arrow{key}"),
+        _ => key,
+    }
+}
+
+/// A display name for a normalized key. `written` is the key as it was written
+/// in the hotkey.
+fn key_label(key: &str, written: &str) -> String {
+    match key {
+        " " => "Space".to_string(),
+        "escape" => "Escape".to_string(),
+        "delete" => "Delete".to_string(),
+        "enter" => "Enter".to_string(),
+        "arrowup" => "ArrowUp".to_string(),
+        "arrowdown" => "ArrowDown".to_string(),
+        "arrowleft" => "ArrowLeft".to_string(),
+        "arrowright" => "ArrowRight".to_string(),
+        _ if key.chars().count() == 1 => key.to_uppercase(),
+        _ => {
+            let mut chars = written.chars();
+            let first = chars.next().unwrap_or_default();
+            format!("{}{}", first.to_uppercase(), chars.as_str())
+        }
+    }
+}
+
+/// The key for a physical key code, if it's a letter or digit.
+/// The normalized key for `press`.
+///
+/// This is the character the layout produced, unless it isn't ASCII, for
+/// example because `Alt` changed it or the layout isn't latin. Then we fall
+/// back to the physical key, if it's a letter or digit.
+fn pressed_key(press: &KeyPress) -> String {
+    let key = normalize_key(&press.key);
+
+    if key.is_ascii() && !matches!(key.as_str(), "dead" | "unidentified") {
+        key
+    } else {
+        code_key(&press.code).unwrap_or(key)
+    }
+}
+
+fn code_key(code: &str) -> Option<String> {
+    code.strip_prefix("Key")
+        .or_else(|| code.strip_prefix("Digit"))
+        .filter(|key| key.len() == 1)
+        .map(str::to_lowercase)
+}
+
+fn is_symbol(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!((chars.next(), chars.next()), (Some(c), None) if !c.is_alphanumeric() && c != ' ')
+}
+
+/// How long to wait for the next key in a sequence, in milliseconds.
+const SEQUENCE_TIMEOUT: f64 = 1000.0;
+
+fn on_keydown(event: KeyboardEvent) {
+    let press = KeyPress::new(&event);
+
+    if press.is_modifier() || event.repeat() {
+        return;
+    }
+
+    let is_apple = is_apple();
+    let path = event.composed_path();
+    let in_text_field = path
+        .get(0)
+        .dyn_ref::<web_sys::Element>()
+        .is_some_and(is_text_field);
+
+    let handler = task::local::with(|local| {
+        let mut registry = local.hotkey.0.borrow_mut();
+        let registry = &mut *registry;
+
+        if registry
+            .pending
+            .last()
+            .is_some_and(|last| press.time - last.time > SEQUENCE_TIMEOUT)
+        {
+            registry.pending.clear();
+        }
+
+        registry.pending.push(press.clone());
+
+        // Find the innermost matching binding, either continuing the current sequence
+        // or starting a new one.
+        for presses in [registry.pending.clone(), vec![press]] {
+            let mut best: Option<(u32, &Entry)> = None;
+            let mut prefix = false;
+
+            for entry in &registry.bindings {
+                if in_text_field && !entry.keys.has_command_modifier() {
+                    continue;
+                }
+
+                let Some(depth) = entry.depth(&path) else {
+                    continue;
+                };
+
+                match entry.keys.matches(&presses, is_apple) {
+                    Match::None => (),
+                    Match::Prefix => prefix = true,
+                    Match::Full => {
+                        if best.is_none_or(|(best_depth, _)| depth < best_depth) {
+                            best = Some((depth, entry));
+                        }
+                    }
+                }
+            }
+
+            if let Some((_, entry)) = best {
+                let handler = entry.handler.clone();
+                registry.pending.clear();
+                return Some(handler);
+            }
+
+            if prefix {
+                registry.pending = presses;
+                return None;
+            }
+        }
+
+        registry.pending.clear();
+        None
+    });
+
+    if let Some(handler) = handler {
+        event.prevent_default();
+        handler.borrow_mut()(event);
+    }
+}
+
+fn is_text_field(element: &web_sys::Element) -> bool {
+    if element
+        .dyn_ref::<web_sys::HtmlElement>()
+        .is_some_and(|element| element.is_content_editable())
+    {
+        return true;
+    }
+
+    match element.tag_name().to_ascii_lowercase().as_str() {
+        "textarea" | "select" => true,
+        "input" => !matches!(
+            element
+                .get_attribute("type")
+                .unwrap_or_default()
+                .to_ascii_lowercase()
+                .as_str(),
+            "button" | "checkbox" | "radio" | "reset" | "submit" | "range" | "color" | "file"
+        ),
+        _ => false,
+    }
+}
+
+#[cfg_browser(true)]
+fn is_apple() -> bool {
+    use wasm_bindgen::UnwrapThrowExt;
+
+    let platform = web_sys::window()
+        .expect_throw("Window must be available")
+        .navigator()
+        .platform()
+        .unwrap_or_default();
+
+    ["Mac", "iPhone", "iPad", "iPod"]
+        .iter()
+        .any(|prefix| platform.starts_with(prefix))
+}
+
+#[cfg_browser(false)]
+fn is_apple() -> bool {
+    false
+}
+
+type Scope = Box<dyn Fn() -> Option<web_sys::Element>>;
+
+struct Entry {
+    id: u64,
+    keys: Hotkey,
+    description: String,
+    scope: Option<Scope>,
+    handler: Rc<RefCell<dyn FnMut(KeyboardEvent)>>,
+}
+
+impl Entry {
+    /// How deep in the event path is the scope element? Global bindings are
+    /// deepest, and [`None`] means the binding is out of scope.
+    fn depth(&self, path: &js_sys::Array) -> Option<u32> {
+        let Some(scope) = &self.scope else {
+            return Some(u32::MAX);
+        };
+
+        let element = scope()?;
+        let index = path.index_of(&element, 0);
+
+        (index >= 0).then_some(index as u32)
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    bindings: Vec<Entry>,
+    pending: Vec<KeyPress>,
+    next_id: u64,
+    listening: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct TaskLocal(RefCell<Registry>);
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::{Hotkey, KeyPress, Match, ParseHotkeyError};
+
+    fn press(key: &str, code: &str, modifiers: &str) -> KeyPress {
+        KeyPress {
+            key: key.to_string(),
+            code: code.to_string(),
+            ctrl: modifiers.contains('c'),
+            shift: modifiers.contains('s'),
+            alt: modifiers.contains('a'),
+            meta: modifiers.contains('m'),
+            time: 0.0,
+        }
+    }
+
+    fn hotkey(s: &str) -> Hotkey {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(hotkey("ctrl+shift+k"), hotkey("Shift+Control+K"));
+        assert_eq!(hotkey("Esc"), hotkey("Escape"));
+        assert_eq!(hotkey("g  i").0.len(), 2);
+        assert!(hotkey("Ctrl++").matches(&[press("+", "Equal", "cs")], false) == Match::Full);
+        assert_eq!(
+            "Hyper+K".parse::<Hotkey>(),
+            Err(ParseHotkeyError("unknown modifier \"Hyper\"".to_string()))
+        );
+        assert!("".parse::<Hotkey>().is_err());
+        assert!("Ctrl+".parse::<Hotkey>().is_err());
+    }
+
+    #[test]
+    fn modifiers() {
+        let ctrl_shift_k = hotkey("Ctrl+Shift+K");
+
+        assert_eq!(
+            ctrl_shift_k.matches(&[press("K", "KeyK", "cs")], false),
+            Match::Full
+        );
+        assert_eq!(
+            ctrl_shift_k.matches(&[press("k", "KeyK", "c")], false),
+            Match::None
+        );
+        assert_eq!(
+            ctrl_shift_k.matches(&[press("K", "KeyK", "csa")], false),
+            Match::None
+        );
+    }
+
+    #[test]
+    fn platform_mod() {
+        let mod_s = hotkey("Mod+S");
+
+        assert_eq!(
+            mod_s.matches(&[press("s", "KeyS", "c")], false),
+            Match::Full
+        );
+        assert_eq!(
+            mod_s.matches(&[press("s", "KeyS", "m")], false),
+            Match::None
+        );
+        assert_eq!(mod_s.matches(&[press("s", "KeyS", "m")], true), Match::Full);
+        assert_eq!(mod_s.matches(&[press("s", "KeyS", "c")], true), Match::None);
+    }
+
+    #[test]
+    fn physical_key() {
+        // `Alt+K` on a Mac layout produces a different character
+        assert_eq!(
+            hotkey("Alt+K").matches(&[press("˚", "KeyK", "a")], true),
+            Match::Full
+        );
+    }
+
+    #[test]
+    fn layout_key_before_physical_key() {
+        // The key labelled "A" on AZERTY is where "Q" is on QWERTY
+        let azerty_a = [press("a", "KeyQ", "c")];
+
+        assert_eq!(hotkey("Ctrl+A").matches(&azerty_a, false), Match::Full);
+        assert_eq!(hotkey("Ctrl+Q").matches(&azerty_a, false), Match::None);
+
+        // Non-latin layouts fall back to the physical key
+        assert_eq!(
+            hotkey("Ctrl+A").matches(&[press("ф", "KeyA", "c")], false),
+            Match::Full
+        );
+    }
+
+    #[test]
+    fn symbols_ignore_shift() {
+        assert_eq!(
+            hotkey("?").matches(&[press("?", "Slash", "s")], false),
+            Match::Full
+        );
+    }
+
+    #[test]
+    fn sequence() {
+        let g_i = hotkey("g i");
+
+        assert_eq!(g_i.matches(&[press("g", "KeyG", "")], false), Match::Prefix);
+        assert_eq!(
+            g_i.matches(&[press("g", "KeyG", ""), press("i", "KeyI", "")], false),
+            Match::Full
+        );
+        assert_eq!(
+            g_i.matches(&[press("g", "KeyG", ""), press("x", "KeyX", "")], false),
+            Match::None
+        );
+        assert_eq!(g_i.matches(&[press("i", "KeyI", "")], false), Match::None);
+    }
+
+    #[test]
+    fn display() {
+        struct Platform<'a>(&'a Hotkey, bool);
+
+        impl std::fmt::Display for Platform<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt_for(f, self.1)
+            }
+        }
+
+        let hotkey = hotkey("mod+shift+k space g");
+        assert_eq!(Platform(&hotkey, false).to_string(), "Ctrl+Shift+K Space G");
+        assert_eq!(Platform(&hotkey, true).to_string(), "Meta+Shift+K Space G");
+        assert_eq!(
+            Platform(&super::Hotkey::from_str("ctrl+up").unwrap(), false).to_string(),
+            "Ctrl+ArrowUp"
+        );
+        assert_eq!(
+            Platform(&super::Hotkey::from_str("F1").unwrap(), false).to_string(),
+            "F1"
+        );
+    }
+}
@@ -5,7 +5,14 @@
 //! initial page that non-wasm clients can view, whilst wasm-enabled clients
 //! still have a fully interactive app. See [`hydrate`] for more details on how
 //! this is done.
-use std::fmt;
+//!
+//! To find out where the existing HTML didn't match, turn on
+//! [`Diagnostics`] with [`set_diagnostics`] before hydrating.
+//!
+//! Pages that are mostly static can hydrate just their interactive parts with
+//! [`Islands`]. Subtrees that aren't needed straight away can be hydrated later
+//! with [`LazyHydration`].
+use std::{cell::Cell, fmt};
 
 use include_doc::function_body;
 use wasm_bindgen::JsCast;
@@ -13,10 +20,21 @@ use wasm_bindgen::JsCast;
 use crate::{
     document::{Document, DocumentHead},
     dom::Hydro,
+    mount_point,
     node::element::{Const, GenericElement},
 };
 
+mod island;
+mod lazy;
+
+pub use island::{island, Islands};
+pub use lazy::LazyHydration;
+
 /// Statistics about the hydration process.
+///
+/// If [`Diagnostics`] are enabled, this also records each mismatch between the
+/// existing HTML and the app. The [`Display`](fmt::Display) implementation
+/// prints a report of the stats and mismatches.
 #[derive(Default)]
 pub struct HydrationStats {
     nodes_added: u64,
@@ -24,9 +42,18 @@ pub struct HydrationStats {
     empty_text_removed: u64,
     attributes_set: u64,
     attributes_removed: u64,
+    diagnostics: Diagnostics,
+    mismatches: Vec<HydrationMismatch>,
 }
 
 impl HydrationStats {
+    pub(crate) fn new() -> Self {
+        Self {
+            diagnostics: DIAGNOSTICS.with(Cell::get),
+            ..Self::default()
+        }
+    }
+
     /// `true` if the only diffs between the existing HTML and the element were
     /// whitespace only text nodes.
     pub fn only_whitespace_diffs(&self) -> bool {
@@ -68,8 +95,34 @@ impl HydrationStats {
         self.attributes_removed
     }
 
-    pub(super) fn node_added(&mut self, _elem: &web_sys::Node) {
+    /// The mismatches found during hydration.
+    ///
+    /// This is always empty unless [`Diagnostics`] are enabled.
+    pub fn mismatches(&self) -> &[HydrationMismatch] {
+        &self.mismatches
+    }
+
+    pub(super) fn node_added(&mut self, node: &web_sys::Node) {
         self.nodes_added += 1;
+        self.record(node, || MismatchKind::NodeAdded {
+            expected: describe_node(node),
+        });
+    }
+
+    pub(super) fn text_added(&mut self, text: &web_sys::Text, existing: &web_sys::Node) {
+        self.nodes_added += 1;
+        self.record(existing, || {
+            let expected = describe_node(text);
+
+            if existing.has_type::<web_sys::Text>() {
+                MismatchKind::Text {
+                    expected,
+                    actual: describe_node(existing),
+                }
+            } else {
+                MismatchKind::NodeAdded { expected }
+            }
+        });
     }
 
     pub(super) fn node_removed(&mut self, node: &web_sys::Node) {
@@ -78,16 +131,60 @@ impl HydrationStats {
             .and_then(|t| t.text_content())
         {
             Some(text) if text.trim().is_empty() => self.empty_text_removed += 1,
-            _ => self.nodes_removed += 1,
+            _ => {
+                self.nodes_removed += 1;
+                self.record(node, || MismatchKind::NodeRemoved {
+                    actual: describe_node(node),
+                });
+            }
         }
     }
 
-    pub(super) fn attribute_set(&mut self, _elem: &web_sys::Element, _name: &str, _value: &str) {
+    pub(super) fn element_replaced(&mut self, elem: &web_sys::Element, expected_tag: &str) {
+        self.record(elem, || MismatchKind::TagReplaced {
+            expected: expected_tag.to_string(),
+            actual: elem.tag_name().to_lowercase(),
+        });
+    }
+
+    pub(super) fn attribute_set(
+        &mut self,
+        elem: &web_sys::Element,
+        name: &str,
+        value: &str,
+        existing: Option<&str>,
+    ) {
         self.attributes_set += 1;
+        self.record(elem, || MismatchKind::AttributeSet {
+            name: name.to_string(),
+            expected: value.to_string(),
+            actual: existing.map(str::to_string),
+        });
     }
 
-    pub(super) fn attribute_removed(&mut self, _elem: &web_sys::Element, _name: &str) {
+    pub(super) fn attribute_removed(&mut self, elem: &web_sys::Element, name: &str, value: &str) {
         self.attributes_removed += 1;
+        self.record(elem, || MismatchKind::AttributeRemoved {
+            name: name.to_string(),
+            actual: value.to_string(),
+        });
+    }
+
+    fn record(&mut self, node: &web_sys::Node, kind: impl FnOnce() -> MismatchKind) {
+        if self.diagnostics == Diagnostics::Off {
+            return;
+        }
+
+        let mismatch = HydrationMismatch {
+            path: css_path(node),
+            kind: kind(),
+        };
+
+        if cfg!(debug_assertions) && self.diagnostics == Diagnostics::Strict {
+            panic!("Hydration mismatch: {mismatch}");
+        }
+
+        self.mismatches.push(mismatch);
     }
 }
 
@@ -98,7 +195,235 @@ impl fmt::Display for HydrationStats {
         writeln!(f, "    nodes removed = {}", self.nodes_removed)?;
         writeln!(f, "    empty text removed = {}", self.empty_text_removed)?;
         writeln!(f, "    attributes set = {}", self.attributes_set)?;
-        writeln!(f, "    attributes removed = {}", self.attributes_removed)
+        writeln!(f, "    attributes removed = {}", self.attributes_removed)?;
+
+        if !self.mismatches.is_empty() {
+            writeln!(f, "Hydration mismatches:")?;
+
+            for mismatch in &self.mismatches {
+                writeln!(f, "{mismatch}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How much detail to record about hydration mismatches.
+///
+/// See [`set_diagnostics`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Diagnostics {
+    /// Only count mismatches in [`HydrationStats`].
+    #[default]
+    Off,
+    /// Record each mismatch in [`HydrationStats::mismatches`].
+    Record,
+    /// Panic on the first mismatch in debug builds. Release builds behave
+    /// like [`Diagnostics::Record`].
+    Strict,
+}
+
+/// Set the [`Diagnostics`] mode for any subsequent calls to [`hydrate`] or
+/// [`hydrate_in_head`] on this thread.
+///
+/// Recording mismatches has a cost, so they're [`Diagnostics::Off`] by
+/// default.
+///
+/// # Example
+///
+/// ```no_run
+#[doc = function_body!("tests/doc/hydration.rs", diagnostics_example, [])]
+/// ```
+pub fn set_diagnostics(diagnostics: Diagnostics) {
+    DIAGNOSTICS.with(|current| current.set(diagnostics))
+}
+
+/// A difference between the existing HTML and the app, found during
+/// hydration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HydrationMismatch {
+    path: String,
+    kind: MismatchKind,
+}
+
+impl HydrationMismatch {
+    /// A CSS-like path to the element where the mismatch was found.
+    ///
+    /// For example, `div#app > ul > li:nth-child(2)`. Text mismatches use the
+    /// path of the parent element.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// What didn't match, and what hydration did about it.
+    pub fn kind(&self) -> &MismatchKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for HydrationMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind.reason())?;
+
+        if let Some(expected) = self.kind.expected() {
+            write!(f, "\n    expected: {expected}")?;
+        }
+
+        if let Some(actual) = self.kind.actual() {
+            write!(f, "\n    actual: {actual}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The kind of [`HydrationMismatch`].
+///
+/// Nodes are described by their opening tag for elements, or as a quoted
+/// string for text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MismatchKind {
+    /// There was no matching node in the existing HTML, so one was added.
+    NodeAdded { expected: String },
+    /// An existing node didn't match the app, so it was removed.
+    NodeRemoved { actual: String },
+    /// An existing text node had the wrong content, so new text was added.
+    Text { expected: String, actual: String },
+    /// The element at the mount point had the wrong tag, so it was replaced.
+    TagReplaced { expected: String, actual: String },
+    /// An attribute was missing or had the wrong value.
+    AttributeSet {
+        name: String,
+        expected: String,
+        actual: Option<String>,
+    },
+    /// An existing attribute wasn't in the app, so it was removed.
+    AttributeRemoved { name: String, actual: String },
+}
+
+impl MismatchKind {
+    /// A short description of the mismatch.
+    pub fn reason(&self) -> String {
+        match self {
+            Self::NodeAdded { .. } => "missing node added".to_string(),
+            Self::NodeRemoved { .. } => "unexpected node removed".to_string(),
+            Self::Text { .. } => "text differs".to_string(),
+            Self::TagReplaced { .. } => "tag differs, element replaced".to_string(),
+            Self::AttributeSet {
+                name, actual: None, ..
+            } => format!("missing attribute `{name}` set"),
+            Self::AttributeSet { name, .. } => format!("attribute `{name}` differs"),
+            Self::AttributeRemoved { name, .. } => {
+                format!("unexpected attribute `{name}` removed")
+            }
+        }
+    }
+
+    /// What the app expected, if anything.
+    pub fn expected(&self) -> Option<&str> {
+        match self {
+            Self::NodeAdded { expected }
+            | Self::Text { expected, .. }
+            | Self::TagReplaced { expected, .. }
+            | Self::AttributeSet { expected, .. } => Some(expected),
+            Self::NodeRemoved { .. } | Self::AttributeRemoved { .. } => None,
+        }
+    }
+
+    /// What was in the existing HTML, if anything.
+    pub fn actual(&self) -> Option<&str> {
+        match self {
+            Self::NodeRemoved { actual }
+            | Self::Text { actual, .. }
+            | Self::TagReplaced { actual, .. }
+            | Self::AttributeRemoved { actual, .. } => Some(actual),
+            Self::AttributeSet { actual, .. } => actual.as_deref(),
+            Self::NodeAdded { .. } => None,
+        }
+    }
+}
+
+thread_local! {
+    static DIAGNOSTICS: Cell<Diagnostics> = const { Cell::new(Diagnostics::Off) };
+}
+
+/// Build a CSS-like path to `node`, or its parent if it's not an element.
+///
+/// The path stops at the first ancestor with an `id`, and steps through shadow
+/// roots to their host.
+fn css_path(node: &web_sys::Node) -> String {
+    let mut segments = Vec::new();
+    let mut current = node
+        .dyn_ref::<web_sys::Element>()
+        .cloned()
+        .or_else(|| node.parent_element());
+
+    while let Some(elem) = current {
+        let mut segment = elem.tag_name().to_lowercase();
+        let id = elem.id();
+
+        if !id.is_empty() {
+            segment.push('#');
+            segment.push_str(&id);
+            segments.push(segment);
+            break;
+        }
+
+        let parent = elem.parent_node();
+
+        if let Some(parent) = &parent {
+            let mut sibling = parent.first_child();
+            let mut element_count = 0;
+            let mut position = 0;
+
+            while let Some(node) = sibling {
+                if node.has_type::<web_sys::Element>() {
+                    element_count += 1;
+
+                    if node == *elem {
+                        position = element_count;
+                    }
+                }
+
+                sibling = node.next_sibling();
+            }
+
+            if element_count > 1 {
+                segment.push_str(&format!(":nth-child({position})"));
+            }
+        }
+
+        segments.push(segment);
+
+        current = match parent.and_then(|parent| parent.dyn_into::<web_sys::ShadowRoot>().ok()) {
+            Some(shadow_root) => {
+                segments.push("#shadow-root".to_string());
+                Some(shadow_root.host())
+            }
+            None => elem.parent_element(),
+        };
+    }
+
+    segments.reverse();
+    segments.join(" > ")
+}
+
+/// Describe `node` as its opening tag for elements, or as a quoted string for
+/// text.
+fn describe_node(node: &web_sys::Node) -> String {
+    if let Some(elem) = node.dyn_ref::<web_sys::Element>() {
+        let mut description = format!("<{}", elem.tag_name().to_lowercase());
+        let attributes = elem.attributes();
+
+        for attr in (0..attributes.length()).filter_map(|index| attributes.item(index)) {
+            description.push_str(&format!(r#" {}="{}""#, attr.name(), attr.value()));
+        }
+
+        description.push('>');
+        description
+    } else {
+        format!("{:?}", node.text_content().unwrap_or_default())
     }
 }
 
@@ -139,6 +464,29 @@ pub async fn hydrate(id: &str, element: impl Into<GenericElement<Hydro, Const>>)
     Hydro::mount(id, element).await
 }
 
+/// Hydrate an element once `when` is triggered.
+///
+/// This is the same as [`hydrate`], except it waits for `when` first. `element`
+/// stays reactive while it's waiting, and the existing HTML is left as it is
+/// until it's hydrated.
+///
+/// # Example
+///
+/// ```no_run
+#[doc = function_body!("tests/doc/hydration.rs", hydrate_lazy_example, [])]
+/// ```
+pub async fn hydrate_lazy(
+    id: &str,
+    element: impl Into<GenericElement<Hydro, Const>>,
+    when: LazyHydration,
+) -> HydrationStats {
+    let element = element.into();
+    let event = when.wait(&mount_point(id)).await;
+    let stats = Hydro::mount(id, element).await;
+    lazy::replay(&mount_point(id), event).await;
+    stats
+}
+
 /// Hydrate children in the document `<head>`.
 ///
 /// This will only hydrate against children in `<head>` with attribute
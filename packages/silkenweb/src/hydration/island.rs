@@ -0,0 +1,207 @@
+use std::{collections::HashMap, rc::Rc};
+
+use include_doc::function_body;
+use serde::{de::DeserializeOwned, Serialize};
+use silkenweb_base::document;
+use silkenweb_task::spawn_local;
+use wasm_bindgen::UnwrapThrowExt;
+
+use super::{lazy, HydrationStats, LazyHydration};
+use crate::{
+    document::mount_islands,
+    dom::Hydro,
+    node::element::{Const, Element, GenericElement},
+};
+
+const ISLAND_ATTRIBUTE: &str = "data-silkenweb-island";
+const ISLAND_PROPS_ATTRIBUTE: &str = "data-silkenweb-island-props";
+
+/// Mark the root of an island, for partial hydration with [`Islands`].
+///
+/// This renders `component(props)`, and adds attributes to the root element
+/// with the island's `name` and `props` serialized as JSON. Use the same
+/// `name` and `component` with [`Islands::register`] on the client.
+///
+/// # Panics
+///
+/// If `props` can't be serialized as JSON.
+pub fn island<Props, Elem>(name: &str, props: Props, component: impl FnOnce(Props) -> Elem) -> Elem
+where
+    Props: Serialize,
+    Elem: Element,
+{
+    let props_json = serde_json::to_string(&props)
+        .unwrap_or_else(|e| panic!("Couldn't serialize props for island `{name}`: {e}"));
+
+    component(props)
+        .attribute(ISLAND_ATTRIBUTE, name)
+        .attribute(ISLAND_PROPS_ATTRIBUTE, props_json)
+}
+
+/// Hydrate independent islands of interactivity in static HTML.
+///
+/// Register a constructor for each kind of island with
+/// [`Islands::register`], then call [`Islands::hydrate`]. Only the island roots
+/// marked with [`island`] on the server are hydrated, and everything outside
+/// them is left as it is. Islands nested inside another island are hydrated
+/// as part of their outer island, if it's registered.
+///
+/// Islands with no registered constructor are also left as they are, so a page
+/// only needs to register the islands it uses. Islands registered with
+/// [`Islands::register_lazy`] aren't constructed or hydrated until they're
+/// triggered.
+///
+/// # Example
+///
+/// ```no_run
+#[doc = function_body!("tests/doc/hydration.rs", islands_example, [])]
+/// ```
+#[derive(Default)]
+pub struct Islands {
+    registered: HashMap<String, Registration>,
+}
+
+struct Registration {
+    constructor: Rc<IslandConstructor>,
+    lazy: Option<LazyHydration>,
+}
+
+type IslandConstructor = dyn Fn(&str) -> Result<GenericElement<Hydro, Const>, serde_json::Error>;
+
+impl Islands {
+    /// An empty set of islands. Nothing is hydrated until islands are
+    /// registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `component` as the constructor for islands called `name`.
+    ///
+    /// `component` is called with the props deserialized from the island
+    /// root.
+    pub fn register<Props, Elem>(
+        self,
+        name: impl Into<String>,
+        component: impl Fn(Props) -> Elem + 'static,
+    ) -> Self
+    where
+        Props: DeserializeOwned,
+        Elem: Into<GenericElement<Hydro, Const>>,
+    {
+        self.insert(name.into(), component, None)
+    }
+
+    /// Register `component` for islands called `name`, and hydrate each of
+    /// them once `when` is triggered.
+    ///
+    /// See [`Islands::register`].
+    pub fn register_lazy<Props, Elem>(
+        self,
+        name: impl Into<String>,
+        when: LazyHydration,
+        component: impl Fn(Props) -> Elem + 'static,
+    ) -> Self
+    where
+        Props: DeserializeOwned,
+        Elem: Into<GenericElement<Hydro, Const>>,
+    {
+        self.insert(name.into(), component, Some(when))
+    }
+
+    /// Hydrate every registered island in the document.
+    ///
+    /// Each island root is hydrated in the same way as [`hydrate`], and the
+    /// returned [`HydrationStats`] cover all the islands that aren't lazy. Lazy
+    /// islands are hydrated in the background once they're triggered.
+    ///
+    /// # Panics
+    ///
+    /// If an island's props can't be deserialized.
+    ///
+    /// [`hydrate`]: super::hydrate
+    pub async fn hydrate(self) -> HydrationStats {
+        let island_selector = format!("[{ISLAND_ATTRIBUTE}]");
+        let mut eager = Vec::new();
+
+        for root in document::query_selector_all(&island_selector).unwrap_throw() {
+            if self.is_in_registered_island(&root, &island_selector) {
+                continue;
+            }
+
+            let Some(registration) = root
+                .get_attribute(ISLAND_ATTRIBUTE)
+                .and_then(|name| self.registered.get(&name))
+            else {
+                continue;
+            };
+
+            if let Some(when) = registration.lazy.clone() {
+                let constructor = registration.constructor.clone();
+
+                spawn_local(async move {
+                    let event = when.wait(&root).await;
+                    let element = construct(&root, &*constructor);
+                    mount_islands(vec![(root.clone(), element)]).await;
+                    lazy::replay(&root, event).await;
+                });
+            } else {
+                let element = construct(&root, &*registration.constructor);
+                eager.push((root, element));
+            }
+        }
+
+        mount_islands(eager).await
+    }
+
+    fn insert<Props, Elem>(
+        mut self,
+        name: String,
+        component: impl Fn(Props) -> Elem + 'static,
+        lazy: Option<LazyHydration>,
+    ) -> Self
+    where
+        Props: DeserializeOwned,
+        Elem: Into<GenericElement<Hydro, Const>>,
+    {
+        let constructor =
+            Rc::new(
+                move |props_json: &str| Ok(component(serde_json::from_str(props_json)?).into()),
+            );
+
+        self.registered
+            .insert(name, Registration { constructor, lazy });
+        self
+    }
+
+    fn is_in_registered_island(&self, root: &web_sys::Element, island_selector: &str) -> bool {
+        let mut ancestor = root.parent_element();
+
+        while let Some(outer) =
+            ancestor.and_then(|elem| elem.closest(island_selector).unwrap_throw())
+        {
+            if outer
+                .get_attribute(ISLAND_ATTRIBUTE)
+                .is_some_and(|name| self.registered.contains_key(&name))
+            {
+                return true;
+            }
+
+            ancestor = outer.parent_element();
+        }
+
+        false
+    }
+}
+
+/// Construct the island at `root`, with the props from its attribute.
+fn construct(
+    root: &web_sys::Element,
+    constructor: &IslandConstructor,
+) -> GenericElement<Hydro, Const> {
+    let props_json = root.get_attribute(ISLAND_PROPS_ATTRIBUTE);
+
+    constructor(props_json.as_deref().unwrap_or("null")).unwrap_or_else(|e| {
+        let name = root.get_attribute(ISLAND_ATTRIBUTE).unwrap_or_default();
+        panic!("Couldn't deserialize props for island `{name}`: {e}")
+    })
+}
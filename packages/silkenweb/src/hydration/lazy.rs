@@ -0,0 +1,322 @@
+use std::{cell::RefCell, time::Duration};
+
+use futures::channel::oneshot;
+use futures_signals::signal::SignalExt;
+use silkenweb_base::window as base_window;
+use wasm_bindgen::{prelude::Closure, JsCast, UnwrapThrowExt};
+
+use crate::{time::sleep, window::media_query};
+
+/// How long to wait before hydrating with [`LazyHydration::on_idle`] if the
+/// browser doesn't support `requestIdleCallback`.
+const IDLE_FALLBACK: Duration = Duration::from_millis(200);
+
+/// The events that trigger [`LazyHydration::on_interaction`].
+const INTERACTION_EVENTS: [&str; 2] = ["pointerdown", "focusin"];
+
+/// When to hydrate a subtree that isn't needed straight away.
+///
+/// See [`hydrate_lazy`] and [`Islands::register_lazy`].
+///
+/// [`hydrate_lazy`]: super::hydrate_lazy
+/// [`Islands::register_lazy`]: super::Islands::register_lazy
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LazyHydration(Trigger);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Trigger {
+    Visible,
+    Interaction,
+    Idle,
+    MediaQuery(String),
+}
+
+impl LazyHydration {
+    /// Hydrate when any part of the subtree scrolls into view.
+    pub fn on_visible() -> Self {
+        Self(Trigger::Visible)
+    }
+
+    /// Hydrate on the first pointer or focus event in the subtree.
+    ///
+    /// A copy of the event is dispatched once the subtree is hydrated, so the
+    /// new event handlers see it. If hydration replaced the original target,
+    /// the copy is dispatched on the element that replaced it. The copy stops
+    /// propagating at the root of the subtree, so listeners outside it don't
+    /// see the event twice.
+    pub fn on_interaction() -> Self {
+        Self(Trigger::Interaction)
+    }
+
+    /// Hydrate when the browser is idle.
+    ///
+    /// Browsers that don't support `requestIdleCallback` hydrate after a short
+    /// delay instead.
+    pub fn on_idle() -> Self {
+        Self(Trigger::Idle)
+    }
+
+    /// Hydrate once the media query `query` matches, like
+    /// `"(min-width: 768px)"`.
+    pub fn on_media_query(query: impl Into<String>) -> Self {
+        Self(Trigger::MediaQuery(query.into()))
+    }
+
+    /// Wait until the subtree at `root` should be hydrated.
+    ///
+    /// This returns the event that triggered hydration, if there was one.
+    pub(super) async fn wait(&self, root: &web_sys::Element) -> Option<Replay> {
+        match &self.0 {
+            Trigger::Visible => {
+                wait_for_visible(root).await;
+                None
+            }
+            Trigger::Interaction => Some(wait_for_interaction(root).await),
+            Trigger::Idle => {
+                wait_for_idle().await;
+                None
+            }
+            Trigger::MediaQuery(query) => {
+                media_query(query).wait_for(true).await;
+                None
+            }
+        }
+    }
+}
+
+/// An event that triggered hydration, to dispatch again in the hydrated
+/// subtree.
+pub(super) struct Replay {
+    name: &'static str,
+    event: web_sys::Event,
+    /// The element indices from the root to the original target.
+    path: Vec<u32>,
+}
+
+impl Replay {
+    fn new(root: &web_sys::Element, name: &'static str, event: web_sys::Event) -> Self {
+        let mut path = Vec::new();
+        let mut element: Option<web_sys::Element> =
+            event.target().and_then(|target| target.dyn_into().ok());
+
+        while let Some(child) = element.filter(|child| child != root) {
+            let mut index = 0;
+            let mut sibling = child.previous_element_sibling();
+
+            while let Some(previous) = sibling {
+                index += 1;
+                sibling = previous.previous_element_sibling();
+            }
+
+            path.push(index);
+            element = child.parent_element();
+        }
+
+        path.reverse();
+
+        Self { name, event, path }
+    }
+
+    /// The original target if it's still in the subtree, or the element that
+    /// replaced it.
+    ///
+    /// If the subtree has changed shape, this is the closest ancestor we can
+    /// find.
+    fn target(&self, root: &web_sys::Element) -> web_sys::Element {
+        let original: Option<web_sys::Element> = self
+            .event
+            .target()
+            .and_then(|target| target.dyn_into().ok());
+
+        if let Some(original) = original.filter(|original| root.contains(Some(original))) {
+            return original;
+        }
+
+        let mut target = root.clone();
+
+        for &index in &self.path {
+            let mut child = target.first_element_child();
+
+            for _ in 0..index {
+                child = child.and_then(|child| child.next_element_sibling());
+            }
+
+            match child {
+                Some(child) => target = child,
+                None => break,
+            }
+        }
+
+        target
+    }
+}
+
+/// Dispatch a copy of the event in the hydrated subtree at `root`, once the
+/// original dispatch has finished.
+///
+/// The copy doesn't propagate beyond `root`.
+pub(super) async fn replay(root: &web_sys::Element, replay: Option<Replay>) {
+    let Some(replay) = replay else {
+        return;
+    };
+
+    // Hydration can run between listeners for the original event, so we wait for
+    // the next task, when the original dispatch has finished.
+    sleep(Duration::ZERO).await;
+
+    if !root.is_connected() {
+        return;
+    }
+
+    let target = replay.target(root);
+    let event = copy_event(&replay.event);
+    let name = replay.name;
+    let stop_at_root = Closure::<dyn FnMut(web_sys::Event)>::new({
+        let root = root.clone();
+        let replayed = event.clone();
+
+        move |event: web_sys::Event| {
+            if event == replayed {
+                dispatch_delegated(name, &event, &root);
+                event.stop_propagation();
+            }
+        }
+    });
+    let names = [name];
+    let _stop_at_root = ListenerGuard::new(root, &names, &stop_at_root);
+
+    target.dispatch_event(&event).unwrap_throw();
+}
+
+/// Delegated handlers are called from a listener on the document root, which
+/// won't see the replayed event, so we call the handlers in the subtree here.
+#[cfg(feature = "delegated-events")]
+fn dispatch_delegated(name: &'static str, event: &web_sys::Event, root: &web_sys::Element) {
+    crate::event::delegate::dispatch_within(name, event, root)
+}
+
+#[cfg(not(feature = "delegated-events"))]
+fn dispatch_delegated(_name: &'static str, _event: &web_sys::Event, _root: &web_sys::Element) {}
+
+/// A new event with the same type and properties as `event`.
+fn copy_event(event: &web_sys::Event) -> web_sys::Event {
+    // Event constructors read their init dictionary from properties, so the event
+    // itself can be used as the init dictionary.
+    let constructor: js_sys::Function = js_sys::Reflect::get(event, &"constructor".into())
+        .unwrap_throw()
+        .unchecked_into();
+
+    js_sys::Reflect::construct(
+        &constructor,
+        &js_sys::Array::of2(&event.type_().into(), event),
+    )
+    .unwrap_throw()
+    .unchecked_into()
+}
+
+async fn wait_for_visible(root: &web_sys::Element) {
+    let (send, receive) = oneshot::channel();
+    let send = RefCell::new(Some(send));
+    let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+        let visible = entries.iter().any(|entry| {
+            entry
+                .unchecked_into::<web_sys::IntersectionObserverEntry>()
+                .is_intersecting()
+        });
+
+        if visible {
+            if let Some(send) = send.take() {
+                let _ = send.send(());
+            }
+        }
+    });
+
+    let observer = ObserverGuard(
+        web_sys::IntersectionObserver::new(callback.as_ref().unchecked_ref()).unwrap_throw(),
+    );
+    observer.0.observe(root);
+    let _ = receive.await;
+}
+
+async fn wait_for_interaction(root: &web_sys::Element) -> Replay {
+    let (send, receive) = oneshot::channel();
+    let send = RefCell::new(Some(send));
+    let callback = Closure::<dyn FnMut(web_sys::Event)>::new(move |event: web_sys::Event| {
+        if let Some(send) = send.take() {
+            let _ = send.send(event);
+        }
+    });
+    let _listeners = ListenerGuard::new(root, &INTERACTION_EVENTS, &callback);
+
+    let event = receive
+        .await
+        .expect_throw("Interaction listener dropped before it was called");
+    let name = INTERACTION_EVENTS
+        .into_iter()
+        .find(|name| *name == event.type_())
+        .expect_throw("Unexpected interaction event");
+
+    Replay::new(root, name, event)
+}
+
+async fn wait_for_idle() {
+    let (send, receive) = oneshot::channel();
+    // The browser always calls the callback, so it's freed after the call.
+    let callback = Closure::once_into_js(move || {
+        let _ = send.send(());
+    });
+
+    if base_window::request_idle_callback(callback.unchecked_ref()).is_ok() {
+        let _ = receive.await;
+    } else {
+        sleep(IDLE_FALLBACK).await;
+    }
+}
+
+/// Disconnect an observer when dropped, so its callback can be freed.
+struct ObserverGuard(web_sys::IntersectionObserver);
+
+impl Drop for ObserverGuard {
+    fn drop(&mut self) {
+        self.0.disconnect();
+    }
+}
+
+/// Remove event listeners when dropped, so their callback can be freed.
+struct ListenerGuard<'a> {
+    target: &'a web_sys::Element,
+    names: &'a [&'a str],
+    callback: &'a js_sys::Function,
+}
+
+impl<'a> ListenerGuard<'a> {
+    fn new(
+        target: &'a web_sys::Element,
+        names: &'a [&'a str],
+        callback: &'a Closure<dyn FnMut(web_sys::Event)>,
+    ) -> Self {
+        let callback = callback.as_ref().unchecked_ref();
+
+        for name in names {
+            target
+                .add_event_listener_with_callback(name, callback)
+                .unwrap_throw();
+        }
+
+        Self {
+            target,
+            names,
+            callback,
+        }
+    }
+}
+
+impl Drop for ListenerGuard<'_> {
+    fn drop(&mut self) {
+        for name in self.names {
+            self.target
+                .remove_event_listener_with_callback(name, self.callback)
+                .unwrap_throw();
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Infinite scrolling.
+//!
+//! [`InfiniteScroll`] loads pages of items into a [`MutableVec`] with an async
+//! loader. Each page comes with a cursor for the next page, or `None` when
+//! there's no more data. [`InfiniteScroll::render`] renders the items into a
+//! container, and [`InfiniteScroll::sentinel`] turns an element into a
+//! sentinel, which loads the next page as it scrolls into view. Place the
+//! sentinel after the container, so the container only holds items. The
+//! loading, error and end of data states are available as signals.
+//!
+//! For server side rendering, call [`InfiniteScroll::load_more`] and await it
+//! before rendering, so the first page is rendered statically. The sentinel is
+//! never visible on the server, so nothing else is loaded.
+//!
+//! # Example
+//!
+//! ```no_run
+#![doc = function_body!("tests/doc/infinite_scroll.rs", module_example, [])]
+//! ```
+use std::{cell::RefCell, future::Future, pin::Pin, rc::Rc};
+
+use futures_signals::{
+    map_ref,
+    signal::{Mutable, Signal, SignalExt},
+    signal_vec::{MutableVec, SignalVecExt},
+};
+use include_doc::function_body;
+
+use crate::{
+    dom::Dom,
+    node::{
+        element::{Element, ParentElement},
+        Node,
+    },
+    observer::{self, IntersectionOptions},
+};
+
+/// A page of items from an [`InfiniteScroll`] loader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    /// The cursor for the next page, or `None` if this is the last page.
+    pub next: Option<C>,
+}
+
+impl<T, C> Page<T, C> {
+    /// A page of `items`, followed by the page at `next`.
+    pub fn new(items: Vec<T>, next: Option<C>) -> Self {
+        Self { items, next }
+    }
+}
+
+/// The loading state of an [`InfiniteScroll`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status<E> {
+    /// Waiting to load the next page.
+    Idle,
+    /// A page is loading.
+    Loading,
+    /// The last page failed to load. Use [`InfiniteScroll::load_more`] to try
+    /// again.
+    Error(E),
+    /// All the pages have been loaded.
+    Done,
+}
+
+/// Load pages of items as the user scrolls.
+///
+/// See the [module level documentation](self) for more details.
+pub struct InfiniteScroll<T, C, E>(Rc<State<T, C, E>>);
+
+impl<T, C, E> Clone for InfiniteScroll<T, C, E> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+type Loader<T, C, E> = Box<dyn Fn(C) -> Pin<Box<dyn Future<Output = Result<Page<T, C>, E>>>>>;
+
+struct State<T, C, E> {
+    items: MutableVec<T>,
+    cursor: RefCell<Option<C>>,
+    load_more: Loader<T, C, E>,
+    status: Mutable<Status<E>>,
+    options: RefCell<IntersectionOptions>,
+}
+
+impl<T, C, E> InfiniteScroll<T, C, E>
+where
+    T: Clone + 'static,
+    C: Clone + 'static,
+    E: Clone + 'static,
+{
+    /// Load pages into `items`, starting at `cursor`.
+    ///
+    /// `items` can already contain items, for example if they were rendered on
+    /// the server, as long as `cursor` is for the page after them.
+    pub fn new<Fut>(items: MutableVec<T>, cursor: C, load_more: impl Fn(C) -> Fut + 'static) -> Self
+    where
+        Fut: Future<Output = Result<Page<T, C>, E>> + 'static,
+    {
+        Self(Rc::new(State {
+            items,
+            cursor: RefCell::new(Some(cursor)),
+            load_more: Box::new(move |cursor| Box::pin(load_more(cursor))),
+            status: Mutable::new(Status::Idle),
+            options: RefCell::new(IntersectionOptions::new().root_margin("0px 0px 200px 0px")),
+        }))
+    }
+
+    /// Start loading the next page when the sentinel is within `margin` of
+    /// the viewport.
+    ///
+    /// `margin` is a CSS margin. It defaults to `"0px 0px 200px 0px"`.
+    pub fn root_margin(self, margin: impl Into<String>) -> Self {
+        self.0
+            .options
+            .replace_with(|options| options.clone().root_margin(margin));
+        self
+    }
+
+    /// The loading state.
+    pub fn status(&self) -> impl Signal<Item = Status<E>> {
+        self.0.status.signal_cloned()
+    }
+
+    /// Is a page loading?
+    pub fn is_loading(&self) -> impl Signal<Item = bool> {
+        self.0
+            .status
+            .signal_ref(|status| matches!(status, Status::Loading))
+    }
+
+    /// The error from the last page, if it failed to load.
+    pub fn error(&self) -> impl Signal<Item = Option<E>> {
+        self.0.status.signal_ref(|status| match status {
+            Status::Error(e) => Some(e.clone()),
+            _ => None,
+        })
+    }
+
+    /// Have all the pages been loaded?
+    pub fn is_done(&self) -> impl Signal<Item = bool> {
+        self.0
+            .status
+            .signal_ref(|status| matches!(status, Status::Done))
+    }
+
+    /// Load the next page now.
+    ///
+    /// This does nothing if a page is already loading, or all the pages have
+    /// been loaded. It's useful for "Load more" buttons, retrying after an
+    /// error, and loading the first page on the server.
+    pub async fn load_more(&self) {
+        let state = &self.0;
+
+        if matches!(&*state.status.lock_ref(), Status::Loading | Status::Done) {
+            return;
+        }
+
+        let Some(cursor) = state.cursor.borrow().clone() else {
+            state.status.set(Status::Done);
+            return;
+        };
+
+        state.status.set(Status::Loading);
+
+        match (state.load_more)(cursor).await {
+            Ok(Page { items, next }) => {
+                let mut existing = state.items.lock_mut();
+
+                for item in items {
+                    existing.push_cloned(item);
+                }
+
+                state.status.set(if next.is_some() {
+                    Status::Idle
+                } else {
+                    Status::Done
+                });
+                state.cursor.replace(next);
+            }
+            Err(e) => state.status.set(Status::Error(e)),
+        }
+    }
+
+    /// Render the items into `container`, using `item` to render each item.
+    ///
+    /// Nothing is loaded unless there's also a [`sentinel`](Self::sentinel).
+    pub fn render<D, P, N>(&self, container: P, item: impl FnMut(T) -> N + 'static) -> P
+    where
+        D: Dom,
+        P: ParentElement<D>,
+        N: Into<Node<D>>,
+    {
+        container.children_signal(self.0.items.signal_vec_cloned().map(item))
+    }
+
+    /// Load the next page when `sentinel` scrolls into view.
+    ///
+    /// Place `sentinel` after the container the items are rendered into. It
+    /// can be empty, or show the loading state. If it's still in view once a
+    /// page has been rendered, the next page is loaded.
+    pub fn sentinel<S: Element>(&self, sentinel: S) -> S {
+        let handle = sentinel.handle();
+        let feed = self.clone();
+
+        sentinel.spawn_future(async move {
+            loop {
+                // Observe the sentinel afresh for each page. The observer reports the current
+                // intersection once the new items have been laid out, whereas an existing
+                // observation would still say the sentinel is visible.
+                let handle = handle.clone();
+                let visible = observer::intersection_signal(
+                    move || handle.try_element(),
+                    feed.0.options.borrow().clone(),
+                );
+                let should_load = map_ref! {
+                    let visible = visible,
+                    let idle = feed.0.status.signal_ref(|status| matches!(status, Status::Idle)) =>
+                    visible.is_intersecting && *idle
+                };
+
+                if should_load.wait_for(true).await.is_none() {
+                    break;
+                }
+
+                feed.load_more().await;
+            }
+        })
+    }
+}
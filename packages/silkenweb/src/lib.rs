@@ -45,6 +45,18 @@
 //! See [caniuse](https://caniuse.com/mdn-javascript_builtins_weakref) for
 //! current browser support.
 //!
+//! ## `delegated-events`
+//!
+//! Handle bubbling events, like `click` and `input`, with a single listener
+//! per event type on the document root, instead of a listener per element.
+//! This saves memory and startup time for large lists and tables. Handlers
+//! see the same event and element as they would without delegation.
+//!
+//! Listeners with non-default [`ListenerOptions`] aren't delegated. Events
+//! that don't cross shadow root boundaries, like `change`, won't reach
+//! delegated handlers inside a shadow root. This has no effect with
+//! `weak-refs`.
+//!
 //! ## `declarative-shadow-dom`
 //!
 //! Print [Declarative Shadow DOM] when server side rendering. Hydration will
@@ -73,6 +85,7 @@
 //! [Server Side Rendering]: https://github.com/silkenweb/silkenweb/tree/main/examples/ssr-full
 //! [examples]: https://github.com/silkenweb/silkenweb/tree/main/examples
 //! [Declarative Shadow DOM]: https://web.dev/declarative-shadow-dom/
+//! [`ListenerOptions`]: crate::event::ListenerOptions
 
 #[doc(inline)]
 pub use clonelet::clone;
@@ -326,20 +339,31 @@ pub use wasm_rs_dbg::dbg;
 #[macro_use]
 pub mod macros;
 
-mod event;
-
 pub mod animation;
 pub mod attribute;
+pub mod context;
 pub mod document;
 pub mod dom;
 pub mod elements;
+pub mod event;
+pub mod form;
+pub mod gesture;
+pub mod hotkey;
 pub mod hydration;
+pub mod infinite_scroll;
 pub mod node;
+pub mod observer;
 pub mod property;
+pub mod query;
 pub mod router;
+pub mod serialize;
+pub mod sortable;
+pub mod state_transfer;
 pub mod storage;
+pub mod streaming;
 pub mod task;
 pub mod time;
+pub mod virtual_list;
 pub mod window;
 
 pub use futures_signals;
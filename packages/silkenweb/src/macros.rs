@@ -9,6 +9,18 @@ pub use web_sys;
 
 pub use crate::intern_str;
 
+/// The element an event handler was added to.
+///
+/// This is `event.currentTarget`, unless the event has been delegated.
+pub fn current_target(event: &JsValue) -> Option<web_sys::EventTarget> {
+    #[cfg(feature = "delegated-events")]
+    if let Some(element) = crate::event::delegate::current_target(event) {
+        return Some(element.into());
+    }
+
+    event.unchecked_ref::<web_sys::Event>().current_target()
+}
+
 /// Define a custom html element.
 ///
 /// This will define a struct for an html element, with a method for each
@@ -371,12 +383,25 @@ macro_rules! dom_element {
                 Self(self.0.spawn_future(future))
             }
 
-            fn on(
+            fn on_with_options(
                 self,
                 name: &'static str,
+                options: $crate::event::ListenerOptions,
                 f: impl FnMut($crate::macros::JsValue) + 'static
             ) -> Self {
-                Self($crate::node::element::Element::on(self.0, name, f))
+                Self($crate::node::element::Element::on_with_options(self.0, name, options, f))
+            }
+
+            fn on_mount(self, mut f: impl FnMut(&Self::DomElement) + 'static) -> Self {
+                Self(self.0.on_mount(move |elem| {
+                    f($crate::macros::UnwrapThrowExt::unwrap_throw($crate::macros::JsCast::dyn_ref(elem)))
+                }))
+            }
+
+            fn on_unmount(self, mut f: impl FnMut(&Self::DomElement) + 'static) -> Self {
+                Self(self.0.on_unmount(move |elem| {
+                    f($crate::macros::UnwrapThrowExt::unwrap_throw($crate::macros::JsCast::dyn_ref(elem)))
+                }))
             }
         }
 
@@ -427,6 +452,42 @@ macro_rules! dom_element {
             }
         }
 
+        impl $crate::query::QuerySelector
+        for $camel_name<$crate::dom::Dry, $crate::node::element::Const>
+        {
+            fn element_ref(&self) -> ::std::option::Option<$crate::query::ElementRef> {
+                self.0.element_ref()
+            }
+        }
+
+        impl $crate::serialize::ToHtml
+        for $camel_name<$crate::dom::Dry, $crate::node::element::Const>
+        {
+            fn write_html<W: $crate::serialize::HtmlWrite>(
+                &self,
+                w: &mut W
+            ) -> ::std::result::Result<(), W::Error> {
+                self.0.write_html(w)
+            }
+
+            fn write_pretty_html<W: $crate::serialize::HtmlWrite>(
+                &self,
+                printer: &$crate::serialize::PrettyPrinter,
+                depth: usize,
+                w: &mut W
+            ) -> ::std::result::Result<(), W::Error> {
+                self.0.write_pretty_html(printer, depth, w)
+            }
+
+            fn write_xml<W: $crate::serialize::HtmlWrite>(
+                &self,
+                parent_namespace: ::std::option::Option<&$crate::node::element::Namespace>,
+                w: &mut W
+            ) -> ::std::result::Result<(), W::Error> {
+                self.0.write_xml(parent_namespace, w)
+            }
+        }
+
         impl<InitParam, Dom> $camel_name<
             $crate::dom::Template<InitParam, Dom>,
             $crate::node::element::Const,
@@ -786,20 +847,32 @@ macro_rules! events {
             $(#[$event_meta])*
             $visiblity fn [<on_ $name >] (
                 self,
+                f: impl FnMut($event_type, $elem_type) + 'static
+            ) -> Self {
+                self.[<on_ $name _with_options>]($crate::event::ListenerOptions::default(), f)
+            }
+
+            $(#[$event_meta])*
+            ///
+            /// The listener is added with `options`.
+            $visiblity fn [<on_ $name _with_options>] (
+                self,
+                options: $crate::event::ListenerOptions,
                 mut f: impl FnMut($event_type, $elem_type) + 'static
             ) -> Self {
-                $crate::node::element::Element::on(
+                $crate::node::element::Element::on_with_options(
                     self,
                     $crate::text_name_intern!($name),
+                    options,
                     move |js_ev| {
                         use $crate::macros::JsCast;
                         // I *think* we can assume event and event.current_target aren't null
-                        let event: $event_type = js_ev.into();
                         let target: $elem_type =
                             $crate::macros::UnwrapThrowExt::unwrap_throw(
-                                event.current_target()
+                                $crate::macros::current_target(&js_ev)
                             )
                             .unchecked_into();
+                        let event: $event_type = js_ev.into();
                         f(event, target);
                     }
                 )
@@ -6,9 +6,14 @@ use discard::DiscardOnDrop;
 use futures_signals::CancelableFutureHandle;
 use silkenweb_signals_ext::value::Value;
 
-use crate::dom::{
-    private::{DomText, EventStore},
-    DefaultDom, Dom, InDom,
+use crate::{
+    dom::{
+        private::{DomText, EventStore},
+        DefaultDom, Dom, Dry, InDom,
+    },
+    node::element::Namespace,
+    query::{ElementRef, QuerySelector},
+    serialize::{HtmlWrite, PrettyPrinter, ToHtml},
 };
 
 mod component;
@@ -25,6 +30,12 @@ pub struct Node<D: Dom = DefaultDom> {
     node: D::Node,
 }
 
+impl<D: Dom> Node<D> {
+    pub(crate) fn dom_node(&self) -> &D::Node {
+        &self.node
+    }
+}
+
 impl<D: Dom> Value for Node<D> {}
 
 impl<D: Dom> InDom for Node<D> {
@@ -47,6 +58,35 @@ impl<D: Dom> fmt::Display for Node<D> {
     }
 }
 
+impl QuerySelector for Node<Dry> {
+    fn element_ref(&self) -> Option<ElementRef> {
+        ElementRef::from_node(self.dom_node())
+    }
+}
+
+impl ToHtml for Node<Dry> {
+    fn write_html<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        self.node.write_html(w)
+    }
+
+    fn write_pretty_html<W: HtmlWrite>(
+        &self,
+        printer: &PrettyPrinter,
+        depth: usize,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        self.node.write_pretty_html(printer, depth, w)
+    }
+
+    fn write_xml<W: HtmlWrite>(
+        &self,
+        parent_namespace: Option<&Namespace>,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        self.node.write_xml(parent_namespace, w)
+    }
+}
+
 /// Trait alias for nodes that can be used as a child
 pub trait ChildNode<D: Dom = DefaultDom>: Into<Node<D>> + Value + 'static {}
 
@@ -2,10 +2,13 @@
 #[cfg(debug_assertions)]
 use std::collections::HashSet;
 use std::{
-    self, fmt,
-    future::Future,
+    self,
+    cell::RefCell,
+    fmt,
+    future::{self, Future},
     marker::PhantomData,
     pin::{pin, Pin},
+    rc::Rc,
 };
 
 use discard::DiscardOnDrop;
@@ -19,25 +22,33 @@ use futures_signals::{
 use include_doc::function_body;
 use silkenweb_base::document;
 use silkenweb_signals_ext::value::{Executor, RefSignalOrValue, SignalOrValue, Value};
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
 
-use self::child_vec::{ChildVec, ParentUnique};
+use self::{
+    child_vec::{ChildVec, ParentUnique},
+    lifecycle::Lifecycle,
+};
 use super::{ChildNode, Node, Resource};
 use crate::{
     attribute::Attribute,
     clone,
     dom::{
         private::{DomElement, DomText, EventStore, InstantiableDomElement},
-        DefaultDom, Dom, Hydro, InDom, InstantiableDom, Template, Wet,
+        DefaultDom, Dom, Dry, Hydro, InDom, InstantiableDom, Template, Wet,
     },
     empty_str,
+    event::{DispatchOptions, ListenerOptions, TypedCustomEvent, TypedEvent},
     hydration::HydrationStats,
     intern_str,
     node::text,
+    observer::{self, Intersection, IntersectionOptions, ResizeBox, Size},
+    query::{ElementRef, QuerySelector},
+    serialize::{HtmlWrite, PrettyPrinter, ToHtml},
     task,
 };
 
 pub(crate) mod child_vec;
+pub(crate) mod lifecycle;
 
 /// A generic HTML element.
 ///
@@ -86,6 +97,29 @@ impl<D: Dom> GenericElement<D> {
         self
     }
 
+    /// Mark the `<option>` children that match `value` as `selected`.
+    ///
+    /// See [`DomElement::select_option`].
+    pub(crate) fn select_option_signal(
+        mut self,
+        value: impl Signal<Item = String> + 'static,
+    ) -> Self {
+        clone!(mut self.element);
+
+        self.spawn(value.for_each(move |value| {
+            element.select_option(&value);
+            async {}
+        }));
+
+        self
+    }
+
+    fn lifecycle(mut self, lifecycle: Lifecycle) -> Self {
+        self.element.effect(lifecycle.register());
+        self.resources.push(Resource::Any(Box::new(lifecycle)));
+        self
+    }
+
     pub(crate) fn from_dom(element: D::Element, static_child_count: usize) -> Self {
         Self {
             static_child_count,
@@ -525,10 +559,23 @@ impl<D: Dom> Element for GenericElement<D> {
         self
     }
 
-    fn on(mut self, name: &'static str, f: impl FnMut(JsValue) + 'static) -> Self {
-        self.element.on(name, f, &mut self.events);
+    fn on_with_options(
+        mut self,
+        name: &'static str,
+        options: ListenerOptions,
+        f: impl FnMut(JsValue) + 'static,
+    ) -> Self {
+        self.element.on(name, options, f, &mut self.events);
         self
     }
+
+    fn on_mount(self, f: impl FnMut(&Self::DomElement) + 'static) -> Self {
+        self.lifecycle(Lifecycle::new(Some(Box::new(f)), None))
+    }
+
+    fn on_unmount(self, f: impl FnMut(&Self::DomElement) + 'static) -> Self {
+        self.lifecycle(Lifecycle::new(None, Some(Box::new(f))))
+    }
 }
 
 impl<D: Dom> Executor for GenericElement<D> {
@@ -719,7 +766,119 @@ pub trait Element: Sized {
     /// javascript `Event` object.
     ///
     /// [MDN Events]: https://developer.mozilla.org/en-US/docs/Web/Events
-    fn on(self, name: &'static str, f: impl FnMut(JsValue) + 'static) -> Self;
+    fn on(self, name: &'static str, f: impl FnMut(JsValue) + 'static) -> Self {
+        self.on_with_options(name, ListenerOptions::default(), f)
+    }
+
+    /// Register an event handler with [`ListenerOptions`].
+    ///
+    /// See [`Self::on`]. The listener is removed when the element is dropped,
+    /// or earlier if `options` say so. Options have no effect on [`Dry`]
+    /// elements, as they have no listeners.
+    ///
+    /// [`Dry`]: crate::dom::Dry
+    fn on_with_options(
+        self,
+        name: &'static str,
+        options: ListenerOptions,
+        f: impl FnMut(JsValue) + 'static,
+    ) -> Self;
+
+    /// Call `f` each time the element is attached to the document.
+    ///
+    /// `f` is called from the render queue, after Silkenweb has attached the
+    /// element, so it's safe to measure or focus it, or to set up third party
+    /// widgets. [`Hydro`] elements are mounted once they've been hydrated.
+    /// Only changes that Silkenweb makes to the DOM are noticed.
+    ///
+    /// `f` is never called for [`Dry`] elements, or if the element is dropped
+    /// before it's attached.
+    ///
+    /// [`Dry`]: crate::dom::Dry
+    fn on_mount(self, f: impl FnMut(&Self::DomElement) + 'static) -> Self;
+
+    /// Call `f` each time the element is detached from the document, after
+    /// it's been mounted.
+    ///
+    /// `f` is also called if the element is dropped while it's mounted, even
+    /// if it's still in the document, so each mount is paired with an
+    /// unmount. It's called before the element's other resources are dropped.
+    /// See [`Self::on_mount`].
+    fn on_unmount(self, f: impl FnMut(&Self::DomElement) + 'static) -> Self;
+
+    /// Register a handler for a [`TypedEvent`].
+    ///
+    /// Events with a detail that doesn't deserialize as an `E` are ignored.
+    /// See the [`event`] module for an example.
+    ///
+    /// [`event`]: crate::event
+    fn on_custom<E: TypedEvent>(
+        self,
+        mut f: impl FnMut(TypedCustomEvent<E>, Self::DomElement) + 'static,
+    ) -> Self {
+        self.on(E::NAME, move |js_ev| {
+            let target: Self::DomElement = crate::macros::current_target(&js_ev)
+                .unwrap_throw()
+                .unchecked_into();
+
+            if let Some(event) = TypedCustomEvent::from_event(js_ev.unchecked_into()) {
+                f(event, target);
+            }
+        })
+    }
+
+    /// Call `f` when the element's intersection with the viewport changes.
+    ///
+    /// `f` is called with the current intersection once the element is
+    /// observed. See the [`observer`] module for details.
+    ///
+    /// [`observer`]: crate::observer
+    fn on_intersection(
+        self,
+        options: IntersectionOptions,
+        f: impl FnMut(Intersection) + 'static,
+    ) -> Self {
+        observe_with(self, move |element| {
+            observer::observe_intersection(element, &options, f)
+        })
+    }
+
+    /// A signal of the element's intersection with the viewport.
+    ///
+    /// The element is observed until the signal is dropped. See the
+    /// [`observer`] module for details.
+    ///
+    /// [`observer`]: crate::observer
+    fn intersection_signal(
+        &self,
+        options: IntersectionOptions,
+    ) -> impl Signal<Item = Intersection> + 'static {
+        let handle = self.handle();
+        observer::intersection_signal(move || handle.try_element(), options)
+    }
+
+    /// Call `f` when the element's size changes.
+    ///
+    /// `f` is called with the current size once the element is observed. See
+    /// the [`observer`] module for details.
+    ///
+    /// [`observer`]: crate::observer
+    fn on_resize(self, resize_box: ResizeBox, f: impl FnMut(Size) + 'static) -> Self {
+        observe_with(self, move |element| {
+            observer::observe_resize(element, resize_box, f)
+        })
+    }
+
+    /// A signal of the element's size.
+    ///
+    /// The element is observed until the signal is dropped. See the
+    /// [`observer`] module for details.
+    ///
+    /// [`observer`]: crate::observer
+    fn size_signal(&self, resize_box: ResizeBox) -> impl Signal<Item = Size> + 'static {
+        let handle = self.handle();
+        observer::size_signal(move || handle.try_element(), resize_box)
+    }
 }
 
 /// An element that can have text children.
@@ -823,6 +982,35 @@ where
     }
 }
 
+impl QuerySelector for GenericElement<Dry, Const> {
+    fn element_ref(&self) -> Option<ElementRef> {
+        Some(ElementRef::root(self.element.clone()))
+    }
+}
+
+impl ToHtml for GenericElement<Dry, Const> {
+    fn write_html<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        self.element.write_html(w)
+    }
+
+    fn write_pretty_html<W: HtmlWrite>(
+        &self,
+        printer: &PrettyPrinter,
+        depth: usize,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        self.element.write_pretty_html(printer, depth, w)
+    }
+
+    fn write_xml<W: HtmlWrite>(
+        &self,
+        parent_namespace: Option<&Namespace>,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        self.element.write_xml(parent_namespace, w)
+    }
+}
+
 impl<Param, D> GenericElement<Template<Param, D>, Const>
 where
     D: InstantiableDom,
@@ -836,7 +1024,7 @@ where
     }
 }
 
-fn spawn_cancelable_future(
+pub(crate) fn spawn_cancelable_future(
     future: impl Future<Output = ()> + 'static,
 ) -> DiscardOnDrop<CancelableFutureHandle> {
     let (handle, cancelable_future) = cancelable_future(future, || ());
@@ -846,6 +1034,26 @@ fn spawn_cancelable_future(
     handle
 }
 
+/// Observe an element until it's dropped.
+fn observe_with<E: Element>(
+    element: E,
+    observe: impl FnOnce(&web_sys::Element) -> observer::Registration + 'static,
+) -> E {
+    let registration = Rc::new(RefCell::new(None));
+
+    element
+        .effect({
+            clone!(registration);
+            move |element| {
+                registration.replace(Some(observe(element.unchecked_ref())));
+            }
+        })
+        .spawn_future(async move {
+            let _registration = registration;
+            future::pending().await
+        })
+}
+
 /// A handle to an element in the DOM.
 ///
 /// The handle will only be valid for [`Wet`]  DOM elements, so the methods
@@ -860,6 +1068,12 @@ impl<D: Dom, DomElement> Clone for ElementHandle<D, DomElement> {
     }
 }
 
+impl<D: Dom, DomElement> ElementHandle<D, DomElement> {
+    pub(crate) fn try_element(&self) -> Option<web_sys::Element> {
+        self.0.try_dom_element()
+    }
+}
+
 impl<D: Dom, DomElement: JsCast + Clone> ElementHandle<D, DomElement> {
     /// Get the associated DOM element, if it is a [`Wet`] element.
     ///
@@ -880,6 +1094,32 @@ impl<D: Dom, DomElement: JsCast + Clone> ElementHandle<D, DomElement> {
     pub fn dom_element(&self) -> DomElement {
         self.0.dom_element().dyn_into().unwrap()
     }
+
+    /// Dispatch a [`TypedEvent`] from this element with default
+    /// [`DispatchOptions`].
+    ///
+    /// See [`Self::dispatch_with_options`].
+    pub fn dispatch<E: TypedEvent>(&self, detail: &E) -> bool {
+        self.dispatch_with_options(detail, DispatchOptions::default())
+    }
+
+    /// Dispatch a [`TypedEvent`] from this element.
+    ///
+    /// Returns `false` if the event was cancelable and a listener called
+    /// `prevent_default`. If the element isn't [`Wet`] or a hydrated [`Hydro`]
+    /// element, there's nothing to dispatch from, so this does nothing and
+    /// returns `true`.
+    pub fn dispatch_with_options<E: TypedEvent>(
+        &self,
+        detail: &E,
+        options: DispatchOptions,
+    ) -> bool {
+        self.0.try_dom_element().is_none_or(|element| {
+            element
+                .dispatch_event(&options.new_event(detail))
+                .unwrap_throw()
+        })
+    }
 }
 
 impl<D: Dom> ElementHandle<D, web_sys::Element> {
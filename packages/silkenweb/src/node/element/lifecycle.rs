@@ -0,0 +1,153 @@
+//! Mount and unmount notifications, for [`Element::on_mount`] and
+//! [`Element::on_unmount`].
+//!
+//! An element registers once its effects have run, and it's a live DOM
+//! element. Each time Silkenweb inserts or removes DOM nodes, or hydrates a
+//! tree, a single check is queued on the render queue. The check compares each
+//! registered element's `isConnected` with whether it was last seen mounted,
+//! and calls the hooks for any that have changed. Nothing is queued when
+//! nothing is registered, and nothing is polled while the DOM isn't changing.
+//!
+//! Anything else that needs to know when the DOM changes can register a
+//! [`DomWatcher`] with [`watch`].
+//!
+//! Elements are usually dropped as soon as they're removed, before the check
+//! runs, so a mounted element is unmounted when it's dropped, whether or not
+//! it's still attached.
+//!
+//! [`Element::on_mount`]: super::Element::on_mount
+//! [`Element::on_unmount`]: super::Element::on_unmount
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use silkenweb_macros::cfg_browser;
+
+use crate::task;
+
+pub(crate) type Hook = Box<dyn FnMut(&web_sys::Element)>;
+
+/// The mount and unmount hooks for an element.
+///
+/// This is owned by the element, so the hooks are dropped with it.
+pub(crate) struct Lifecycle(Rc<State>);
+
+impl Lifecycle {
+    pub fn new(on_mount: Option<Hook>, on_unmount: Option<Hook>) -> Self {
+        Self(Rc::new(State {
+            element: RefCell::new(None),
+            mounted: Cell::new(false),
+            on_mount: RefCell::new(on_mount),
+            on_unmount: RefCell::new(on_unmount),
+        }))
+    }
+
+    /// An effect that registers the live DOM element.
+    pub fn register(&self) -> impl FnOnce(&web_sys::Element) + 'static {
+        let state = Rc::downgrade(&self.0);
+
+        move |element| {
+            if let Some(state) = state.upgrade() {
+                state.element.replace(Some(element.clone()));
+                let watcher: Rc<dyn DomWatcher> = state;
+                watch(&watcher);
+                watcher.dom_changed();
+            }
+        }
+    }
+}
+
+impl Drop for Lifecycle {
+    fn drop(&mut self) {
+        let element = self.0.element.borrow().clone();
+
+        if let Some(element) = element {
+            if self.0.mounted.get() {
+                self.0.set_mounted(&element, false);
+            }
+        }
+    }
+}
+
+/// Something that's notified, on the render queue, after Silkenweb has changed
+/// the DOM.
+pub(crate) trait DomWatcher {
+    fn dom_changed(&self);
+}
+
+/// Notify `watcher` after DOM changes, until it's dropped.
+#[cfg_browser(true)]
+pub(crate) fn watch(watcher: &Rc<dyn DomWatcher>) {
+    REGISTERED.with(|registered| registered.borrow_mut().push(Rc::downgrade(watcher)));
+}
+
+/// There's no live DOM to change on the server.
+#[cfg_browser(false)]
+pub(crate) fn watch(_watcher: &Rc<dyn DomWatcher>) {}
+
+/// Queue a check of registered elements, as nodes have been inserted or
+/// removed.
+pub(crate) fn dom_changed() {
+    let has_registered = REGISTERED.with(|registered| !registered.borrow().is_empty());
+
+    if has_registered && !CHECK_PENDING.replace(true) {
+        task::on_animation_frame(check_registered);
+    }
+}
+
+fn check_registered() {
+    CHECK_PENDING.set(false);
+
+    let registered: Vec<Rc<dyn DomWatcher>> = REGISTERED.with(|registered| {
+        let mut registered = registered.borrow_mut();
+        registered.retain(|state| state.strong_count() > 0);
+        registered.iter().filter_map(Weak::upgrade).collect()
+    });
+
+    // Hooks can change the DOM, so we don't hold any borrows while calling them.
+    for watcher in registered {
+        watcher.dom_changed();
+    }
+}
+
+struct State {
+    element: RefCell<Option<web_sys::Element>>,
+    mounted: Cell<bool>,
+    on_mount: RefCell<Option<Hook>>,
+    on_unmount: RefCell<Option<Hook>>,
+}
+
+impl DomWatcher for State {
+    fn dom_changed(&self) {
+        let element = self.element.borrow().clone();
+
+        if let Some(element) = element {
+            let connected = element.is_connected();
+
+            if connected != self.mounted.get() {
+                self.set_mounted(&element, connected);
+            }
+        }
+    }
+}
+
+impl State {
+    fn set_mounted(&self, element: &web_sys::Element, mounted: bool) {
+        self.mounted.set(mounted);
+        let hook = if mounted {
+            &self.on_mount
+        } else {
+            &self.on_unmount
+        };
+
+        if let Some(hook) = hook.borrow_mut().as_mut() {
+            hook(element);
+        }
+    }
+}
+
+thread_local! {
+    static REGISTERED: RefCell<Vec<Weak<dyn DomWatcher>>> = const { RefCell::new(Vec::new()) };
+    static CHECK_PENDING: Cell<bool> = const { Cell::new(false) };
+}
@@ -0,0 +1,512 @@
+//! Intersection and resize observers.
+//!
+//! [`Element::on_intersection`] and [`Element::intersection_signal`] report how
+//! much of an element is visible in the viewport, using an
+//! [`IntersectionObserver`]. [`Element::on_resize`] and
+//! [`Element::size_signal`] report the size of an element, using a
+//! [`ResizeObserver`].
+//!
+//! Elements observed with the same options share an observer. An element is
+//! unobserved when it's dropped or, for the signals, when the signal is
+//! dropped.
+//!
+//! Only elements in the browser can be observed. Callbacks are never called for
+//! [`Dry`] elements, and signals stay at their default values: not
+//! intersecting and zero size. [`Hydro`] elements are observed once they've
+//! been hydrated.
+//!
+//! [`Element::on_intersection`]: crate::node::element::Element::on_intersection
+//! [`Element::intersection_signal`]: crate::node::element::Element::intersection_signal
+//! [`Element::on_resize`]: crate::node::element::Element::on_resize
+//! [`Element::size_signal`]: crate::node::element::Element::size_signal
+//! [`IntersectionObserver`]: https://developer.mozilla.org/en-US/docs/Web/API/IntersectionObserver
+//! [`ResizeObserver`]: https://developer.mozilla.org/en-US/docs/Web/API/ResizeObserver
+//! [`Dry`]: crate::dom::Dry
+//! [`Hydro`]: crate::dom::Hydro
+//!
+//! # Example
+//!
+//! ```no_run
+#![doc = function_body!("tests/doc/observer.rs", module_example, [])]
+//! ```
+use std::{cell::RefCell, rc::Rc};
+
+use futures_signals::signal::{Mutable, Signal, SignalExt};
+use include_doc::function_body;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
+
+use crate::node::element::lifecycle::{self, DomWatcher};
+
+/// Options for [`Element::on_intersection`] and
+/// [`Element::intersection_signal`].
+///
+/// [`Element::on_intersection`]: crate::node::element::Element::on_intersection
+/// [`Element::intersection_signal`]: crate::node::element::Element::intersection_signal
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntersectionOptions {
+    root_margin: String,
+    thresholds: Vec<f64>,
+}
+
+impl IntersectionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grow or shrink the viewport by a CSS margin, like `"200px 0px"`.
+    ///
+    /// Defaults to `"0px"`.
+    pub fn root_margin(mut self, margin: impl Into<String>) -> Self {
+        self.root_margin = margin.into();
+        self
+    }
+
+    /// Report a change each time the visible ratio crosses one of
+    /// `thresholds`.
+    ///
+    /// Defaults to `[0.0]`, so a change is reported when the element starts or
+    /// stops being visible.
+    pub fn thresholds(mut self, thresholds: impl IntoIterator<Item = f64>) -> Self {
+        self.thresholds = thresholds.into_iter().collect();
+        self
+    }
+}
+
+impl Default for IntersectionOptions {
+    fn default() -> Self {
+        Self {
+            root_margin: "0px".to_string(),
+            thresholds: vec![0.0],
+        }
+    }
+}
+
+/// A rectangle in CSS pixels, relative to the viewport.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl From<web_sys::DomRectReadOnly> for Rect {
+    fn from(rect: web_sys::DomRectReadOnly) -> Self {
+        Self {
+            x: rect.x(),
+            y: rect.y(),
+            width: rect.width(),
+            height: rect.height(),
+        }
+    }
+}
+
+/// How an element intersects the viewport.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Intersection {
+    pub is_intersecting: bool,
+    /// How much of the element is visible, from `0.0` to `1.0`.
+    pub ratio: f64,
+    /// The bounds of the element.
+    pub bounds: Rect,
+    /// The visible part of the element.
+    pub intersection: Rect,
+    /// The bounds of the viewport, including the root margin.
+    ///
+    /// This is `None` for cross origin iframes.
+    pub root_bounds: Option<Rect>,
+    /// When the change happened, as a `DOMHighResTimeStamp`.
+    pub time: f64,
+}
+
+impl From<web_sys::IntersectionObserverEntry> for Intersection {
+    fn from(entry: web_sys::IntersectionObserverEntry) -> Self {
+        Self {
+            is_intersecting: entry.is_intersecting(),
+            ratio: entry.intersection_ratio(),
+            bounds: entry.bounding_client_rect().into(),
+            intersection: entry.intersection_rect().into(),
+            root_bounds: entry.root_bounds().map(Rect::from),
+            time: entry.time(),
+        }
+    }
+}
+
+/// Which box to measure with [`Element::on_resize`] and
+/// [`Element::size_signal`].
+///
+/// [`Element::on_resize`]: crate::node::element::Element::on_resize
+/// [`Element::size_signal`]: crate::node::element::Element::size_signal
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ResizeBox {
+    /// The content box, excluding padding and borders.
+    #[default]
+    Content,
+    /// The border box, including padding and borders.
+    Border,
+    /// The content box, in device pixels.
+    DevicePixelContent,
+}
+
+/// The size of an element.
+///
+/// The inline size is reported as the width and the block size as the height,
+/// so these will be swapped for vertical writing modes.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Size {
+    fn from_entry(entry: &web_sys::ResizeObserverEntry, resize_box: ResizeBox) -> Self {
+        let sizes = match resize_box {
+            ResizeBox::Content => entry.content_box_size(),
+            ResizeBox::Border => entry.border_box_size(),
+            ResizeBox::DevicePixelContent => entry.device_pixel_content_box_size(),
+        };
+
+        // Elements are never fragmented into more than one box at the moment.
+        let size: web_sys::ResizeObserverSize = sizes.get(0).unchecked_into();
+
+        Self {
+            width: size.inline_size(),
+            height: size.block_size(),
+        }
+    }
+}
+
+pub(crate) fn observe_intersection(
+    element: &web_sys::Element,
+    options: &IntersectionOptions,
+    mut f: impl FnMut(Intersection) + 'static,
+) -> Registration {
+    observe::<web_sys::IntersectionObserver>(element, options, move |entry| f(entry.clone().into()))
+}
+
+pub(crate) fn observe_resize(
+    element: &web_sys::Element,
+    resize_box: ResizeBox,
+    mut f: impl FnMut(Size) + 'static,
+) -> Registration {
+    observe::<web_sys::ResizeObserver>(element, &resize_box, move |entry| {
+        f(Size::from_entry(entry, resize_box))
+    })
+}
+
+pub(crate) fn intersection_signal(
+    element: impl Fn() -> Option<web_sys::Element> + 'static,
+    options: IntersectionOptions,
+) -> impl Signal<Item = Intersection> {
+    observed_signal(element, move |element, f| {
+        observe_intersection(element, &options, f)
+    })
+}
+
+pub(crate) fn size_signal(
+    element: impl Fn() -> Option<web_sys::Element> + 'static,
+    resize_box: ResizeBox,
+) -> impl Signal<Item = Size> {
+    observed_signal(element, move |element, f| {
+        observe_resize(element, resize_box, f)
+    })
+}
+
+/// A signal of observed values, which stops observing when it's dropped.
+fn observed_signal<T, Observe>(
+    element: impl Fn() -> Option<web_sys::Element> + 'static,
+    observe: Observe,
+) -> impl Signal<Item = T>
+where
+    T: Copy + Default + 'static,
+    Observe: FnOnce(&web_sys::Element, Box<dyn FnMut(T)>) -> Registration + 'static,
+{
+    let value = Mutable::new(T::default());
+    let pending = when_available(element, {
+        let value = value.clone();
+        move |element| observe(element, Box::new(move |new_value| value.set(new_value)))
+    });
+
+    value.signal().map(move |value| {
+        let _pending = &pending;
+        value
+    })
+}
+
+/// Start observing once the DOM element is available, which for [`Hydro`]
+/// elements is after hydration.
+///
+/// If the element isn't available yet, this is checked again each time
+/// Silkenweb changes the DOM, until the returned value is dropped.
+///
+/// [`Hydro`]: crate::dom::Hydro
+fn when_available(
+    element: impl Fn() -> Option<web_sys::Element> + 'static,
+    observe: impl FnOnce(&web_sys::Element) -> Registration + 'static,
+) -> Rc<dyn DomWatcher> {
+    let pending: Rc<dyn DomWatcher> = Rc::new(PendingObservation {
+        element: Box::new(element),
+        observe: RefCell::new(Some(Box::new(observe))),
+        registration: RefCell::new(None),
+    });
+
+    pending.dom_changed();
+    lifecycle::watch(&pending);
+    pending
+}
+
+type Observe = Box<dyn FnOnce(&web_sys::Element) -> Registration>;
+
+struct PendingObservation {
+    element: Box<dyn Fn() -> Option<web_sys::Element>>,
+    observe: RefCell<Option<Observe>>,
+    registration: RefCell<Option<Registration>>,
+}
+
+impl DomWatcher for PendingObservation {
+    fn dom_changed(&self) {
+        if self.registration.borrow().is_some() {
+            return;
+        }
+
+        if let Some(element) = (self.element)() {
+            if let Some(observe) = self.observe.take() {
+                self.registration.replace(Some(observe(&element)));
+            }
+        }
+    }
+}
+
+/// Keeps an element observed until it's dropped.
+pub(crate) struct Registration {
+    id: u64,
+    unregister: fn(u64),
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        (self.unregister)(self.id)
+    }
+}
+
+fn observe<O: Observer>(
+    element: &web_sys::Element,
+    options: &O::Options,
+    f: impl FnMut(&O::Entry) + 'static,
+) -> Registration {
+    O::with_registry(|registry| {
+        let id = registry.next_id;
+        registry.next_id += 1;
+
+        let shared = if let Some(index) = registry
+            .observers
+            .iter()
+            .position(|shared| &shared.options == options)
+        {
+            &registry.observers[index]
+        } else {
+            registry.observers.push(Shared::new(options.clone()));
+            registry.observers.last().unwrap_throw()
+        };
+
+        let mut targets = shared.targets.borrow_mut();
+
+        // Observing an element again doesn't report its current state, so start over.
+        // Existing callbacks will get an extra entry, which is harmless.
+        if targets.iter().any(|target| &target.element == element) {
+            shared.observer.unobserve(element);
+        }
+
+        shared.observer.observe(element, options);
+        targets.push(Target {
+            id,
+            element: element.clone(),
+            callback: Rc::new(RefCell::new(f)),
+        });
+
+        Registration {
+            id,
+            unregister: unregister::<O>,
+        }
+    })
+}
+
+fn unregister<O: Observer>(id: u64) {
+    O::with_registry(|registry| {
+        for shared in &registry.observers {
+            let mut targets = shared.targets.borrow_mut();
+
+            if let Some(index) = targets.iter().position(|target| target.id == id) {
+                let target = targets.remove(index);
+
+                if !targets.iter().any(|other| other.element == target.element) {
+                    shared.observer.unobserve(&target.element);
+                }
+
+                return;
+            }
+        }
+    })
+}
+
+/// An observer that's shared between all elements observed with the same
+/// options.
+///
+/// Shared observers are kept for the lifetime of the thread, so we never
+/// drop the callback while the browser might be calling it.
+struct Shared<O: Observer> {
+    options: O::Options,
+    observer: O,
+    targets: Rc<RefCell<Vec<Target<O::Entry>>>>,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl<O: Observer> Shared<O> {
+    fn new(options: O::Options) -> Self {
+        let targets: Rc<RefCell<Vec<Target<O::Entry>>>> = Rc::default();
+        let callback = Closure::<dyn FnMut(js_sys::Array)>::new({
+            let targets = targets.clone();
+
+            move |entries: js_sys::Array| {
+                for entry in entries.iter() {
+                    let entry: O::Entry = entry.unchecked_into();
+                    let element = O::target(&entry);
+                    // Callbacks might observe other elements, so don't hold a borrow while
+                    // calling them.
+                    let callbacks: Vec<_> = targets
+                        .borrow()
+                        .iter()
+                        .filter(|target| target.element == element)
+                        .map(|target| target.callback.clone())
+                        .collect();
+
+                    for callback in callbacks {
+                        callback.borrow_mut()(&entry);
+                    }
+                }
+            }
+        });
+        let observer = O::new(callback.as_ref().unchecked_ref(), &options);
+
+        Self {
+            options,
+            observer,
+            targets,
+            _callback: callback,
+        }
+    }
+}
+
+type Callback<Entry> = Rc<RefCell<dyn FnMut(&Entry)>>;
+
+struct Target<Entry> {
+    id: u64,
+    element: web_sys::Element,
+    callback: Callback<Entry>,
+}
+
+struct Registry<O: Observer> {
+    next_id: u64,
+    observers: Vec<Shared<O>>,
+}
+
+impl<O: Observer> Default for Registry<O> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            observers: Vec::new(),
+        }
+    }
+}
+
+trait Observer: Sized + 'static {
+    type Options: Clone + PartialEq + 'static;
+    type Entry: JsCast + 'static;
+
+    fn with_registry<R>(f: impl FnOnce(&mut Registry<Self>) -> R) -> R;
+
+    fn new(callback: &js_sys::Function, options: &Self::Options) -> Self;
+
+    fn observe(&self, element: &web_sys::Element, options: &Self::Options);
+
+    fn unobserve(&self, element: &web_sys::Element);
+
+    fn target(entry: &Self::Entry) -> web_sys::Element;
+}
+
+impl Observer for web_sys::IntersectionObserver {
+    type Entry = web_sys::IntersectionObserverEntry;
+    type Options = IntersectionOptions;
+
+    fn with_registry<R>(f: impl FnOnce(&mut Registry<Self>) -> R) -> R {
+        thread_local! {
+            static REGISTRY: RefCell<Registry<web_sys::IntersectionObserver>> =
+                RefCell::default();
+        }
+
+        REGISTRY.with(|registry| f(&mut registry.borrow_mut()))
+    }
+
+    fn new(callback: &js_sys::Function, options: &Self::Options) -> Self {
+        let init = web_sys::IntersectionObserverInit::new();
+        init.set_root_margin(&options.root_margin);
+        let thresholds: js_sys::Array = options
+            .thresholds
+            .iter()
+            .map(|&threshold| JsValue::from(threshold))
+            .collect();
+        init.set_threshold(&thresholds);
+
+        web_sys::IntersectionObserver::new_with_options(callback, &init).unwrap_throw()
+    }
+
+    fn observe(&self, element: &web_sys::Element, _options: &Self::Options) {
+        web_sys::IntersectionObserver::observe(self, element)
+    }
+
+    fn unobserve(&self, element: &web_sys::Element) {
+        web_sys::IntersectionObserver::unobserve(self, element)
+    }
+
+    fn target(entry: &Self::Entry) -> web_sys::Element {
+        entry.target()
+    }
+}
+
+impl Observer for web_sys::ResizeObserver {
+    type Entry = web_sys::ResizeObserverEntry;
+    type Options = ResizeBox;
+
+    fn with_registry<R>(f: impl FnOnce(&mut Registry<Self>) -> R) -> R {
+        thread_local! {
+            static REGISTRY: RefCell<Registry<web_sys::ResizeObserver>> = RefCell::default();
+        }
+
+        REGISTRY.with(|registry| f(&mut registry.borrow_mut()))
+    }
+
+    fn new(callback: &js_sys::Function, _options: &Self::Options) -> Self {
+        web_sys::ResizeObserver::new(callback).unwrap_throw()
+    }
+
+    fn observe(&self, element: &web_sys::Element, options: &Self::Options) {
+        let init = web_sys::ResizeObserverOptions::new();
+        init.set_box(match options {
+            ResizeBox::Content => web_sys::ResizeObserverBoxOptions::ContentBox,
+            ResizeBox::Border => web_sys::ResizeObserverBoxOptions::BorderBox,
+            ResizeBox::DevicePixelContent => {
+                web_sys::ResizeObserverBoxOptions::DevicePixelContentBox
+            }
+        });
+
+        self.observe_with_options(element, &init)
+    }
+
+    fn unobserve(&self, element: &web_sys::Element) {
+        web_sys::ResizeObserver::unobserve(self, element)
+    }
+
+    fn target(entry: &Self::Entry) -> web_sys::Element {
+        entry.target()
+    }
+}
@@ -0,0 +1,541 @@
+//! Query [`Dry`] trees with CSS selectors.
+//!
+//! [`QuerySelector`] finds elements in a [`Dry`] tree, like
+//! [`querySelector`] does in the browser. This is useful in tests that run
+//! natively, or to process server side rendered HTML without parsing it.
+//!
+//! Matches are returned as [`ElementRef`]s, which can read the element's
+//! attributes, text and children. Only light DOM children are searched.
+//! Structural pseudo classes, like `:first-child` and `:not(...)`, are
+//! supported. Other pseudo classes, like `:hover`, and pseudo elements are
+//! rejected with a [`SelectorError`].
+//!
+//! [`querySelector`]: https://developer.mozilla.org/en-US/docs/Web/API/Document/querySelector
+//!
+//! # Example
+//!
+//! ```
+#![doc = function_body!("tests/doc/query.rs", module_example, [])]
+//! ```
+use std::{
+    borrow::Cow,
+    fmt::{self, Display},
+    rc::Rc,
+};
+
+use cssparser_selectors::{serialize_identifier, CowRcStr, ParserInput, ToCss};
+use include_doc::function_body;
+use parcel_selectors::{
+    attr::{AttrSelectorOperation, CaseSensitivity, NamespaceConstraint},
+    context::{MatchingContext, MatchingMode, QuirksMode},
+    matching::{self, ElementSelectorFlags},
+    parser::{NestingRequirement, ParseErrorRecovery, SelectorParseErrorKind},
+    Element, OpaqueElement, SelectorImpl, SelectorList,
+};
+
+use crate::{
+    attribute::Attribute,
+    dom::{private, Dry},
+    node::element::Namespace,
+    serialize::{FmtWrite, HtmlWrite, PrettyPrinter, ToHtml},
+};
+
+/// Find elements with CSS selectors.
+pub trait QuerySelector {
+    /// A handle to this element, or `None` if this is a text node.
+    fn element_ref(&self) -> Option<ElementRef>;
+
+    /// Find the first descendant that matches `selectors`, in document order.
+    fn query_selector(&self, selectors: &str) -> Result<Option<ElementRef>, SelectorError> {
+        let selectors = parse_selectors(selectors)?;
+        let mut found = None;
+
+        if let Some(root) = self.element_ref() {
+            root.visit_descendants(&mut |element| {
+                if element.matches_list(&selectors) {
+                    found = Some(element.clone());
+                    return false;
+                }
+
+                true
+            });
+        }
+
+        Ok(found)
+    }
+
+    /// Find all the descendants that match `selectors`, in document order.
+    fn query_selector_all(&self, selectors: &str) -> Result<Vec<ElementRef>, SelectorError> {
+        let selectors = parse_selectors(selectors)?;
+        let mut found = Vec::new();
+
+        if let Some(root) = self.element_ref() {
+            root.visit_descendants(&mut |element| {
+                if element.matches_list(&selectors) {
+                    found.push(element.clone());
+                }
+
+                true
+            });
+        }
+
+        Ok(found)
+    }
+}
+
+/// A handle to an element in a [`Dry`] tree.
+///
+/// The handle knows the element's ancestors, up to the node that was queried.
+#[derive(Clone)]
+pub struct ElementRef {
+    element: DryElement,
+    parent: Option<Rc<ElementRef>>,
+    /// The index of this element in its parent's children.
+    index: usize,
+}
+
+impl ElementRef {
+    pub(crate) fn root(element: DryElement) -> Self {
+        Self {
+            element,
+            parent: None,
+            index: 0,
+        }
+    }
+
+    pub(crate) fn from_node(node: &DryNode) -> Option<Self> {
+        match node {
+            DryNode::Element(element) => Some(Self::root(element.clone())),
+            DryNode::Text(_) => None,
+        }
+    }
+
+    /// The tag name.
+    pub fn tag(&self) -> String {
+        self.element.shared().tag().to_string()
+    }
+
+    /// Get an attribute value.
+    ///
+    /// Any style properties are included in the `style` attribute.
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        self.element
+            .shared()
+            .get_attribute(name)
+            .map(Cow::into_owned)
+    }
+
+    /// All the attributes, as `(name, value)` pairs.
+    pub fn attributes(&self) -> Vec<(String, String)> {
+        self.element
+            .shared()
+            .attributes()
+            .map(|(name, value)| (name.to_string(), value.into_owned()))
+            .collect()
+    }
+
+    /// Set an attribute, or remove it if `value` is `None`.
+    ///
+    /// This changes the underlying element, so it can be used to post process a
+    /// tree before it's serialized. Any reactive updates to the attribute will
+    /// overwrite the value.
+    pub fn set_attribute(&self, name: &str, value: impl Attribute) {
+        self.element.shared_mut().attribute(name, value)
+    }
+
+    /// The text content of this element and its descendants.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        push_text(&self.element, &mut text);
+        text
+    }
+
+    /// The child nodes.
+    pub fn children(&self) -> Vec<NodeRef> {
+        let parent = Rc::new(self.clone());
+
+        self.element
+            .shared()
+            .children()
+            .iter()
+            .enumerate()
+            .map(|(index, child)| match child {
+                DryNode::Element(element) => NodeRef::Element(ElementRef {
+                    element: element.clone(),
+                    parent: Some(parent.clone()),
+                    index,
+                }),
+                DryNode::Text(text) => NodeRef::Text(text.text().to_string()),
+            })
+            .collect()
+    }
+
+    /// The parent element, if it's part of the tree that was queried.
+    pub fn parent(&self) -> Option<ElementRef> {
+        self.parent.as_deref().cloned()
+    }
+
+    /// Does this element match `selectors`?
+    pub fn matches(&self, selectors: &str) -> Result<bool, SelectorError> {
+        Ok(self.matches_list(&parse_selectors(selectors)?))
+    }
+
+    fn matches_list(&self, selectors: &SelectorList<'_, Selectors>) -> bool {
+        let mut context =
+            MatchingContext::new(MatchingMode::Normal, None, None, QuirksMode::NoQuirks);
+        matching::matches_selector_list(selectors, &MatchElement(self.clone()), &mut context)
+    }
+
+    /// Call `f` on each descendant in document order, until it returns `false`.
+    fn visit_descendants(&self, f: &mut impl FnMut(&ElementRef) -> bool) -> bool {
+        for child in self.children() {
+            if let NodeRef::Element(child) = child {
+                if !f(&child) || !child.visit_descendants(f) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    fn sibling_element(&self, mut indices: impl Iterator<Item = usize>) -> Option<Self> {
+        let parent = self.parent.as_ref()?;
+        let siblings = parent.element.shared();
+
+        indices.find_map(|index| match &siblings.children()[index] {
+            DryNode::Element(element) => Some(ElementRef {
+                element: element.clone(),
+                parent: Some(parent.clone()),
+                index,
+            }),
+            DryNode::Text(_) => None,
+        })
+    }
+
+    fn sibling_count(&self) -> usize {
+        self.parent
+            .as_ref()
+            .map_or(0, |parent| parent.element.shared().children().len())
+    }
+
+    fn is_html(&self) -> bool {
+        *self.element.shared().namespace() == Namespace::Html
+    }
+}
+
+impl QuerySelector for ElementRef {
+    fn element_ref(&self) -> Option<ElementRef> {
+        Some(self.clone())
+    }
+}
+
+impl fmt::Debug for ElementRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ElementRef")
+            .field("tag", &self.tag())
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Display for ElementRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_html(&mut FmtWrite(f))
+    }
+}
+
+impl ToHtml for ElementRef {
+    fn write_html<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error> {
+        self.element.write_html(w)
+    }
+
+    fn write_pretty_html<W: HtmlWrite>(
+        &self,
+        printer: &PrettyPrinter,
+        depth: usize,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        self.element.write_pretty_html(printer, depth, w)
+    }
+
+    fn write_xml<W: HtmlWrite>(
+        &self,
+        parent_namespace: Option<&Namespace>,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        self.element.write_xml(parent_namespace, w)
+    }
+}
+
+/// A handle to a node in a [`Dry`] tree.
+#[derive(Clone, Debug)]
+pub enum NodeRef {
+    /// An element.
+    Element(ElementRef),
+    /// A text node, with its text.
+    Text(String),
+}
+
+/// An error parsing a CSS selector.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectorError(String);
+
+impl Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SelectorError {}
+
+type DryElement = <Dry as private::Dom>::Element;
+
+type DryNode = <Dry as private::Dom>::Node;
+
+fn push_text(element: &DryElement, text: &mut String) {
+    for child in element.shared().children() {
+        match child {
+            DryNode::Element(element) => push_text(element, text),
+            DryNode::Text(child_text) => text.push_str(&child_text.text()),
+        }
+    }
+}
+
+fn parse_selectors(selectors: &str) -> Result<SelectorList<'_, Selectors>, SelectorError> {
+    let mut input = ParserInput::new(selectors);
+
+    SelectorList::parse(
+        &SelectorParser,
+        &mut cssparser_selectors::Parser::new(&mut input),
+        ParseErrorRecovery::DiscardList,
+        NestingRequirement::None,
+    )
+    .map_err(|e| SelectorError(format!("Invalid selector `{selectors}`: {:?}", e.kind)))
+}
+
+/// An [`ElementRef`] that can be matched against selectors.
+#[derive(Clone, Debug)]
+struct MatchElement(ElementRef);
+
+impl<'i> Element<'i> for MatchElement {
+    type Impl = Selectors;
+
+    fn opaque(&self) -> OpaqueElement {
+        OpaqueElement::new(&*self.0.element.shared())
+    }
+
+    fn parent_element(&self) -> Option<Self> {
+        self.0.parent().map(Self)
+    }
+
+    fn parent_node_is_shadow_root(&self) -> bool {
+        false
+    }
+
+    fn containing_shadow_host(&self) -> Option<Self> {
+        None
+    }
+
+    fn is_pseudo_element(&self) -> bool {
+        false
+    }
+
+    fn prev_sibling_element(&self) -> Option<Self> {
+        self.0.sibling_element((0..self.0.index).rev()).map(Self)
+    }
+
+    fn next_sibling_element(&self) -> Option<Self> {
+        self.0
+            .sibling_element(self.0.index + 1..self.0.sibling_count())
+            .map(Self)
+    }
+
+    fn is_html_element_in_html_document(&self) -> bool {
+        self.0.is_html()
+    }
+
+    fn has_local_name(&self, local_name: &CssString) -> bool {
+        self.0.element.shared().tag() == local_name.0
+    }
+
+    fn has_namespace(&self, ns: &CssString) -> bool {
+        self.0.element.shared().namespace().as_str() == ns.0
+    }
+
+    fn is_same_type(&self, other: &Self) -> bool {
+        let element = self.0.element.shared();
+        let other = other.0.element.shared();
+
+        element.tag() == other.tag() && element.namespace() == other.namespace()
+    }
+
+    fn attr_matches(
+        &self,
+        ns: &NamespaceConstraint<&CssString>,
+        local_name: &CssString,
+        operation: &AttrSelectorOperation<&CssString>,
+    ) -> bool {
+        // Attributes don't have a namespace.
+        if let NamespaceConstraint::Specific(ns) = ns {
+            if !ns.0.is_empty() {
+                return false;
+            }
+        }
+
+        self.0
+            .element
+            .shared()
+            .get_attribute(&local_name.0)
+            .is_some_and(|value: Cow<'_, str>| operation.eval_str(&value))
+    }
+
+    fn match_non_ts_pseudo_class<F>(
+        &self,
+        pc: &Unsupported,
+        _context: &mut MatchingContext<'_, 'i, Self::Impl>,
+        _flags_setter: &mut F,
+    ) -> bool
+    where
+        F: FnMut(&Self, ElementSelectorFlags),
+    {
+        match *pc {}
+    }
+
+    fn match_pseudo_element(
+        &self,
+        pe: &Unsupported,
+        _context: &mut MatchingContext<'_, 'i, Self::Impl>,
+    ) -> bool {
+        match *pe {}
+    }
+
+    fn is_link(&self) -> bool {
+        self.0.is_html()
+            && matches!(self.0.element.shared().tag(), "a" | "area" | "link")
+            && self.0.element.shared().get_attribute("href").is_some()
+    }
+
+    fn is_html_slot_element(&self) -> bool {
+        self.0.is_html() && self.0.element.shared().tag() == "slot"
+    }
+
+    fn has_id(&self, id: &CssString, case_sensitivity: CaseSensitivity) -> bool {
+        self.0
+            .element
+            .shared()
+            .get_attribute("id")
+            .is_some_and(|value| case_sensitivity.eq(value.as_bytes(), id.0.as_bytes()))
+    }
+
+    fn has_class(&self, name: &CssString, case_sensitivity: CaseSensitivity) -> bool {
+        self.0
+            .element
+            .shared()
+            .get_attribute("class")
+            .is_some_and(|classes| {
+                classes
+                    .split_ascii_whitespace()
+                    .any(|class| case_sensitivity.eq(class.as_bytes(), name.0.as_bytes()))
+            })
+    }
+
+    fn imported_part(&self, _name: &CssString) -> Option<CssString> {
+        None
+    }
+
+    fn is_part(&self, _name: &CssString) -> bool {
+        false
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0
+            .element
+            .shared()
+            .children()
+            .iter()
+            .all(|child| match child {
+                DryNode::Element(_) => false,
+                DryNode::Text(text) => text.text().is_empty(),
+            })
+    }
+
+    fn is_root(&self) -> bool {
+        self.0.parent.is_none()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Selectors;
+
+impl<'i> SelectorImpl<'i> for Selectors {
+    type AttrValue = CssString;
+    type BorrowedLocalName = CssString;
+    type BorrowedNamespaceUrl = CssString;
+    type ExtraMatchingData = ();
+    type Identifier = CssString;
+    type LocalName = CssString;
+    type NamespacePrefix = CssString;
+    type NamespaceUrl = CssString;
+    type NonTSPseudoClass = Unsupported;
+    type PseudoElement = Unsupported;
+    type VendorPrefix = Unsupported;
+}
+
+struct SelectorParser;
+
+impl<'i> parcel_selectors::Parser<'i> for SelectorParser {
+    type Error = SelectorParseErrorKind<'i>;
+    type Impl = Selectors;
+
+    fn parse_is_and_where(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+struct CssString(String);
+
+impl<'a> From<CowRcStr<'a>> for CssString {
+    fn from(value: CowRcStr<'a>) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl AsRef<str> for CssString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl ToCss for CssString {
+    fn to_css<W: fmt::Write>(&self, dest: &mut W) -> fmt::Result {
+        serialize_identifier(&self.0, dest)
+    }
+}
+
+/// Pseudo classes, pseudo elements and vendor prefixes aren't supported.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Unsupported {}
+
+impl ToCss for Unsupported {
+    fn to_css<W: fmt::Write>(&self, _dest: &mut W) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl<'i> parcel_selectors::parser::NonTSPseudoClass<'i> for Unsupported {
+    type Impl = Selectors;
+
+    fn is_active_or_hover(&self) -> bool {
+        match *self {}
+    }
+
+    fn is_user_action_state(&self) -> bool {
+        match *self {}
+    }
+}
+
+impl<'i> parcel_selectors::parser::PseudoElement<'i> for Unsupported {
+    type Impl = Selectors;
+}
@@ -0,0 +1,314 @@
+//! Serialize [`Dry`] nodes to HTML.
+//!
+//! [`Dry`] nodes implement [`Display`], so [`to_string`] will render them as
+//! HTML. The tools in this module write HTML directly into a sink instead, to
+//! avoid building an intermediate [`String`] and going through
+//! [`fmt::Formatter`]:
+//!
+//! - [`ToHtml::write_html`] writes into any [`HtmlWrite`] sink. Any [`BufMut`],
+//!   like `Vec<u8>` or [`BytesMut`], is a sink. Use [`FmtWrite`] to write into
+//!   a [`fmt::Write`].
+//! - [`HtmlSerializer`] has a reusable buffer, and can write into an
+//!   [`io::Write`] with a single call to [`write_all`](io::Write::write_all),
+//!   or produce [`Bytes`] for a response body.
+//! - [`PrettyPrinter`] writes indented HTML, optionally with sorted attributes
+//!   and classes. This is useful for snapshot tests.
+//!
+//! Nodes can also be serialized as XML, with [`ToHtml::write_xml`] or
+//! [`Syntax::Xml`]. This is useful for standalone SVG files, or XHTML
+//! documents.
+//!
+//! [`Dry`]: crate::dom::Dry
+//! [`Display`]: fmt::Display
+//! [`to_string`]: ToString::to_string
+//!
+//! # Example
+//!
+//! ```
+#![doc = function_body!("tests/doc/serialize.rs", module_example, [])]
+//! ```
+use std::{convert::Infallible, fmt, io};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use include_doc::function_body;
+
+use crate::node::element::Namespace;
+
+/// The XML declaration for a standalone XML document, like an SVG file.
+pub const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+/// A sink for HTML.
+pub trait HtmlWrite {
+    type Error;
+
+    /// Append `s` to the sink.
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error>;
+}
+
+impl<B: BufMut> HtmlWrite for B {
+    type Error = Infallible;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.put_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Adapt a [`fmt::Write`] to [`HtmlWrite`].
+pub struct FmtWrite<W>(pub W);
+
+impl<W: fmt::Write> HtmlWrite for FmtWrite<W> {
+    type Error = fmt::Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.0.write_str(s)
+    }
+}
+
+/// Nodes that can be serialized as HTML.
+pub trait ToHtml {
+    /// Write compact HTML.
+    fn write_html<W: HtmlWrite>(&self, w: &mut W) -> Result<(), W::Error>;
+
+    /// Write HTML formatted by `printer`, indented to `depth`.
+    ///
+    /// The first line isn't indented, as the caller is responsible for any
+    /// whitespace before the node. By default, this writes compact HTML.
+    fn write_pretty_html<W: HtmlWrite>(
+        &self,
+        printer: &PrettyPrinter,
+        depth: usize,
+        w: &mut W,
+    ) -> Result<(), W::Error> {
+        let _ = (printer, depth);
+        self.write_html(w)
+    }
+
+    /// Write XML.
+    ///
+    /// Elements without children are self closing, text is XML escaped, and
+    /// `xmlns` is declared wherever an element's namespace differs from its
+    /// parent's. `parent_namespace` is the namespace of the parent element, or
+    /// `None` to declare the namespace on the root element.
+    fn write_xml<W: HtmlWrite>(
+        &self,
+        parent_namespace: Option<&Namespace>,
+        w: &mut W,
+    ) -> Result<(), W::Error>;
+}
+
+/// The syntax to serialize nodes with.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Syntax {
+    /// HTML syntax, as used by [`Display`](fmt::Display).
+    #[default]
+    Html,
+    /// XML syntax. See [`ToHtml::write_xml`].
+    Xml,
+}
+
+/// Serialize nodes with a reusable buffer.
+///
+/// The buffer grows to fit the largest node serialized, and is reused for
+/// subsequent nodes.
+#[derive(Default)]
+pub struct HtmlSerializer {
+    buffer: BytesMut,
+    syntax: Syntax,
+}
+
+impl HtmlSerializer {
+    /// A serializer with an empty buffer, that writes [`Syntax::Html`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A serializer with room for `capacity` bytes of HTML before the buffer
+    /// needs to grow.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buffer: BytesMut::with_capacity(capacity),
+            syntax: Syntax::Html,
+        }
+    }
+
+    /// Set the syntax to serialize with. The default is [`Syntax::Html`].
+    pub fn syntax(mut self, syntax: Syntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Serialize `node` into the buffer, and return the HTML.
+    pub fn serialize(&mut self, node: &impl ToHtml) -> &[u8] {
+        self.buffer.clear();
+        self.write_buffer(node);
+        &self.buffer
+    }
+
+    /// Serialize `node` and write it to `writer`.
+    pub fn write(&mut self, node: &impl ToHtml, writer: &mut impl io::Write) -> io::Result<()> {
+        writer.write_all(self.serialize(node))
+    }
+
+    /// Serialize `node` into [`Bytes`].
+    ///
+    /// This doesn't copy the HTML. The buffer's allocation is reused once the
+    /// [`Bytes`] are dropped, if it's big enough.
+    pub fn to_bytes(&mut self, node: &impl ToHtml) -> Bytes {
+        self.buffer.clear();
+        self.write_buffer(node);
+        self.buffer.split().freeze()
+    }
+
+    fn write_buffer(&mut self, node: &impl ToHtml) {
+        let Ok(()) = match self.syntax {
+            Syntax::Html => node.write_html(&mut self.buffer),
+            Syntax::Xml => node.write_xml(None, &mut self.buffer),
+        };
+    }
+}
+
+/// Write HTML in a readable, canonical form.
+///
+/// Elements that only have block level children are written with each child
+/// on its own, indented line. Anything where whitespace is significant is
+/// written unchanged. That is:
+///
+/// - the content of whitespace sensitive elements, like `<pre>` and
+///   `<textarea>`.
+/// - elements with text or inline children, like `<span>` or `<a>`.
+///
+/// Optionally, attributes can be sorted by name, and classes sorted within the
+/// `class` attribute, so the output doesn't depend on the order they were
+/// added.
+///
+/// This doesn't change what [`HtmlSerializer`] or [`Display`](fmt::Display)
+/// write.
+#[derive(Clone, Debug)]
+pub struct PrettyPrinter {
+    indent: String,
+    sort_attributes: bool,
+    sort_classes: bool,
+}
+
+impl PrettyPrinter {
+    /// Indent by 2 spaces, and don't sort anything.
+    pub fn new() -> Self {
+        Self {
+            indent: "  ".to_string(),
+            sort_attributes: false,
+            sort_classes: false,
+        }
+    }
+
+    /// Indent each level by `width` spaces.
+    pub fn indent(mut self, width: usize) -> Self {
+        self.indent = " ".repeat(width);
+        self
+    }
+
+    /// Sort attributes by name.
+    pub fn sort_attributes(mut self, sort: bool) -> Self {
+        self.sort_attributes = sort;
+        self
+    }
+
+    /// Sort the classes in `class` attributes.
+    pub fn sort_classes(mut self, sort: bool) -> Self {
+        self.sort_classes = sort;
+        self
+    }
+
+    /// Pretty print `node` as a [`String`].
+    pub fn print(&self, node: &impl ToHtml) -> String {
+        let mut html = String::new();
+        self.write(node, &mut FmtWrite(&mut html))
+            .expect("Writing to a `String` shouldn't fail");
+        html
+    }
+
+    /// Pretty print `node` into `w`.
+    pub fn write<W: HtmlWrite>(&self, node: &impl ToHtml, w: &mut W) -> Result<(), W::Error> {
+        node.write_pretty_html(self, 0, w)
+    }
+
+    pub(crate) fn sorts_attributes(&self) -> bool {
+        self.sort_attributes
+    }
+
+    pub(crate) fn sorts_classes(&self) -> bool {
+        self.sort_classes
+    }
+
+    pub(crate) fn new_line<W: HtmlWrite>(&self, depth: usize, w: &mut W) -> Result<(), W::Error> {
+        w.write_str("\n")?;
+
+        for _ in 0..depth {
+            w.write_str(&self.indent)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escape `&` and `<` in text content.
+pub(crate) fn write_text<W: HtmlWrite>(text: &str, w: &mut W) -> Result<(), W::Error> {
+    write_escaped(text, w, |byte| match byte {
+        b'&' => Some("&amp;"),
+        b'<' => Some("&lt;"),
+        _ => None,
+    })
+}
+
+/// Escape `&`, `<` and `>` in text content, as required for XML.
+pub(crate) fn write_xml_text<W: HtmlWrite>(text: &str, w: &mut W) -> Result<(), W::Error> {
+    write_escaped(text, w, |byte| match byte {
+        b'&' => Some("&amp;"),
+        b'<' => Some("&lt;"),
+        b'>' => Some("&gt;"),
+        _ => None,
+    })
+}
+
+/// Escape a double quoted attribute value.
+pub(crate) fn write_attribute_value<W: HtmlWrite>(value: &str, w: &mut W) -> Result<(), W::Error> {
+    write_escaped(value, w, |byte| match byte {
+        b'&' => Some("&amp;"),
+        b'"' => Some("&quot;"),
+        b'<' => Some("&lt;"),
+        b'>' => Some("&gt;"),
+        _ => None,
+    })
+}
+
+/// Write `s` into `w` a segment at a time, replacing any ASCII byte that
+/// `escape` has an entity for.
+fn write_escaped<W: HtmlWrite>(
+    s: &str,
+    w: &mut W,
+    escape: impl Fn(u8) -> Option<&'static str>,
+) -> Result<(), W::Error> {
+    let mut rest = s;
+
+    while let Some((index, entity)) = rest
+        .bytes()
+        .enumerate()
+        .find_map(|(index, byte)| Some((index, escape(byte)?)))
+    {
+        // The escaped byte is ASCII, so `index` is on a `char` boundary.
+        if index > 0 {
+            w.write_str(&rest[..index])?;
+        }
+
+        w.write_str(entity)?;
+        rest = &rest[index + 1..];
+    }
+
+    w.write_str(rest)
+}
+
+impl Default for PrettyPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
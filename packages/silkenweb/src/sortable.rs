@@ -0,0 +1,653 @@
+//! Sortable lists.
+//!
+//! [`Sortable`] renders the items of a [`MutableVec`] as the children of a
+//! container, and lets the user reorder them by dragging with a mouse, pen or
+//! touch, or with the keyboard. The new order is written back to the
+//! [`MutableVec`]. Lists in the same [`Group`] are connected, so items can be
+//! dragged from one list to another.
+//!
+//! Items are focusable, and can be moved with the keyboard:
+//!
+//! - `Space` or `Enter` picks up the focused item, or drops it if it's already
+//!   picked up.
+//! - The arrow keys move a picked up item within its list.
+//! - `Escape` puts a picked up item back where it was.
+//!
+//! Moving focus away from a picked up item drops it where it is. Items can
+//! only be moved to another list in the [`Group`] by dragging them.
+//!
+//! Each of these is announced to screen readers, using the label from
+//! [`Sortable::label`].
+//!
+//! No styles are applied, apart from moving an item with the CSS `translate`
+//! property as it's dragged. Instead, these attributes are set:
+//!
+//! - `data-sortable-dragging` on an item that's being dragged or is picked up.
+//! - `data-sortable-drop` on the item a dragged item will be dropped next to,
+//!   with a value of `before` or `after`.
+//! - `data-sortable-drop="end"` on a list when a dragged item will be dropped
+//!   at the end of it. This is useful to highlight empty lists.
+//!
+//! As with [`gesture`](crate::gesture), you'll usually want to set
+//! `touch-action: none` on items, so dragging them by touch doesn't scroll the
+//! page.
+//!
+//! # Example
+//!
+//! ```no_run
+#![doc = function_body!("tests/doc/sortable.rs", module_example, [])]
+//! ```
+use std::{
+    cell::{Cell, RefCell},
+    future,
+    rc::Rc,
+};
+
+use futures_signals::{
+    map_ref,
+    signal::{Mutable, ReadOnlyMutable, SignalExt},
+    signal_vec::{MutableVec, SignalVecExt},
+};
+use include_doc::function_body;
+use silkenweb_macros::cfg_browser;
+use wasm_bindgen::JsCast;
+
+use crate::{
+    clone,
+    dom::Dom,
+    gesture::{ElementGestures, Phase, Point},
+    node::{
+        element::{Element, ParentElement},
+        Node,
+    },
+    task::on_animation_frame,
+    value::Sig,
+};
+
+/// A list that can be reordered by the user.
+pub struct Sortable<T> {
+    items: MutableVec<T>,
+    group: Group<T>,
+    orientation: Orientation,
+    label: Rc<dyn Fn(&T) -> String>,
+}
+
+impl<T: Clone + 'static> Sortable<T> {
+    /// A sortable list of `items`.
+    ///
+    /// The list isn't connected to any other lists until it's added to a
+    /// [`Group`].
+    pub fn new(items: MutableVec<T>) -> Self {
+        Self {
+            items,
+            group: Group::new(),
+            orientation: Orientation::Vertical,
+            label: Rc::new(|_| "Item".to_string()),
+        }
+    }
+
+    /// Connect this list to the other lists in `group`.
+    pub fn group(mut self, group: &Group<T>) -> Self {
+        self.group = group.clone();
+        self
+    }
+
+    /// The items are laid out in a row, rather than a column.
+    ///
+    /// This decides whether a dragged item is dropped before or after the
+    /// item under the pointer.
+    pub fn horizontal(mut self) -> Self {
+        self.orientation = Orientation::Horizontal;
+        self
+    }
+
+    /// How to describe an item to screen readers.
+    ///
+    /// By default, items are described as "Item".
+    pub fn label(mut self, f: impl Fn(&T) -> String + 'static) -> Self {
+        self.label = Rc::new(f);
+        self
+    }
+
+    /// Render the list into `container`, using `item` to render each item.
+    ///
+    /// Items are given a `tabindex`, an `aria-roledescription` and some
+    /// `data-sortable-*` attributes, so `item` shouldn't set these.
+    pub fn render<D, C, E>(self, container: C, mut item: impl FnMut(T) -> E + 'static) -> C
+    where
+        D: Dom,
+        C: ParentElement<D>,
+        E: Element + Into<Node<D>>,
+    {
+        let handle = container.handle();
+        let id = self.group.0.add_list(List {
+            id: 0,
+            items: self.items.clone(),
+            orientation: self.orientation,
+            container: Box::new(move || handle.try_element()),
+        });
+        let registration = Registration {
+            group: self.group.0.clone(),
+            id,
+        };
+        let target = self.group.0.target.clone();
+        let drop_at_end = Some(DropTarget {
+            list: id,
+            item: None,
+            after: false,
+        });
+
+        let group = self.group;
+        let items = self.items;
+        let label = self.label;
+
+        container
+            .attribute("data-sortable-list", true)
+            .attribute(
+                "data-sortable-drop",
+                Sig(target
+                    .signal()
+                    .map(move |target| (target == drop_at_end).then_some("end"))),
+            )
+            .spawn_future(async move {
+                let _registration = registration;
+                future::pending().await
+            })
+            .children_signal(
+                items
+                    .signal_vec_cloned()
+                    .enumerate()
+                    .map(move |(index, value)| {
+                        SortableItem {
+                            group: group.clone(),
+                            list: id,
+                            items: items.clone(),
+                            index,
+                            label: label.clone(),
+                        }
+                        .render(item(value))
+                    }),
+            )
+    }
+}
+
+/// A group of connected [`Sortable`] lists.
+///
+/// Items can be dragged between lists in the same group.
+pub struct Group<T>(Rc<GroupState<T>>);
+
+impl<T> Group<T> {
+    pub fn new() -> Self {
+        Self(Rc::new(GroupState {
+            lists: RefCell::new(Vec::new()),
+            next_id: Cell::new(0),
+            target: Mutable::new(None),
+        }))
+    }
+}
+
+impl<T> Default for Group<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Group<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Orientation {
+    Vertical,
+    Horizontal,
+}
+
+/// Where a dragged item will be dropped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct DropTarget {
+    list: usize,
+    /// The item to drop next to, or `None` to drop at the end of the list.
+    item: Option<usize>,
+    after: bool,
+}
+
+struct GroupState<T> {
+    lists: RefCell<Vec<List<T>>>,
+    next_id: Cell<usize>,
+    target: Mutable<Option<DropTarget>>,
+}
+
+impl<T: Clone> GroupState<T> {
+    fn add_list(&self, mut list: List<T>) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        list.id = id;
+        self.lists.borrow_mut().push(list);
+        id
+    }
+
+    fn items(&self, list: usize) -> Option<MutableVec<T>> {
+        self.lists
+            .borrow()
+            .iter()
+            .find(|entry| entry.id == list)
+            .map(|entry| entry.items.clone())
+    }
+
+    /// Move the item at `index` in `list` to `target`.
+    fn drop_item(&self, list: usize, index: usize, target: DropTarget) {
+        let (Some(source), Some(dest)) = (self.items(list), self.items(target.list)) else {
+            return;
+        };
+        let to = target.item.map_or_else(
+            || dest.lock_ref().len(),
+            |item| item + usize::from(target.after),
+        );
+
+        if list == target.list {
+            move_within(&source, index, to);
+        } else {
+            move_between(&source, index, &dest, to);
+        }
+    }
+
+    /// Find where an item would be dropped if it was released at `position`.
+    #[cfg_browser(true)]
+    fn drop_target(&self, position: Point, dragged: &web_sys::Element) -> Option<DropTarget> {
+        use silkenweb_base::document;
+
+        let lists = self.lists.borrow();
+
+        for element in document::elements_from_point(position.x as f32, position.y as f32) {
+            if dragged.contains(Some(&element)) {
+                continue;
+            }
+
+            let Some(container) = element.closest("[data-sortable-list]").ok().flatten() else {
+                continue;
+            };
+            let Some(list) = lists
+                .iter()
+                .find(|list| (list.container)().as_ref() == Some(&container))
+            else {
+                continue;
+            };
+
+            let item = element
+                .closest("[data-sortable-index]")
+                .ok()
+                .flatten()
+                .filter(|item| item != &container && container.contains(Some(item)))
+                .and_then(|item| {
+                    let index = item.get_attribute("data-sortable-index")?.parse().ok()?;
+                    Some((index, item.get_bounding_client_rect()))
+                });
+
+            return match item {
+                Some((index, rect)) => {
+                    let after = match list.orientation {
+                        Orientation::Vertical => position.y > rect.top() + rect.height() / 2.0,
+                        Orientation::Horizontal => position.x > rect.left() + rect.width() / 2.0,
+                    };
+
+                    Some(DropTarget {
+                        list: list.id,
+                        item: Some(index),
+                        after,
+                    })
+                }
+                // Dropping an item in the gap it left behind shouldn't move it to the end of
+                // the list.
+                None if container.contains(Some(dragged)) => None,
+                None => Some(DropTarget {
+                    list: list.id,
+                    item: None,
+                    after: false,
+                }),
+            };
+        }
+
+        None
+    }
+
+    #[cfg_browser(false)]
+    fn drop_target(&self, _position: Point, _dragged: &web_sys::Element) -> Option<DropTarget> {
+        None
+    }
+}
+
+struct List<T> {
+    id: usize,
+    items: MutableVec<T>,
+    // These are only used for hit testing in the browser.
+    #[allow(dead_code)]
+    orientation: Orientation,
+    #[allow(dead_code)]
+    container: Box<dyn Fn() -> Option<web_sys::Element>>,
+}
+
+/// Remove a list from its group when dropped.
+struct Registration<T> {
+    group: Rc<GroupState<T>>,
+    id: usize,
+}
+
+impl<T> Drop for Registration<T> {
+    fn drop(&mut self) {
+        let id = self.id;
+        self.group.lists.borrow_mut().retain(|list| list.id != id);
+        let mut target = self.group.target.lock_mut();
+
+        if target.is_some_and(|target| target.list == id) {
+            *target = None;
+        }
+    }
+}
+
+struct SortableItem<T> {
+    group: Group<T>,
+    list: usize,
+    items: MutableVec<T>,
+    index: ReadOnlyMutable<Option<usize>>,
+    label: Rc<dyn Fn(&T) -> String>,
+}
+
+impl<T: Clone + 'static> SortableItem<T> {
+    fn render<E: Element>(self, element: E) -> E {
+        let Self {
+            group,
+            list,
+            items,
+            index,
+            label: item_label,
+        } = self;
+        let dragging = Mutable::new(false);
+        // The index the item was picked up from with the keyboard.
+        let picked_up = Rc::new(Cell::new(None));
+
+        let drop_position = map_ref! {
+            let target = group.0.target.signal(),
+            let index = index.signal() => {
+                let target = *target;
+
+                target
+                    .filter(|target| {
+                        target.list == list && target.item.is_some() && target.item == *index
+                    })
+                    .map(|target| if target.after { "after" } else { "before" })
+            }
+        };
+
+        element
+            .attribute("tabindex", "0")
+            .attribute("aria-roledescription", "sortable item")
+            .attribute(
+                "data-sortable-index",
+                Sig(index
+                    .signal()
+                    .map(|index| index.map(|index| index.to_string()))),
+            )
+            .attribute("data-sortable-dragging", Sig(dragging.signal()))
+            .attribute("data-sortable-drop", Sig(drop_position))
+            .on_drag({
+                clone!(group, index, dragging);
+
+                move |drag, element| {
+                    let element: &web_sys::Element = element.unchecked_ref();
+                    let group = &group.0;
+
+                    match drag.phase {
+                        Phase::Start | Phase::Move => {
+                            dragging.set_neq(true);
+                            set_translate(element, Some(drag.offset));
+                            group
+                                .target
+                                .set_neq(group.drop_target(drag.position, element));
+                        }
+                        Phase::End | Phase::Cancel => {
+                            dragging.set_neq(false);
+                            set_translate(element, None);
+                            let target = group.target.replace(None);
+
+                            if drag.phase == Phase::End {
+                                if let (Some(target), Some(index)) = (target, index.get()) {
+                                    group.drop_item(list, index, target);
+                                }
+                            }
+                        }
+                    }
+                }
+            })
+            .on("focusout", {
+                clone!(picked_up, dragging);
+
+                move |js_ev| {
+                    let Some(target) = crate::macros::current_target(&js_ev) else {
+                        return;
+                    };
+                    clone!(picked_up, dragging);
+
+                    // Moving an item with the keyboard loses focus until it's refocused on the
+                    // next animation frame, so check for focus after that.
+                    on_animation_frame(move || {
+                        let focused = target
+                            .dyn_ref::<web_sys::Element>()
+                            .is_some_and(|element| element.matches(":focus").unwrap_or(false));
+
+                        if !focused {
+                            picked_up.set(None);
+                            dragging.set_neq(false);
+                        }
+                    });
+                }
+            })
+            .on("keydown", move |js_ev| {
+                let Some(index) = index.get() else {
+                    return;
+                };
+                let Some(target) = crate::macros::current_target(&js_ev) else {
+                    return;
+                };
+                let event: web_sys::KeyboardEvent = js_ev.unchecked_into();
+
+                // Leave keys alone if they're for something inside the item, like a button.
+                if event.target().as_ref() != Some(&target) {
+                    return;
+                }
+
+                let len = items.lock_ref().len();
+                let Some(label) = items.lock_ref().get(index).map(|item| item_label(item)) else {
+                    return;
+                };
+                let position = |index: usize| format!("position {} of {len}", index + 1);
+                let move_to = |new_index: usize| {
+                    items.lock_mut().move_from_to(index, new_index);
+                    refocus(target.clone());
+                };
+
+                match (event.key().as_str(), picked_up.get()) {
+                    (" " | "Enter", None) => {
+                        picked_up.set(Some(index));
+                        dragging.set_neq(true);
+                        announce(&format!(
+                            "Picked up {label}, {}. Use the arrow keys to move it, space to drop \
+                             it, or escape to cancel.",
+                            position(index)
+                        ));
+                    }
+                    (" " | "Enter", Some(_)) => {
+                        picked_up.set(None);
+                        dragging.set_neq(false);
+                        announce(&format!("Dropped {label} at {}.", position(index)));
+                    }
+                    ("ArrowUp" | "ArrowLeft", Some(_)) => {
+                        if index > 0 {
+                            move_to(index - 1);
+                            announce(&format!("Moved {label} to {}.", position(index - 1)));
+                        }
+                    }
+                    ("ArrowDown" | "ArrowRight", Some(_)) => {
+                        if index + 1 < len {
+                            move_to(index + 1);
+                            announce(&format!("Moved {label} to {}.", position(index + 1)));
+                        }
+                    }
+                    ("Escape", Some(original)) => {
+                        picked_up.set(None);
+                        dragging.set_neq(false);
+
+                        if original != index && original < len {
+                            move_to(original);
+                        }
+
+                        announce(&format!(
+                            "Cancelled. {label} returned to {}.",
+                            position(original)
+                        ));
+                    }
+                    _ => return,
+                }
+
+                event.prevent_default();
+            })
+    }
+}
+
+/// Move the item at `from` so it's before the item currently at `to`.
+///
+/// `to` can be the length of the list, to move the item to the end.
+fn move_within<T>(items: &MutableVec<T>, from: usize, to: usize) {
+    // Removing the item shifts everything after it down.
+    let to = if to > from { to - 1 } else { to };
+
+    if to != from {
+        items.lock_mut().move_from_to(from, to);
+    }
+}
+
+/// Move the item at `from` in `source` so it's before the item at `to` in
+/// `dest`.
+fn move_between<T: Clone>(source: &MutableVec<T>, from: usize, dest: &MutableVec<T>, to: usize) {
+    let item = source.lock_mut().remove(from);
+    dest.lock_mut().insert_cloned(to, item);
+}
+
+/// Move `element` by `offset`, or back to where it was.
+fn set_translate(element: &web_sys::Element, offset: Option<Point>) {
+    let Some(element) = element.dyn_ref::<web_sys::HtmlElement>() else {
+        return;
+    };
+    let style = element.style();
+
+    let _ = match offset {
+        Some(offset) => style.set_property("translate", &format!("{}px {}px", offset.x, offset.y)),
+        None => style.remove_property("translate").map(|_| ()),
+    };
+}
+
+/// Moving an element in the DOM loses focus, so focus it again once it's been
+/// moved.
+fn refocus(element: web_sys::EventTarget) {
+    on_animation_frame(move || {
+        if let Some(element) = element.dyn_ref::<web_sys::HtmlElement>() {
+            let _ = element.focus();
+        }
+    });
+}
+
+/// Tell screen readers about a change.
+#[cfg_browser(true)]
+fn announce(message: &str) {
+    use silkenweb_base::document;
+
+    thread_local! {
+        static LIVE_REGION: RefCell<Option<web_sys::Element>> = const { RefCell::new(None) };
+    }
+
+    LIVE_REGION.with(|live_region| {
+        let mut live_region = live_region.borrow_mut();
+
+        if !live_region
+            .as_ref()
+            .is_some_and(|region| region.is_connected())
+        {
+            let Some(body) = document::body() else {
+                return;
+            };
+            let region = document::create_element("div");
+            let _ = region.set_attribute("role", "status");
+            let _ = region.set_attribute("aria-live", "assertive");
+            // Visually hidden, but still read by screen readers.
+            let _ = region.set_attribute(
+                "style",
+                "position: absolute; width: 1px; height: 1px; margin: -1px; padding: 0; \
+                 border: 0; overflow: hidden; clip: rect(0 0 0 0); white-space: nowrap",
+            );
+            let _ = body.append_child(&region);
+            *live_region = Some(region);
+        }
+
+        if let Some(region) = live_region.as_ref() {
+            region.set_text_content(Some(message));
+        }
+    });
+}
+
+#[cfg_browser(false)]
+fn announce(_message: &str) {}
+
+#[cfg(test)]
+mod tests {
+    use futures_signals::signal_vec::MutableVec;
+
+    use super::{move_between, move_within};
+
+    fn items(values: &[u32]) -> MutableVec<u32> {
+        MutableVec::new_with_values(values.to_vec())
+    }
+
+    #[test]
+    fn move_forward() {
+        let list = items(&[0, 1, 2, 3]);
+        move_within(&list, 0, 3);
+        assert_eq!(*list.lock_ref(), [1, 2, 0, 3]);
+    }
+
+    #[test]
+    fn move_backward() {
+        let list = items(&[0, 1, 2, 3]);
+        move_within(&list, 3, 1);
+        assert_eq!(*list.lock_ref(), [0, 3, 1, 2]);
+    }
+
+    #[test]
+    fn move_to_end() {
+        let list = items(&[0, 1, 2, 3]);
+        move_within(&list, 1, 4);
+        assert_eq!(*list.lock_ref(), [0, 2, 3, 1]);
+    }
+
+    #[test]
+    fn move_in_place() {
+        let list = items(&[0, 1, 2]);
+        move_within(&list, 1, 1);
+        assert_eq!(*list.lock_ref(), [0, 1, 2]);
+        move_within(&list, 1, 2);
+        assert_eq!(*list.lock_ref(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn move_to_other_list() {
+        let source = items(&[0, 1, 2]);
+        let dest = items(&[10, 11]);
+        move_between(&source, 1, &dest, 1);
+        assert_eq!(*source.lock_ref(), [0, 2]);
+        assert_eq!(*dest.lock_ref(), [10, 1, 11]);
+
+        move_between(&source, 0, &dest, 3);
+        assert_eq!(*source.lock_ref(), [2]);
+        assert_eq!(*dest.lock_ref(), [10, 1, 11, 0]);
+    }
+}
@@ -0,0 +1,179 @@
+//! Transfer state from the server to the client.
+//!
+//! Values are registered under a [`StateKey`] while rendering on the server.
+//! [`HtmlDocument`] serializes them as JSON into a
+//! `<script type="application/json">` in `<head>`, and the client reads them
+//! back synchronously with the same key. This means the client can hydrate
+//! with exactly the same data the server rendered, without fetching it again.
+//!
+//! The state is stored per [`task::scope`] on the server, so concurrent
+//! requests don't share state. Values must be registered before the document
+//! `<head>` is written, so state registered by [`deferred`] content isn't
+//! transferred.
+//!
+//! [`HtmlDocument`]: crate::document::HtmlDocument
+//! [`task::scope`]: crate::task::scope
+//! [`deferred`]: crate::streaming::deferred
+//!
+//! # Example
+//!
+//! ```
+#![doc = function_body!("tests/doc/state_transfer.rs", module_example, [])]
+//! ```
+use std::{cell::RefCell, collections::BTreeMap, fmt, marker::PhantomData};
+
+use include_doc::function_body;
+use serde::{de::DeserializeOwned, Serialize};
+use silkenweb_macros::cfg_browser;
+
+use crate::{serialize::HtmlWrite, task};
+
+/// The `id` of the `<script>` element that holds the state.
+pub const STATE_SCRIPT_ID: &str = "silkenweb-state";
+
+/// A typed key for transferring a value from the server to the client.
+///
+/// Keys are usually declared as constants, so the server and client agree on
+/// the name and type:
+///
+/// ```
+/// # use silkenweb::state_transfer::StateKey;
+/// const USER_NAMES: StateKey<Vec<String>> = StateKey::new("user-names");
+/// ```
+pub struct StateKey<T> {
+    name: &'static str,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> StateKey<T> {
+    /// A key called `name`.
+    ///
+    /// The server and client must use the same `name` for a value, or
+    /// [`StateKey::get`] won't find it on the client. They must also use the
+    /// same `T`, or [`StateKey::get`] will panic.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The name of the key in the serialized state.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for StateKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for StateKey<T> {}
+
+impl<T> fmt::Debug for StateKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StateKey").field(&self.name).finish()
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> StateKey<T> {
+    /// Register `value` under this key.
+    ///
+    /// # Panics
+    ///
+    /// If `value` can't be serialized as JSON.
+    pub fn set(&self, value: &T) {
+        let value = serde_json::to_value(value)
+            .unwrap_or_else(|e| panic!("Couldn't serialize state `{}`: {e}", self.name));
+        with_state(|state| state.insert(self.name.to_string(), value));
+    }
+
+    /// Get the value registered under this key.
+    ///
+    /// On the client, this is the value the server registered.
+    ///
+    /// # Panics
+    ///
+    /// If the value can't be deserialized as a `T`.
+    pub fn get(&self) -> Option<T> {
+        with_state(|state| state.get(self.name).cloned()).map(|value| {
+            serde_json::from_value(value)
+                .unwrap_or_else(|e| panic!("Couldn't deserialize state `{}`: {e}", self.name))
+        })
+    }
+
+    /// Get the value registered under this key, or register the value from
+    /// `f` if there isn't one.
+    ///
+    /// On the server, this calls `f`. On the client, this uses the server's
+    /// value, and only calls `f` if the server didn't register one.
+    pub fn get_or_insert_with(&self, f: impl FnOnce() -> T) -> T {
+        self.get().unwrap_or_else(|| {
+            let value = f();
+            self.set(&value);
+            value
+        })
+    }
+}
+
+/// Write the state as a `<script type="application/json">` element.
+///
+/// Nothing is written if no state has been registered.
+/// [`HtmlDocument`](crate::document::HtmlDocument) calls this for you, so it's
+/// only needed for custom page templates.
+pub fn write_script<W: HtmlWrite>(w: &mut W) -> Result<(), W::Error> {
+    let Some(json) = with_state(|state| {
+        (!state.is_empty())
+            .then(|| serde_json::to_string(state).expect("JSON values should serialize"))
+    }) else {
+        return Ok(());
+    };
+
+    // `<` and `>` can only appear in JSON strings, so escaping them stops the
+    // content from closing the script element.
+    let json = json.replace('<', r"\u003c").replace('>', r"\u003e");
+
+    w.write_str(r#"<script type="application/json" id=""#)?;
+    w.write_str(STATE_SCRIPT_ID)?;
+    w.write_str(r#"">"#)?;
+    w.write_str(&json)?;
+    w.write_str("</script>")
+}
+
+type State = BTreeMap<String, serde_json::Value>;
+
+#[derive(Default)]
+pub(crate) struct TaskLocal(RefCell<Option<State>>);
+
+fn with_state<R>(f: impl FnOnce(&mut State) -> R) -> R {
+    task::local::with(|local| {
+        let mut state = local.state_transfer.0.borrow_mut();
+        f(state.get_or_insert_with(arch::initial_state))
+    })
+}
+
+#[cfg_browser(false)]
+mod arch {
+    use super::State;
+
+    pub fn initial_state() -> State {
+        State::new()
+    }
+}
+
+#[cfg_browser(true)]
+mod arch {
+    use silkenweb_base::document;
+    use wasm_bindgen::UnwrapThrowExt;
+
+    use super::{State, STATE_SCRIPT_ID};
+
+    pub fn initial_state() -> State {
+        document::get_element_by_id(STATE_SCRIPT_ID)
+            .and_then(|script| script.text_content())
+            .map(|json| serde_json::from_str(&json).expect_throw("Invalid state transfer payload"))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,327 @@
+//! Streaming server side rendering.
+//!
+//! Without streaming, server side rendering waits for everything to load, then
+//! renders the whole page with [`to_string`]. [`HtmlStream`] renders the page
+//! as a stream instead. The document shell and the parts of the page that are
+//! ready are sent straight away. Parts of the page that are still loading are
+//! marked with [`deferred`], which renders a fallback until its content is
+//! ready.
+//!
+//! Once deferred content is ready, it's sent as an inline chunk with a small
+//! script to move it into place. Chunks are sent in the order the content
+//! becomes ready, rather than the order it appears on the page.
+//!
+//! [`HtmlStream::into_stream`] renders the page on a [`LocalPoolHandle`] and
+//! produces a `Send` stream of [`Bytes`], so it can be used as a response body
+//! with web frameworks like [`axum`]. For example,
+//! `StreamBody::new(html.into_stream(&local_pool).map(Ok::<_, Infallible>))`.
+//!
+//! [`to_string`]: ToString::to_string
+//! [`axum`]: https://docs.rs/axum
+//! [`LocalPoolHandle`]: https://docs.rs/tokio-util/latest/tokio_util/task/struct.LocalPoolHandle.html
+//!
+//! # Example
+//!
+//! ```no_run
+#![doc = function_body!("tests/doc/streaming.rs", module_example, [])]
+//! ```
+use std::{cell::RefCell, fmt::Display, future::Future};
+
+use bytes::Bytes;
+use futures::{
+    channel::{mpsc, oneshot},
+    future::{self, LocalBoxFuture},
+    stream::{self, FuturesUnordered},
+    FutureExt, Stream, StreamExt,
+};
+use futures_signals::signal::{self, SignalExt};
+use include_doc::function_body;
+use silkenweb_macros::cfg_browser;
+use silkenweb_signals_ext::value::Sig;
+
+use crate::{
+    document::HtmlDocument,
+    dom::{Dom, Dry},
+    elements::html::div,
+    node::{
+        element::{Element, ParentElement},
+        Node,
+    },
+    serialize::FmtWrite,
+    task::{self, render_now},
+};
+
+/// Render `fallback` until `content` is ready.
+///
+/// When the page is rendered with an [`HtmlStream`], `fallback` is sent with
+/// the document shell, and `content` is streamed when it's ready. Otherwise,
+/// `fallback` is replaced with `content` when it's ready.
+///
+/// The fallback and content are wrapped in a `<div>` with `display: contents`,
+/// so the wrapper doesn't affect layout.
+///
+/// # Hydration
+///
+/// The client doesn't know which content the server has streamed, so it
+/// hydrates `fallback` against the wrapper. Any streamed content is replaced
+/// with `fallback`, which is reported as a hydration mismatch, and `fallback`
+/// is replaced with `content` once it's ready on the client.
+pub fn deferred<D, Content>(
+    fallback: impl Into<Node<D>>,
+    content: impl Future<Output = Content> + 'static,
+) -> Node<D>
+where
+    D: Dom,
+    Content: Into<Node<D>>,
+{
+    let (send_content, receive_content) = oneshot::channel();
+    let load = async move {
+        let content: Node<D> = content.await.into();
+        let html: Box<dyn Display> = Box::new(content.dom_node().clone());
+        // The receiver is only dropped if the wrapper element has been dropped, in
+        // which case we don't care.
+        let _ = send_content.send(content);
+        html
+    };
+
+    let mut fallback = Some(fallback.into());
+    let child = signal::from_future(receive_content).map(move |content| match content {
+        None => fallback.take(),
+        Some(content) => content.ok(),
+    });
+    let wrapper = div::<D>().style_property("display", "contents");
+    let wrapper = match register(load.boxed_local()) {
+        Ok(id) => wrapper.attribute(DEFERRED_ATTR, id.to_string()),
+        Err(load) => {
+            task::spawn_local(load.map(|_| ()));
+            wrapper
+        }
+    };
+
+    wrapper.optional_child(Sig(child)).into()
+}
+
+/// Render a page as a stream of HTML chunks.
+///
+/// See the [module level documentation](self) for more details.
+pub struct HtmlStream<F> {
+    app: F,
+    shell: Shell,
+}
+
+impl<F, N> HtmlStream<F>
+where
+    F: FnOnce() -> N + 'static,
+    N: Into<Node<Dry>>,
+{
+    /// Stream the page produced by `app`.
+    ///
+    /// `app` is called from within the stream's [`task::scope`].
+    pub fn new(app: F) -> Self {
+        Self {
+            app,
+            shell: Shell::Static {
+                before: String::new(),
+                after: String::new(),
+            },
+        }
+    }
+
+    /// Set the document shell to put around the app.
+    ///
+    /// `before` is sent with the first chunk, and would normally open the
+    /// `<body>` element. `after` is sent once all the deferred content has been
+    /// sent, and would normally close the `<body>` element.
+    pub fn shell(mut self, before: impl Into<String>, after: impl Into<String>) -> Self {
+        self.shell = Shell::Static {
+            before: before.into(),
+            after: after.into(),
+        };
+        self
+    }
+
+    /// Use `document` as the document shell.
+    ///
+    /// The opening part of the document is written once the app has rendered,
+    /// so it includes any content mounted in `<head>` by the app. This replaces
+    /// any shell set with [`HtmlStream::shell`].
+    pub fn document(mut self, document: HtmlDocument) -> Self {
+        self.shell = Shell::Document(document);
+        self
+    }
+
+    /// Render the page on the current thread.
+    ///
+    /// The stream isn't `Send`, so it must be polled on the thread that created
+    /// it, for example with a [`tokio::task::LocalSet`]. See
+    /// [`HtmlStream::into_stream`] for a `Send` stream.
+    ///
+    /// [`tokio::task::LocalSet`]: https://docs.rs/tokio/latest/tokio/task/struct.LocalSet.html
+    pub fn into_local_stream(self) -> impl Stream<Item = Bytes> {
+        let (send, receive) = mpsc::unbounded();
+        let render = task::scope(self.render(send));
+
+        stream::select(
+            receive,
+            stream::once(render).filter_map(|()| future::ready(None)),
+        )
+    }
+
+    async fn render(self, sender: mpsc::UnboundedSender<Bytes>) {
+        let send = |html: String| sender.unbounded_send(Bytes::from(html)).is_ok();
+        let _registry = Registry::start();
+        let app = (self.app)().into();
+        render_now().await;
+
+        let mut pending: FuturesUnordered<_> = take_pending().into_iter().collect();
+        let (mut shell, after) = self.shell.render();
+        shell.push_str(&app.to_string());
+
+        if !pending.is_empty() {
+            shell.push_str(FILL_SCRIPT);
+        }
+
+        if !send(shell) {
+            return;
+        }
+
+        while let Some((id, content)) = pending.next().await {
+            // Render `content` in the app, along with anything it depends on.
+            render_now().await;
+            pending.extend(take_pending());
+
+            let fill = format!(
+                r#"<template {FILL_ATTR}="{id}">{content}</template><script>__silkenwebFill({id})</script>"#
+            );
+
+            if !send(fill) {
+                return;
+            }
+        }
+
+        send(after);
+    }
+}
+
+#[cfg_browser(false)]
+impl<F, N> HtmlStream<F>
+where
+    F: FnOnce() -> N + Send + 'static,
+    N: Into<Node<Dry>>,
+{
+    /// Render the page on a thread from `pool`.
+    ///
+    /// Nodes aren't `Send`, so the page is rendered as a task pinned to one of
+    /// `pool`'s threads, and the chunks are sent back over a channel. The
+    /// thread is free to run other tasks while deferred content is loading.
+    ///
+    /// Rendering stops if the stream is dropped.
+    pub fn into_stream(
+        self,
+        pool: &tokio_util::task::LocalPoolHandle,
+    ) -> impl Stream<Item = Bytes> + Send + 'static {
+        let (send, receive) = mpsc::unbounded();
+
+        pool.spawn_pinned(move || async move {
+            let mut html = std::pin::pin!(self.into_local_stream());
+
+            while let Some(chunk) = html.next().await {
+                if send.unbounded_send(chunk).is_err() {
+                    break;
+                }
+            }
+        });
+
+        receive
+    }
+}
+
+enum Shell {
+    Static { before: String, after: String },
+    Document(HtmlDocument),
+}
+
+impl Shell {
+    /// Render the HTML before and after the app.
+    fn render(self) -> (String, String) {
+        match self {
+            Shell::Static { before, after } => (before, after),
+            Shell::Document(document) => {
+                let mut before = String::new();
+                let mut after = String::new();
+                document
+                    .write_open(&mut FmtWrite(&mut before))
+                    .expect("Writing to a `String` shouldn't fail");
+                document
+                    .write_close(&mut FmtWrite(&mut after))
+                    .expect("Writing to a `String` shouldn't fail");
+
+                (before, after)
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TaskLocal(RefCell<Option<Registry>>);
+
+#[derive(Default)]
+struct Registry {
+    next_id: usize,
+    pending: Vec<PendingContent>,
+}
+
+impl Registry {
+    /// Start registering deferred content, until the guard is dropped.
+    fn start() -> impl Drop {
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                task::local::with(|local| local.streaming.0.take());
+            }
+        }
+
+        task::local::with(|local| local.streaming.0.replace(Some(Registry::default())));
+        Guard
+    }
+}
+
+type PendingContent = LocalBoxFuture<'static, (usize, Box<dyn Display>)>;
+
+type LoadContent = LocalBoxFuture<'static, Box<dyn Display>>;
+
+/// Register `load` with the current [`HtmlStream`], or give it back if there
+/// isn't one.
+fn register(load: LoadContent) -> Result<usize, LoadContent> {
+    task::local::with(|local| {
+        let mut registry = local.streaming.0.borrow_mut();
+        let Some(registry) = registry.as_mut() else {
+            return Err(load);
+        };
+        let id = registry.next_id;
+        registry.next_id += 1;
+        registry
+            .pending
+            .push(load.map(move |content| (id, content)).boxed_local());
+
+        Ok(id)
+    })
+}
+
+fn take_pending() -> Vec<PendingContent> {
+    task::local::with(|local| {
+        local
+            .streaming
+            .0
+            .borrow_mut()
+            .as_mut()
+            .map(|registry| std::mem::take(&mut registry.pending))
+            .unwrap_or_default()
+    })
+}
+
+const DEFERRED_ATTR: &str = "data-silkenweb-deferred";
+const FILL_ATTR: &str = "data-silkenweb-fill";
+
+const FILL_SCRIPT: &str = r#"<script>function __silkenwebFill(id){const t=document.querySelector('template[data-silkenweb-fill="'+id+'"]'),p=document.querySelector('[data-silkenweb-deferred="'+id+'"]');if(p){p.replaceChildren(t.content)}t.remove();document.currentScript.remove()}</script>"#;
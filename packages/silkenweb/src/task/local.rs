@@ -1,12 +1,17 @@
 use silkenweb_macros::cfg_browser;
 
-use crate::{document, router};
+use crate::{context, document, hotkey, router, state_transfer, streaming, window};
 
 #[derive(Default)]
 pub struct TaskLocal {
     pub(crate) task: super::TaskLocal,
+    pub(crate) context: context::TaskLocal,
     pub(crate) document: document::TaskLocal,
+    pub(crate) hotkey: hotkey::TaskLocal,
     pub(crate) router: router::TaskLocal,
+    pub(crate) state_transfer: state_transfer::TaskLocal,
+    pub(crate) streaming: streaming::TaskLocal,
+    pub(crate) window: window::TaskLocal,
 }
 
 #[cfg_browser(true)]
@@ -0,0 +1,966 @@
+//! Virtualized lists and grids.
+//!
+//! [`VirtualList`] and [`VirtualGrid`] only render the items that are visible
+//! in a scrolling container, plus a few either side to hide rendering while
+//! scrolling. The container's scroll height is kept correct, so the scrollbar
+//! behaves as if every item was rendered.
+//!
+//! Row elements are reused as the user scrolls, so the render function is
+//! given a [`Row`], which holds signals for the index and item it's currently
+//! showing. Items can come from a [`SignalVec`], or from any indexed data
+//! source with [`VirtualList::from_fn`] and [`VirtualGrid::from_fn`].
+//!
+//! The container should have a fixed height. Its `overflow-y` is set to
+//! `auto`.
+//!
+//! # Example
+//!
+//! ```no_run
+#![doc = function_body!("tests/doc/virtual_list.rs", module_example, [])]
+//! ```
+use std::{
+    cell::{Cell, RefCell},
+    ops::Range,
+    pin::Pin,
+    rc::Rc,
+};
+
+use futures_signals::{
+    map_ref,
+    signal::{Mutable, ReadOnlyMutable, Signal, SignalExt},
+    signal_vec::{MutableVec, SignalVec, SignalVecExt, VecDiff},
+};
+use include_doc::function_body;
+use wasm_bindgen::JsCast;
+
+use crate::{
+    dom::Dom,
+    elements::html::{div, Div},
+    node::{
+        element::{Element, ParentElement},
+        Node,
+    },
+    observer::{ResizeBox, Size},
+    task::on_animation_frame,
+    value::Sig,
+};
+
+/// How many items to render either side of the visible items, by default.
+const DEFAULT_OVERSCAN: usize = 3;
+
+/// The height of rows in a [`VirtualList`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RowHeight {
+    /// Every row has the same height, in CSS pixels.
+    Fixed(f64),
+    /// Rows are measured once they're rendered. Until then, they're assumed to
+    /// be `estimate` CSS pixels high.
+    Measured { estimate: f64 },
+}
+
+/// A virtualized list.
+///
+/// See the [module level documentation](self) for more details.
+pub struct VirtualList<T>(Builder<T>);
+
+impl<T: Clone + 'static> VirtualList<T> {
+    /// A list of the items in `items`.
+    pub fn new(items: impl SignalVec<Item = T> + 'static, row_height: RowHeight) -> Self {
+        Self(Builder::new(
+            Source::Items(items.boxed_local()),
+            Data::Items(RefCell::default()),
+            Layout::list(row_height),
+        ))
+    }
+
+    /// A list of `len` items, where `get` gets the item at an index.
+    ///
+    /// Visible items are fetched again each time `len` yields a value.
+    pub fn from_fn(
+        len: impl Signal<Item = usize> + 'static,
+        get: impl Fn(usize) -> T + 'static,
+        row_height: RowHeight,
+    ) -> Self {
+        Self(Builder::new(
+            Source::Len(len.boxed_local()),
+            Data::Fn(Box::new(get)),
+            Layout::list(row_height),
+        ))
+    }
+
+    /// Render `overscan` rows either side of the visible rows.
+    ///
+    /// Defaults to 3.
+    pub fn overscan(self, overscan: usize) -> Self {
+        Self(self.0.overscan(overscan))
+    }
+
+    /// A [`Scroller`] to programmatically scroll the list.
+    pub fn scroller(&self) -> Scroller {
+        self.0.scroller()
+    }
+
+    /// Render the list into `container`, using `row` to render each row.
+    pub fn render<D, C, E>(self, container: C, row: impl FnMut(Row<T>) -> E + 'static) -> C
+    where
+        D: Dom,
+        C: ParentElement<D>,
+        E: Into<Node<D>>,
+    {
+        self.0.render(container, row)
+    }
+}
+
+/// A virtualized grid, with fixed size cells.
+///
+/// As many columns as will fit in the container are used. See the [module
+/// level documentation](self) for more details.
+pub struct VirtualGrid<T>(Builder<T>);
+
+impl<T: Clone + 'static> VirtualGrid<T> {
+    /// A grid of the items in `items`, with cells `cell_width` by
+    /// `cell_height` CSS pixels.
+    pub fn new(
+        items: impl SignalVec<Item = T> + 'static,
+        cell_width: f64,
+        cell_height: f64,
+    ) -> Self {
+        Self(Builder::new(
+            Source::Items(items.boxed_local()),
+            Data::Items(RefCell::default()),
+            Layout::grid(cell_width, cell_height),
+        ))
+    }
+
+    /// A grid of `len` items, where `get` gets the item at an index.
+    ///
+    /// Visible items are fetched again each time `len` yields a value.
+    pub fn from_fn(
+        len: impl Signal<Item = usize> + 'static,
+        get: impl Fn(usize) -> T + 'static,
+        cell_width: f64,
+        cell_height: f64,
+    ) -> Self {
+        Self(Builder::new(
+            Source::Len(len.boxed_local()),
+            Data::Fn(Box::new(get)),
+            Layout::grid(cell_width, cell_height),
+        ))
+    }
+
+    /// Render `overscan` rows either side of the visible rows.
+    ///
+    /// Defaults to 3.
+    pub fn overscan(self, overscan: usize) -> Self {
+        Self(self.0.overscan(overscan))
+    }
+
+    /// A [`Scroller`] to programmatically scroll the grid.
+    pub fn scroller(&self) -> Scroller {
+        self.0.scroller()
+    }
+
+    /// Render the grid into `container`, using `cell` to render each cell.
+    pub fn render<D, C, E>(self, container: C, cell: impl FnMut(Row<T>) -> E + 'static) -> C
+    where
+        D: Dom,
+        C: ParentElement<D>,
+        E: Into<Node<D>>,
+    {
+        self.0.render(container, cell)
+    }
+}
+
+/// The item a reusable row element is currently showing.
+pub struct Row<T> {
+    index: ReadOnlyMutable<usize>,
+    item: ReadOnlyMutable<T>,
+}
+
+impl<T: Clone + 'static> Row<T> {
+    /// The index of the item.
+    pub fn index(&self) -> impl Signal<Item = usize> {
+        self.index.signal()
+    }
+
+    /// The item.
+    pub fn item(&self) -> impl Signal<Item = T> {
+        self.item.signal_cloned()
+    }
+
+    /// Map the item by reference.
+    pub fn item_ref<U>(&self, f: impl FnMut(&T) -> U) -> impl Signal<Item = U> {
+        self.item.signal_ref(f)
+    }
+}
+
+/// Scroll a [`VirtualList`] or [`VirtualGrid`].
+#[derive(Clone)]
+pub struct Scroller(Rc<dyn Fn(usize)>);
+
+impl Scroller {
+    /// Scroll so the item at `index` is at the top of the container.
+    ///
+    /// This does nothing until the list has been rendered in the browser.
+    pub fn scroll_to_index(&self, index: usize) {
+        (self.0)(index)
+    }
+}
+
+struct Builder<T> {
+    source: Source<T>,
+    state: Rc<State<T>>,
+}
+
+/// Where changes to the data come from.
+enum Source<T> {
+    Items(Pin<Box<dyn SignalVec<Item = T>>>),
+    Len(Pin<Box<dyn Signal<Item = usize>>>),
+}
+
+impl<T: Clone + 'static> Builder<T> {
+    fn new(source: Source<T>, data: Data<T>, layout: Layout) -> Self {
+        Self {
+            source,
+            state: Rc::new(State {
+                data,
+                len: Mutable::new(0),
+                data_version: Mutable::new(0),
+                layout: RefCell::new(layout),
+                layout_version: Mutable::new(0),
+                scroll_top: Mutable::new(0.0),
+                viewport: Mutable::new(Size::default()),
+                overscan: Cell::new(DEFAULT_OVERSCAN),
+                slots: MutableVec::new(),
+                container: RefCell::new(None),
+            }),
+        }
+    }
+
+    fn overscan(self, overscan: usize) -> Self {
+        self.state.overscan.set(overscan);
+        self
+    }
+
+    fn scroller(&self) -> Scroller {
+        let state = self.state.clone();
+        Scroller(Rc::new(move |index| state.scroll_to_index(index)))
+    }
+
+    fn render<D, C, E>(self, container: C, mut row: impl FnMut(Row<T>) -> E + 'static) -> C
+    where
+        D: Dom,
+        C: ParentElement<D>,
+        E: Into<Node<D>>,
+    {
+        let state = self.state;
+        let handle = container.handle();
+        state
+            .container
+            .replace(Some(Box::new(move || handle.try_element())));
+
+        let container = match self.source {
+            Source::Items(items) => container.spawn_future({
+                let state = state.clone();
+                items.for_each(move |diff| {
+                    state.apply_diff(diff);
+                    async {}
+                })
+            }),
+            Source::Len(len) => container.spawn_future({
+                let state = state.clone();
+                len.for_each(move |len| {
+                    state.set_len(len);
+                    async {}
+                })
+            }),
+        };
+
+        let update = map_ref! {
+            let _scroll_top = state.scroll_top.signal(),
+            let _viewport = state.viewport.signal(),
+            let _len = state.len.signal(),
+            let _data_version = state.data_version.signal(),
+            let _layout_version = state.layout_version.signal() => ()
+        };
+
+        let total_height = map_ref! {
+            let len = state.len.signal(),
+            let viewport = state.viewport.signal(),
+            let _layout_version = state.layout_version.signal() => (*len, *viewport)
+        }
+        .map({
+            let state = state.clone();
+            move |(len, viewport)| {
+                format!("{}px", state.layout.borrow().total_height(len, viewport))
+            }
+        });
+
+        let spacer = div::<D>()
+            .style_property("position", "relative")
+            .style_property("height", Sig(total_height))
+            .children_signal(state.slots.signal_vec_cloned().map({
+                let state = state.clone();
+                move |slot| state.render_slot(&slot, &mut row)
+            }));
+
+        container
+            .style_property("overflow-y", "auto")
+            .on("scroll", {
+                let state = state.clone();
+                move |js_ev| {
+                    if let Some(target) = crate::macros::current_target(&js_ev) {
+                        let target: web_sys::Element = target.unchecked_into();
+                        state.scroll_top.set_neq(target.scroll_top().into());
+                    }
+                }
+            })
+            .on_resize(ResizeBox::Content, {
+                let state = state.clone();
+                move |size| state.viewport.set_neq(size)
+            })
+            .spawn_future(update.for_each(move |()| {
+                state.update();
+                async {}
+            }))
+            .child(spacer)
+    }
+}
+
+struct State<T> {
+    data: Data<T>,
+    len: Mutable<usize>,
+    data_version: Mutable<u64>,
+    layout: RefCell<Layout>,
+    layout_version: Mutable<u64>,
+    scroll_top: Mutable<f64>,
+    viewport: Mutable<Size>,
+    overscan: Cell<usize>,
+    slots: MutableVec<Rc<Slot<T>>>,
+    container: RefCell<Option<ElementFn>>,
+}
+
+type ElementFn = Box<dyn Fn() -> Option<web_sys::Element>>;
+
+impl<T: Clone + 'static> State<T> {
+    fn apply_diff(&self, diff: VecDiff<T>) {
+        let Data::Items(items) = &self.data else {
+            return;
+        };
+        let mut items = items.borrow_mut();
+        let mut layout = self.layout.borrow_mut();
+
+        match diff {
+            VecDiff::Replace { values } => {
+                *items = values;
+                layout.reset(items.len());
+            }
+            VecDiff::InsertAt { index, value } => {
+                items.insert(index, value);
+                layout.insert(index);
+            }
+            VecDiff::UpdateAt { index, value } => items[index] = value,
+            VecDiff::RemoveAt { index } => {
+                items.remove(index);
+                layout.remove(index);
+            }
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => {
+                let value = items.remove(old_index);
+                items.insert(new_index, value);
+                layout.relocate(old_index, new_index);
+            }
+            VecDiff::Push { value } => {
+                items.push(value);
+                layout.push();
+            }
+            VecDiff::Pop {} => {
+                items.pop();
+                layout.pop();
+            }
+            VecDiff::Clear {} => {
+                items.clear();
+                layout.reset(0);
+            }
+        }
+
+        self.len.set_neq(items.len());
+        bump(&self.data_version);
+        bump(&self.layout_version);
+    }
+
+    fn set_len(&self, len: usize) {
+        self.layout.borrow_mut().resize(len);
+        self.len.set_neq(len);
+        bump(&self.data_version);
+        bump(&self.layout_version);
+    }
+
+    /// Show the visible items in the slots, adding more slots if we need them.
+    fn update(self: &Rc<Self>) {
+        let len = self.len.get();
+        let viewport = self.viewport.get();
+        let layout = self.layout.borrow();
+        let range = layout.range(len, self.scroll_top.get(), viewport, self.overscan.get());
+        let data_version = self.data_version.get();
+        let mut slots = self.slots.lock_mut();
+
+        while slots.len() < range.len() {
+            let index = range.start + slots.len();
+            let Some(item) = self.data.get(index) else {
+                break;
+            };
+            slots.push_cloned(Rc::new(Slot::new(index, item)));
+        }
+
+        let slot_count = slots.len();
+        let mut assigned = vec![false; slot_count];
+
+        if slot_count > 0 {
+            // Each item always goes in the same slot, so only slots for items that have
+            // scrolled into view need updating.
+            for index in range {
+                let slot_index = index % slot_count;
+                let slot = &slots[slot_index];
+
+                if slot.index.get() != index || slot.data_version.get() != data_version {
+                    let Some(item) = self.data.get(index) else {
+                        continue;
+                    };
+                    slot.index.set(index);
+                    slot.item.set(item);
+                    slot.data_version.set(data_version);
+                }
+
+                slot.position.set_neq(layout.position(index, viewport));
+                slot.visible.set_neq(true);
+                assigned[slot_index] = true;
+            }
+        }
+
+        for (slot, assigned) in slots.iter().zip(assigned) {
+            if !assigned {
+                slot.visible.set_neq(false);
+            }
+        }
+
+        if layout.is_measured() {
+            let slots: Vec<_> = slots
+                .iter()
+                .filter(|slot| slot.visible.get())
+                .cloned()
+                .collect();
+            let state = self.clone();
+
+            on_animation_frame(move || {
+                for slot in slots {
+                    if let Some(element) =
+                        slot.element.borrow().as_ref().and_then(|element| element())
+                    {
+                        state.set_height(
+                            slot.index.get(),
+                            element.get_bounding_client_rect().height(),
+                        );
+                    }
+                }
+            });
+        }
+    }
+
+    fn set_height(&self, index: usize, height: f64) {
+        if self.layout.borrow_mut().set_height(index, height) {
+            bump(&self.layout_version);
+        }
+    }
+
+    fn scroll_to_index(&self, index: usize) {
+        let Some(container) = self
+            .container
+            .borrow()
+            .as_ref()
+            .and_then(|container| container())
+        else {
+            return;
+        };
+        let offset = self
+            .layout
+            .borrow()
+            .position(index.min(self.len.get()), self.viewport.get())
+            .1;
+
+        container.set_scroll_top(offset as i32);
+        self.scroll_top.set_neq(container.scroll_top().into());
+    }
+
+    fn render_slot<D: Dom, E: Into<Node<D>>>(
+        self: &Rc<Self>,
+        slot: &Rc<Slot<T>>,
+        row: &mut impl FnMut(Row<T>) -> E,
+    ) -> Div<D> {
+        let layout = self.layout.borrow();
+        let element = div::<D>()
+            .style_property("position", "absolute")
+            .style_property("top", "0")
+            .style_property("left", "0")
+            .style_property("width", layout.cell_width())
+            .style_property(
+                "transform",
+                Sig(slot
+                    .position
+                    .signal()
+                    .map(|(x, y)| format!("translate({x}px, {y}px)"))),
+            )
+            .attribute("hidden", Sig(slot.visible.signal().map(|visible| !visible)));
+        let element = if let Some(height) = layout.cell_height() {
+            element.style_property("height", height)
+        } else {
+            element.on_resize(ResizeBox::Border, {
+                let state = self.clone();
+                let slot = slot.clone();
+                move |size| {
+                    if slot.visible.get() {
+                        state.set_height(slot.index.get(), size.height)
+                    }
+                }
+            })
+        };
+
+        let handle = element.handle();
+        slot.element
+            .replace(Some(Box::new(move || handle.try_element())));
+
+        element.children([row(Row {
+            index: slot.index.read_only(),
+            item: slot.item.read_only(),
+        })])
+    }
+}
+
+fn bump(version: &Mutable<u64>) {
+    version.replace_with(|version| version.wrapping_add(1));
+}
+
+enum Data<T> {
+    Items(RefCell<Vec<T>>),
+    Fn(Box<dyn Fn(usize) -> T>),
+}
+
+impl<T: Clone> Data<T> {
+    fn get(&self, index: usize) -> Option<T> {
+        match self {
+            Self::Items(items) => items.borrow().get(index).cloned(),
+            Self::Fn(get) => Some(get(index)),
+        }
+    }
+}
+
+/// A reusable element to show an item in.
+struct Slot<T> {
+    index: Mutable<usize>,
+    item: Mutable<T>,
+    data_version: Cell<u64>,
+    position: Mutable<(f64, f64)>,
+    visible: Mutable<bool>,
+    element: RefCell<Option<ElementFn>>,
+}
+
+impl<T> Slot<T> {
+    fn new(index: usize, item: T) -> Self {
+        Self {
+            index: Mutable::new(index),
+            item: Mutable::new(item),
+            data_version: Cell::new(0),
+            position: Mutable::new((0.0, 0.0)),
+            visible: Mutable::new(false),
+            element: RefCell::new(None),
+        }
+    }
+}
+
+/// Where items go.
+enum Layout {
+    List {
+        rows: RowHeights,
+        estimate: f64,
+        measured: bool,
+    },
+    Grid {
+        width: f64,
+        height: f64,
+    },
+}
+
+impl Layout {
+    fn list(row_height: RowHeight) -> Self {
+        let (estimate, measured) = match row_height {
+            RowHeight::Fixed(height) => (height, false),
+            RowHeight::Measured { estimate } => (estimate, true),
+        };
+
+        Self::List {
+            rows: RowHeights::default(),
+            estimate,
+            measured,
+        }
+    }
+
+    fn grid(width: f64, height: f64) -> Self {
+        Self::Grid { width, height }
+    }
+
+    fn is_measured(&self) -> bool {
+        matches!(self, Self::List { measured: true, .. })
+    }
+
+    fn cell_width(&self) -> String {
+        match self {
+            Self::List { .. } => "100%".to_string(),
+            Self::Grid { width, .. } => format!("{width}px"),
+        }
+    }
+
+    fn cell_height(&self) -> Option<String> {
+        match self {
+            Self::List {
+                estimate,
+                measured: false,
+                ..
+            } => Some(format!("{estimate}px")),
+            Self::List { measured: true, .. } => None,
+            Self::Grid { height, .. } => Some(format!("{height}px")),
+        }
+    }
+
+    fn columns(&self, viewport: Size) -> usize {
+        match self {
+            Self::List { .. } => 1,
+            Self::Grid { width, .. } => ((viewport.width / width) as usize).max(1),
+        }
+    }
+
+    fn total_height(&self, len: usize, viewport: Size) -> f64 {
+        match self {
+            Self::List { rows, .. } => rows.total(),
+            Self::Grid { height, .. } => len.div_ceil(self.columns(viewport)) as f64 * height,
+        }
+    }
+
+    /// The items to render.
+    fn range(&self, len: usize, scroll_top: f64, viewport: Size, overscan: usize) -> Range<usize> {
+        if len == 0 {
+            return 0..0;
+        }
+
+        let bottom = scroll_top + viewport.height;
+
+        match self {
+            Self::List { rows, .. } => {
+                let first = rows.index_at(scroll_top);
+                let last = rows.index_at(bottom);
+
+                first.saturating_sub(overscan)..(last + 1 + overscan).min(len)
+            }
+            Self::Grid { height, .. } => {
+                let columns = self.columns(viewport);
+                let first = (scroll_top.max(0.0) / height) as usize;
+                let last = (bottom.max(0.0) / height) as usize;
+
+                first.saturating_sub(overscan) * columns..((last + 1 + overscan) * columns).min(len)
+            }
+        }
+    }
+
+    /// The offset of an item from the top left of the list.
+    fn position(&self, index: usize, viewport: Size) -> (f64, f64) {
+        match self {
+            Self::List { rows, .. } => (0.0, rows.prefix_sum(index.min(rows.len()))),
+            Self::Grid { width, height } => {
+                let columns = self.columns(viewport);
+
+                (
+                    (index % columns) as f64 * width,
+                    (index / columns) as f64 * height,
+                )
+            }
+        }
+    }
+
+    /// Set the height of a row, returning `true` if it changed.
+    fn set_height(&mut self, index: usize, height: f64) -> bool {
+        match self {
+            Self::List {
+                rows,
+                measured: true,
+                ..
+            } if index < rows.len() && (rows.get(index) - height).abs() >= 0.5 => {
+                rows.set(index, height);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn reset(&mut self, len: usize) {
+        if let Self::List { rows, estimate, .. } = self {
+            *rows = RowHeights::new(vec![*estimate; len]);
+        }
+    }
+
+    fn resize(&mut self, len: usize) {
+        if let Self::List { rows, estimate, .. } = self {
+            while rows.len() > len {
+                rows.pop();
+            }
+
+            while rows.len() < len {
+                rows.push(*estimate);
+            }
+        }
+    }
+
+    fn insert(&mut self, index: usize) {
+        if let Self::List { rows, estimate, .. } = self {
+            rows.insert(index, *estimate);
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        if let Self::List { rows, .. } = self {
+            rows.remove(index);
+        }
+    }
+
+    fn relocate(&mut self, old_index: usize, new_index: usize) {
+        if let Self::List { rows, .. } = self {
+            let height = rows.remove(old_index);
+            rows.insert(new_index, height);
+        }
+    }
+
+    fn push(&mut self) {
+        if let Self::List { rows, estimate, .. } = self {
+            rows.push(*estimate);
+        }
+    }
+
+    fn pop(&mut self) {
+        if let Self::List { rows, .. } = self {
+            rows.pop();
+        }
+    }
+}
+
+/// Row heights, stored in a Fenwick tree so we can quickly find the offset of a
+/// row, or the row at an offset, as heights change.
+#[derive(Default)]
+struct RowHeights {
+    heights: Vec<f64>,
+    /// `tree[i - 1]` is the sum of the heights in `(i - lowest_bit(i))..i`.
+    tree: Vec<f64>,
+}
+
+impl RowHeights {
+    fn new(heights: Vec<f64>) -> Self {
+        let mut tree = heights.clone();
+        let len = tree.len();
+
+        for i in 1..=len {
+            let parent = i + lowest_bit(i);
+
+            if parent <= len {
+                tree[parent - 1] += tree[i - 1];
+            }
+        }
+
+        Self { heights, tree }
+    }
+
+    fn len(&self) -> usize {
+        self.heights.len()
+    }
+
+    fn get(&self, index: usize) -> f64 {
+        self.heights[index]
+    }
+
+    fn set(&mut self, index: usize, height: f64) {
+        let delta = height - self.heights[index];
+        self.heights[index] = height;
+        let mut i = index + 1;
+
+        while i <= self.len() {
+            self.tree[i - 1] += delta;
+            i += lowest_bit(i);
+        }
+    }
+
+    /// The total height of the rows before `index`.
+    fn prefix_sum(&self, index: usize) -> f64 {
+        let mut sum = 0.0;
+        let mut i = index;
+
+        while i > 0 {
+            sum += self.tree[i - 1];
+            i -= lowest_bit(i);
+        }
+
+        sum
+    }
+
+    fn total(&self) -> f64 {
+        self.prefix_sum(self.len())
+    }
+
+    /// The index of the row at `offset`, clamped to the rows we have.
+    fn index_at(&self, offset: f64) -> usize {
+        let len = self.len();
+
+        if len == 0 {
+            return 0;
+        }
+
+        let mut index = 0;
+        let mut remaining = offset;
+        let mut step = 1 << len.ilog2();
+
+        while step > 0 {
+            let next = index + step;
+
+            if next <= len && self.tree[next - 1] <= remaining {
+                index = next;
+                remaining -= self.tree[next - 1];
+            }
+
+            step >>= 1;
+        }
+
+        index.min(len - 1)
+    }
+
+    fn push(&mut self, height: f64) {
+        let i = self.len() + 1;
+        let node = height + self.prefix_sum(i - 1) - self.prefix_sum(i - lowest_bit(i));
+        self.heights.push(height);
+        self.tree.push(node);
+    }
+
+    fn pop(&mut self) {
+        self.heights.pop();
+        self.tree.pop();
+    }
+
+    fn insert(&mut self, index: usize, height: f64) {
+        let mut heights = std::mem::take(&mut self.heights);
+        heights.insert(index, height);
+        *self = Self::new(heights);
+    }
+
+    fn remove(&mut self, index: usize) -> f64 {
+        let mut heights = std::mem::take(&mut self.heights);
+        let height = heights.remove(index);
+        *self = Self::new(heights);
+        height
+    }
+}
+
+fn lowest_bit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Layout, RowHeight, RowHeights};
+    use crate::observer::Size;
+
+    fn heights(values: &[f64]) -> RowHeights {
+        RowHeights::new(values.to_vec())
+    }
+
+    #[test]
+    fn prefix_sums() {
+        let rows = heights(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        assert_eq!(
+            (0..=5).map(|i| rows.prefix_sum(i)).collect::<Vec<_>>(),
+            [0.0, 1.0, 3.0, 6.0, 10.0, 15.0]
+        );
+    }
+
+    #[test]
+    fn set_height() {
+        let mut rows = heights(&[1.0; 6]);
+        rows.set(2, 10.0);
+
+        assert_eq!(rows.prefix_sum(2), 2.0);
+        assert_eq!(rows.prefix_sum(3), 12.0);
+        assert_eq!(rows.total(), 15.0);
+    }
+
+    #[test]
+    fn push_matches_new() {
+        let values: Vec<f64> = (1..=13).map(f64::from).collect();
+        let mut pushed = RowHeights::default();
+
+        for &value in &values {
+            pushed.push(value);
+        }
+
+        assert_eq!(pushed.tree, heights(&values).tree);
+
+        pushed.pop();
+        assert_eq!(pushed.tree, heights(&values[..12]).tree);
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut rows = heights(&[1.0, 2.0, 3.0]);
+        rows.insert(1, 10.0);
+        assert_eq!(rows.tree, heights(&[1.0, 10.0, 2.0, 3.0]).tree);
+        assert_eq!(rows.remove(0), 1.0);
+        assert_eq!(rows.tree, heights(&[10.0, 2.0, 3.0]).tree);
+    }
+
+    #[test]
+    fn index_at_offset() {
+        let rows = heights(&[10.0, 20.0, 30.0]);
+
+        assert_eq!(rows.index_at(-5.0), 0);
+        assert_eq!(rows.index_at(0.0), 0);
+        assert_eq!(rows.index_at(9.9), 0);
+        assert_eq!(rows.index_at(10.0), 1);
+        assert_eq!(rows.index_at(29.9), 1);
+        assert_eq!(rows.index_at(30.0), 2);
+        assert_eq!(rows.index_at(1000.0), 2);
+        assert_eq!(RowHeights::default().index_at(10.0), 0);
+    }
+
+    fn viewport(width: f64, height: f64) -> Size {
+        Size { width, height }
+    }
+
+    #[test]
+    fn list_range() {
+        let mut layout = Layout::list(RowHeight::Fixed(10.0));
+        layout.reset(100);
+
+        assert_eq!(layout.range(100, 0.0, viewport(100.0, 50.0), 2), 0..8);
+        assert_eq!(layout.range(100, 205.0, viewport(100.0, 50.0), 2), 18..28);
+        assert_eq!(layout.range(100, 960.0, viewport(100.0, 50.0), 2), 94..100);
+        assert_eq!(layout.range(0, 0.0, viewport(100.0, 50.0), 2), 0..0);
+        assert_eq!(layout.total_height(100, viewport(100.0, 50.0)), 1000.0);
+    }
+
+    #[test]
+    fn grid_range() {
+        let layout = Layout::grid(30.0, 10.0);
+        let viewport = viewport(100.0, 20.0);
+
+        assert_eq!(layout.columns(viewport), 3);
+        assert_eq!(layout.range(100, 0.0, viewport, 1), 0..12);
+        assert_eq!(layout.range(100, 100.0, viewport, 1), 27..42);
+        assert_eq!(layout.position(4, viewport), (30.0, 10.0));
+        assert_eq!(layout.total_height(100, viewport), 340.0);
+    }
+}
@@ -1,10 +1,42 @@
 //! Window utilities.
+//!
+//! As well as window level event handlers, there are signals for the state of
+//! the window and its environment:
+//!
+//! - [`inner_width`] and [`inner_height`]
+//! - [`scroll_x`] and [`scroll_y`]
+//! - [`device_pixel_ratio`]
+//! - [`is_online`]
+//! - [`is_page_visible`]
+//! - [`media_query`], [`prefers_color_scheme`] and [`prefers_reduced_motion`]
+//!
+//! In the browser, each kind of signal shares one listener, which is added the
+//! first time the signal is used.
+//!
+//! On the server, the signals come from [`ServerDefaults`], which can be set
+//! for each [`task::scope`] with [`set_server_defaults`]. This lets server side
+//! rendering assume a viewport, for example from the client's user agent.
+//!
+//! [`task::scope`]: crate::task::scope
+//!
+//! # Example
+//!
+//! ```no_run
+#![doc = function_body!("tests/doc/window.rs", module_example, [])]
+//! ```
+use std::collections::HashMap;
 
+use futures_signals::signal::{Mutable, Signal, SignalExt};
+use include_doc::function_body;
 use paste::paste;
 use silkenweb_base::Window;
+use silkenweb_macros::cfg_browser;
 use wasm_bindgen::JsCast;
 
-use crate::event::{bubbling_events, GlobalEventCallback};
+use crate::{
+    event::{bubbling_events, GlobalEventCallback},
+    task,
+};
 
 /// Manage an event handler.
 ///
@@ -78,3 +110,388 @@ events! {
 }
 
 bubbling_events!();
+
+/// The inner width of the window in CSS pixels, including any scroll bar.
+pub fn inner_width() -> impl Signal<Item = f64> {
+    arch::inner_width()
+}
+
+/// The inner height of the window in CSS pixels, including any scroll bar.
+pub fn inner_height() -> impl Signal<Item = f64> {
+    arch::inner_height()
+}
+
+/// How far the document is scrolled horizontally, in CSS pixels.
+pub fn scroll_x() -> impl Signal<Item = f64> {
+    arch::scroll_x()
+}
+
+/// How far the document is scrolled vertically, in CSS pixels.
+pub fn scroll_y() -> impl Signal<Item = f64> {
+    arch::scroll_y()
+}
+
+/// The number of device pixels per CSS pixel.
+///
+/// This changes when the page is zoomed, or the window moves to a screen with
+/// a different pixel density.
+pub fn device_pixel_ratio() -> impl Signal<Item = f64> {
+    arch::device_pixel_ratio()
+}
+
+/// Does the browser think it's online?
+pub fn is_online() -> impl Signal<Item = bool> {
+    arch::is_online()
+}
+
+/// Is the page visible?
+///
+/// The page is hidden when it's in a background tab, or the window is
+/// minimized.
+pub fn is_page_visible() -> impl Signal<Item = bool> {
+    arch::is_page_visible()
+}
+
+/// Does the document match a CSS media query, like `"(min-width: 600px)"`?
+///
+/// Signals for the same query share a listener. On the server, `query` is
+/// looked up in [`ServerDefaults`], so it must match the query given to
+/// [`ServerDefaults::media_query`] exactly.
+pub fn media_query(query: &str) -> impl Signal<Item = bool> {
+    arch::media_query(query)
+}
+
+/// The user's preferred color scheme.
+pub fn prefers_color_scheme() -> impl Signal<Item = ColorScheme> {
+    media_query(DARK_QUERY).map(|dark| {
+        if dark {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        }
+    })
+}
+
+/// Has the user asked for less motion?
+pub fn prefers_reduced_motion() -> impl Signal<Item = bool> {
+    media_query(REDUCED_MOTION_QUERY)
+}
+
+/// Set the values of the window signals on the server.
+///
+/// This applies to the current [`task::scope`], and updates any existing
+/// signals. It has no effect in the browser.
+///
+/// [`task::scope`]: crate::task::scope
+pub fn set_server_defaults(defaults: ServerDefaults) {
+    task::local::with(|local| local.window.0.set(defaults))
+}
+
+/// A color scheme for [`prefers_color_scheme`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+/// The window state to assume on the server.
+///
+/// The defaults are a 1280x720 window, scrolled to the top left, with a device
+/// pixel ratio of 1. The page is visible and online, and no media queries
+/// match, so the user prefers a light color scheme and doesn't mind motion.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerDefaults {
+    inner_width: f64,
+    inner_height: f64,
+    scroll_x: f64,
+    scroll_y: f64,
+    device_pixel_ratio: f64,
+    online: bool,
+    page_visible: bool,
+    media_queries: HashMap<String, bool>,
+}
+
+impl ServerDefaults {
+    /// The defaults, as described above.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the size of the window's content area, for [`inner_width`] and
+    /// [`inner_height`].
+    pub fn inner_size(mut self, width: f64, height: f64) -> Self {
+        self.inner_width = width;
+        self.inner_height = height;
+        self
+    }
+
+    /// Set the scroll position, for [`scroll_x`] and [`scroll_y`].
+    pub fn scroll(mut self, x: f64, y: f64) -> Self {
+        self.scroll_x = x;
+        self.scroll_y = y;
+        self
+    }
+
+    /// Set the ratio of physical to CSS pixels, for [`device_pixel_ratio`].
+    pub fn device_pixel_ratio(mut self, ratio: f64) -> Self {
+        self.device_pixel_ratio = ratio;
+        self
+    }
+
+    /// Set whether the browser is online, for [`is_online`].
+    pub fn online(mut self, online: bool) -> Self {
+        self.online = online;
+        self
+    }
+
+    /// Set whether the page is visible, for [`is_page_visible`].
+    pub fn page_visible(mut self, visible: bool) -> Self {
+        self.page_visible = visible;
+        self
+    }
+
+    /// Set whether `query` matches.
+    ///
+    /// Media queries that haven't been set don't match.
+    pub fn media_query(mut self, query: impl Into<String>, matches: bool) -> Self {
+        self.media_queries.insert(query.into(), matches);
+        self
+    }
+
+    /// Set the user's preferred color scheme, for [`prefers_color_scheme`].
+    pub fn color_scheme(self, scheme: ColorScheme) -> Self {
+        self.media_query(DARK_QUERY, scheme == ColorScheme::Dark)
+    }
+
+    /// Set whether the user prefers reduced motion, for
+    /// [`prefers_reduced_motion`].
+    pub fn reduced_motion(self, reduced: bool) -> Self {
+        self.media_query(REDUCED_MOTION_QUERY, reduced)
+    }
+}
+
+impl Default for ServerDefaults {
+    fn default() -> Self {
+        Self {
+            inner_width: 1280.0,
+            inner_height: 720.0,
+            scroll_x: 0.0,
+            scroll_y: 0.0,
+            device_pixel_ratio: 1.0,
+            online: true,
+            page_visible: true,
+            media_queries: HashMap::new(),
+        }
+    }
+}
+
+const DARK_QUERY: &str = "(prefers-color-scheme: dark)";
+const REDUCED_MOTION_QUERY: &str = "(prefers-reduced-motion: reduce)";
+
+#[derive(Default)]
+pub(crate) struct TaskLocal(Mutable<ServerDefaults>);
+
+#[cfg_browser(false)]
+mod arch {
+    use futures_signals::signal::Signal;
+
+    use super::ServerDefaults;
+    use crate::task;
+
+    pub fn inner_width() -> impl Signal<Item = f64> {
+        server_default(|defaults| defaults.inner_width)
+    }
+
+    pub fn inner_height() -> impl Signal<Item = f64> {
+        server_default(|defaults| defaults.inner_height)
+    }
+
+    pub fn scroll_x() -> impl Signal<Item = f64> {
+        server_default(|defaults| defaults.scroll_x)
+    }
+
+    pub fn scroll_y() -> impl Signal<Item = f64> {
+        server_default(|defaults| defaults.scroll_y)
+    }
+
+    pub fn device_pixel_ratio() -> impl Signal<Item = f64> {
+        server_default(|defaults| defaults.device_pixel_ratio)
+    }
+
+    pub fn is_online() -> impl Signal<Item = bool> {
+        server_default(|defaults| defaults.online)
+    }
+
+    pub fn is_page_visible() -> impl Signal<Item = bool> {
+        server_default(|defaults| defaults.page_visible)
+    }
+
+    pub fn media_query(query: &str) -> impl Signal<Item = bool> {
+        let query = query.to_string();
+
+        server_default(move |defaults| defaults.media_queries.get(&query).copied().unwrap_or(false))
+    }
+
+    fn server_default<T>(f: impl FnMut(&ServerDefaults) -> T) -> impl Signal<Item = T> {
+        task::local::with(|local| local.window.0.signal_ref(f))
+    }
+}
+
+#[cfg_browser(true)]
+mod arch {
+    use std::{cell::RefCell, collections::HashMap};
+
+    use futures_signals::signal::{Mutable, Signal};
+    use silkenweb_base::{document, window};
+    use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
+
+    use crate::task;
+
+    pub fn inner_width() -> impl Signal<Item = f64> {
+        INNER_SIZE.with(|size| size.signal_ref(|(width, _height)| *width))
+    }
+
+    pub fn inner_height() -> impl Signal<Item = f64> {
+        INNER_SIZE.with(|size| size.signal_ref(|(_width, height)| *height))
+    }
+
+    pub fn scroll_x() -> impl Signal<Item = f64> {
+        SCROLL.with(|scroll| scroll.signal_ref(|(x, _y)| *x))
+    }
+
+    pub fn scroll_y() -> impl Signal<Item = f64> {
+        SCROLL.with(|scroll| scroll.signal_ref(|(_x, y)| *y))
+    }
+
+    pub fn device_pixel_ratio() -> impl Signal<Item = f64> {
+        DEVICE_PIXEL_RATIO.with(Mutable::signal)
+    }
+
+    pub fn is_online() -> impl Signal<Item = bool> {
+        ONLINE.with(Mutable::signal)
+    }
+
+    pub fn is_page_visible() -> impl Signal<Item = bool> {
+        PAGE_VISIBLE.with(Mutable::signal)
+    }
+
+    pub fn media_query(query: &str) -> impl Signal<Item = bool> {
+        MEDIA_QUERIES.with(|queries| {
+            queries
+                .borrow_mut()
+                .entry(query.to_string())
+                .or_insert_with(|| SharedQuery::new(query))
+                .matches
+                .signal()
+        })
+    }
+
+    fn inner_size() -> (f64, f64) {
+        (window::inner_width(), window::inner_height())
+    }
+
+    fn scroll_position() -> (f64, f64) {
+        (window::scroll_x(), window::scroll_y())
+    }
+
+    /// There's no event for device pixel ratio changes, so we watch a
+    /// resolution media query for the current ratio, and replace it each time
+    /// it stops matching.
+    fn watch_device_pixel_ratio() {
+        let query = format!("(resolution: {}dppx)", window::device_pixel_ratio());
+        let watcher = MediaQuery::new(&query, |_matches| {
+            DEVICE_PIXEL_RATIO.with(|ratio| ratio.set_neq(window::device_pixel_ratio()));
+            // We can't drop the current watcher from inside its own callback.
+            task::spawn_local(async { watch_device_pixel_ratio() });
+        });
+
+        DEVICE_PIXEL_RATIO_WATCHER.with(|current| current.replace(Some(watcher)));
+    }
+
+    struct SharedQuery {
+        matches: Mutable<bool>,
+        _query: MediaQuery,
+    }
+
+    impl SharedQuery {
+        fn new(query: &str) -> Self {
+            let matches = Mutable::new(false);
+            let media_query = MediaQuery::new(query, {
+                let matches = matches.clone();
+                move |new_matches| matches.set_neq(new_matches)
+            });
+            matches.set(media_query.list.matches());
+
+            Self {
+                matches,
+                _query: media_query,
+            }
+        }
+    }
+
+    struct MediaQuery {
+        list: web_sys::MediaQueryList,
+        on_change: Closure<dyn FnMut(JsValue)>,
+    }
+
+    impl MediaQuery {
+        fn new(query: &str, mut f: impl FnMut(bool) + 'static) -> Self {
+            let list = window::match_media(query);
+            let on_change = Closure::<dyn FnMut(JsValue)>::new({
+                let list = list.clone();
+                move |_event| f(list.matches())
+            });
+            list.add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref())
+                .unwrap_throw();
+
+            Self { list, on_change }
+        }
+    }
+
+    impl Drop for MediaQuery {
+        fn drop(&mut self) {
+            self.list
+                .remove_event_listener_with_callback(
+                    "change",
+                    self.on_change.as_ref().unchecked_ref(),
+                )
+                .unwrap_throw();
+        }
+    }
+
+    thread_local! {
+        static INNER_SIZE: Mutable<(f64, f64)> = {
+            super::on_resize(|_| INNER_SIZE.with(|size| size.set_neq(inner_size()))).perpetual();
+            Mutable::new(inner_size())
+        };
+
+        static SCROLL: Mutable<(f64, f64)> = {
+            crate::document::on_scroll(|_| SCROLL.with(|scroll| scroll.set_neq(scroll_position())))
+                .perpetual();
+            Mutable::new(scroll_position())
+        };
+
+        static DEVICE_PIXEL_RATIO: Mutable<f64> = {
+            watch_device_pixel_ratio();
+            Mutable::new(window::device_pixel_ratio())
+        };
+
+        static DEVICE_PIXEL_RATIO_WATCHER: RefCell<Option<MediaQuery>> = const { RefCell::new(None) };
+
+        static ONLINE: Mutable<bool> = {
+            super::on_online(|_| ONLINE.with(|online| online.set_neq(true))).perpetual();
+            super::on_offline(|_| ONLINE.with(|online| online.set_neq(false))).perpetual();
+            Mutable::new(window::is_online())
+        };
+
+        static PAGE_VISIBLE: Mutable<bool> = {
+            crate::document::on_visibilitychange(|_| {
+                PAGE_VISIBLE.with(|visible| visible.set_neq(!document::hidden()))
+            })
+            .perpetual();
+            Mutable::new(!document::hidden())
+        };
+
+        static MEDIA_QUERIES: RefCell<HashMap<String, SharedQuery>> = RefCell::new(HashMap::new());
+    }
+}
@@ -0,0 +1,64 @@
+use futures_signals::signal::{Mutable, SignalExt};
+use silkenweb::{
+    context::{self, Context},
+    dom::DefaultDom,
+    elements::html::{div, span, Span},
+    node::{
+        element::{ParentElement, TextParentElement},
+        Node,
+    },
+    task::render_now,
+    value::Sig,
+};
+
+#[derive(Clone)]
+struct Name(&'static str);
+
+fn name_span() -> Span {
+    span().text(context::get::<Name>().map_or("none", |name| name.0))
+}
+
+isomorphic_test! {
+    async fn provide_and_shadow() {
+        let outer = context::provide(Name("outer"), || {
+            div()
+                .child(name_span())
+                .child(context::provide(Name("inner"), name_span))
+                .child(name_span())
+        });
+
+        assert_eq!(
+            Node::<DefaultDom>::from(outer).to_string(),
+            "<div><span>outer</span><span>inner</span><span>outer</span></div>"
+        );
+        assert!(context::get::<Name>().is_none());
+    }
+}
+
+isomorphic_test! {
+    async fn lookup_by_type() {
+        let value = context::provide(Name("name"), || {
+            context::provide(42_u32, || {
+                (context::get::<Name>().map(|name| name.0), context::get::<u32>())
+            })
+        });
+
+        assert_eq!(value, (Some("name"), Some(42)));
+        assert_eq!(context::with(|value: Option<&u32>| value.copied()), None);
+    }
+}
+
+isomorphic_test! {
+    async fn captured_context() {
+        let show = Mutable::new(false);
+        let context = context::provide(Name("captured"), Context::current);
+        let element = div().optional_child(Sig(show.signal().map(move |show| {
+            show.then(|| context.enter(name_span))
+        })));
+        let node = Node::<DefaultDom>::from(element);
+
+        show.set(true);
+        render_now().await;
+        assert_eq!(node.to_string(), "<div><span>captured</span></div>");
+    }
+}
@@ -0,0 +1,33 @@
+use silkenweb::{
+    context,
+    dom::Dry,
+    elements::html::{div, p, Div},
+    node::element::{ParentElement, TextParentElement},
+    task::sync_scope,
+};
+
+pub fn module_example() {
+    #[derive(Clone)]
+    struct Theme {
+        name: &'static str,
+    }
+
+    fn themed_paragraph() -> Div<Dry> {
+        let theme = context::get::<Theme>().map_or("default", |theme| theme.name);
+
+        div().child(p().text(format!("Theme is {theme}")))
+    }
+
+    sync_scope(|| {
+        let app = context::provide(Theme { name: "dark" }, || div().child(themed_paragraph()));
+
+        assert_eq!(
+            app.freeze().to_string(),
+            "<div><div><p>Theme is dark</p></div></div>"
+        );
+        assert_eq!(
+            themed_paragraph().freeze().to_string(),
+            "<div><p>Theme is default</p></div>"
+        );
+    });
+}
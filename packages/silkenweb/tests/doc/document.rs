@@ -0,0 +1,41 @@
+#![cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+
+use silkenweb::{
+    document::{Document, DocumentHead, HtmlDocument, WasmBootstrap},
+    dom::Dry,
+    elements::{
+        html::{div, link, title},
+        HtmlElement,
+    },
+    node::element::TextParentElement,
+    task,
+};
+
+pub fn html_document() {
+    let document = HtmlDocument::new()
+        .lang("en")
+        .head_child(link().rel("stylesheet").href("/style.css"))
+        .body_class("dark")
+        .bootstrap(WasmBootstrap::new("/pkg/client.js").entry("js_main"));
+
+    let page = task::sync_scope(|| {
+        Dry::mount_in_head("head", DocumentHead::new().child(title().text("My App")));
+        let app = div().id("app").text("Hello, world!");
+        task::server::render_now_sync();
+
+        document.render(&app.freeze())
+    });
+
+    assert_eq!(
+        page,
+        [
+            r#"<!DOCTYPE html><html lang="en"><head><meta charset="utf-8">"#,
+            r#"<link rel="stylesheet" href="/style.css">"#,
+            r#"<title data-silkenweb-head-id="head">My App</title>"#,
+            r#"<script type="module">import init, * as client from "/pkg/client.js";"#,
+            r#"init().then(() => client["js_main"]());</script></head>"#,
+            r#"<body class="dark"><div id="app">Hello, world!</div></body></html>"#
+        ]
+        .concat()
+    );
+}
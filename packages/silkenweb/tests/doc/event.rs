@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use silkenweb::{
+    elements::{
+        html::{button, div},
+        ElementEvents,
+    },
+    event::{TypedCustomEvent, TypedEvent},
+    mount,
+    node::element::{Element, ParentElement, TextParentElement},
+};
+
+pub fn typed_event() {
+    #[derive(Serialize, Deserialize)]
+    struct ItemSelected {
+        index: usize,
+    }
+
+    impl TypedEvent for ItemSelected {
+        const NAME: &'static str = "item-selected";
+    }
+
+    let item = button().text("Select");
+    let item_handle = item.handle();
+    let item = item.on_click(move |_, _| {
+        item_handle.dispatch(&ItemSelected { index: 3 });
+    });
+
+    mount(
+        "app",
+        div()
+            .on_custom(|event: TypedCustomEvent<ItemSelected>, _| {
+                silkenweb::dbg!(event.detail().index);
+            })
+            .child(item),
+    );
+}
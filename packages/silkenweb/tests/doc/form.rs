@@ -0,0 +1,55 @@
+use futures_signals::signal::SignalExt;
+use silkenweb::{
+    elements::{
+        html::{button, div, form, input, label, span},
+        HtmlElement,
+    },
+    form::{Field, FormState},
+    mount,
+    node::element::{ParentElement, TextParentElement},
+    value::Sig,
+};
+
+pub fn module_example() {
+    let name = Field::new("name", String::new()).validator(|name| {
+        if name.is_empty() {
+            Err("Please enter a name".to_string())
+        } else {
+            Ok(())
+        }
+    });
+    let age = Field::new("age", 18_u32).validator(|age| {
+        if *age < 18 {
+            Err("You must be 18 or over".to_string())
+        } else {
+            Ok(())
+        }
+    });
+    let state = FormState::new().field(&name).field(&age);
+    let submit_disabled = state.valid_signal().map(|valid| !valid);
+
+    let app = state.on_submit(
+        form()
+            .child(label().r#for("name").text("Name"))
+            .child(name.input(input()))
+            .child(span().id(name.error_id()).text(Sig(
+                name.visible_error_signal().map(Option::unwrap_or_default),
+            )))
+            .child(div().child(label().r#for("age").text("Age")))
+            .child(age.input(input().r#type("number")))
+            .child(span().id(age.error_id()).text(Sig(
+                age.visible_error_signal().map(Option::unwrap_or_default),
+            )))
+            .child(
+                button()
+                    .r#type("submit")
+                    .disabled(Sig(submit_disabled))
+                    .text("Submit"),
+            ),
+        move |_| {
+            silkenweb::dbg!(name.value().get_cloned(), age.value().get());
+        },
+    );
+
+    mount("app", app);
+}
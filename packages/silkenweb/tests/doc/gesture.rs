@@ -0,0 +1,43 @@
+use futures_signals::signal::{Mutable, SignalExt};
+use silkenweb::{
+    elements::html::div,
+    gesture::{Direction, ElementGestures, Phase},
+    mount,
+    node::element::Element,
+    value::Sig,
+};
+
+pub fn module_example() {
+    let offset = Mutable::new(0.0);
+    let slide = Mutable::new(0_usize);
+
+    let carousel = div()
+        .style_property("touch-action", "pan-y")
+        .style_property(
+            "transform",
+            Sig(offset.signal().map(|x| format!("translateX({x}px)"))),
+        )
+        .on_drag({
+            let offset = offset.clone();
+            move |drag, _| {
+                offset.set(match drag.phase {
+                    Phase::Start | Phase::Move => drag.offset.x,
+                    Phase::End | Phase::Cancel => 0.0,
+                })
+            }
+        })
+        .on_swipe(move |swipe, _| {
+            let mut slide = slide.lock_mut();
+
+            match swipe.direction {
+                Direction::Left => *slide += 1,
+                Direction::Right => *slide = slide.saturating_sub(1),
+                Direction::Up | Direction::Down => (),
+            }
+        })
+        .on_long_press(|press, _| {
+            silkenweb::dbg!(press.position);
+        });
+
+    mount("app", carousel);
+}
@@ -0,0 +1,31 @@
+use silkenweb::{
+    elements::html::{div, textarea},
+    hotkey::{self, ElementHotkeys},
+    mount,
+    node::element::ParentElement,
+};
+
+pub fn module_example() {
+    hotkey::bind("Mod+K", "Open the command palette", |_| {
+        silkenweb::dbg!("Command palette");
+    })
+    .perpetual();
+
+    hotkey::bind("?", "Show keyboard shortcuts", |_| {
+        for binding in hotkey::bindings() {
+            silkenweb::dbg!(binding.keys().to_string(), binding.description());
+        }
+    })
+    .perpetual();
+
+    let editor = div()
+        .hotkey("Mod+Shift+K", "Delete line", |_| {
+            silkenweb::dbg!("Delete line");
+        })
+        .hotkey("g i", "Go to inbox", |_| {
+            silkenweb::dbg!("Go to inbox");
+        })
+        .child(textarea());
+
+    mount("app", editor);
+}
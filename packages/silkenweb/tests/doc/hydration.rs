@@ -1,9 +1,17 @@
-use futures_signals::signal::always;
+use futures_signals::signal::{always, Mutable, SignalExt};
+use serde::{Deserialize, Serialize};
 use silkenweb::{
     document::DocumentHead,
-    elements::html::{meta, p},
-    hydration::{hydrate, hydrate_in_head},
-    node::element::TextParentElement,
+    dom::{Dry, InstantiableDom},
+    elements::{
+        html::{button, div, meta, p, Button, Div},
+        ElementEvents,
+    },
+    hydration::{
+        hydrate, hydrate_in_head, hydrate_lazy, island, set_diagnostics, Diagnostics, Islands,
+        LazyHydration,
+    },
+    node::element::{ParentElement, TextParentElement},
     value::Sig,
 };
 use silkenweb_task::spawn_local;
@@ -23,3 +31,56 @@ pub async fn hydrate_in_head_example() {
         hydrate_in_head("my-id", head).await;
     });
 }
+
+pub async fn diagnostics_example() {
+    set_diagnostics(Diagnostics::Record);
+    let app = p().text("Hello, world!");
+
+    spawn_local(async {
+        let stats = hydrate("app", app).await;
+        assert!(stats.mismatches().is_empty(), "{stats}");
+    });
+}
+
+pub async fn islands_example() {
+    #[derive(Serialize, Deserialize)]
+    struct CounterProps {
+        initial: i32,
+    }
+
+    fn counter<D: InstantiableDom>(props: CounterProps) -> Button<D> {
+        let count = Mutable::new(props.initial);
+        let text = count.signal().map(|count| count.to_string());
+
+        button()
+            .on_click(move |_, _| {
+                count.replace_with(|count| *count + 1);
+            })
+            .text(Sig(text))
+    }
+
+    // On the server
+    let page: Div<Dry> = div().child(p().text("Static content")).child(island(
+        "counter",
+        CounterProps { initial: 1 },
+        counter,
+    ));
+    let _html = page.freeze().to_string();
+
+    // On the client
+    spawn_local(async {
+        Islands::new()
+            .register("counter", counter)
+            .register_lazy("footer-counter", LazyHydration::on_idle(), counter)
+            .hydrate()
+            .await;
+    });
+}
+
+pub async fn hydrate_lazy_example() {
+    let app = p().text("Hydrated when visible");
+
+    spawn_local(async {
+        hydrate_lazy("app", app, LazyHydration::on_visible()).await;
+    });
+}
@@ -0,0 +1,55 @@
+use futures_signals::{signal::SignalExt, signal_vec::MutableVec};
+use silkenweb::{
+    elements::{
+        html::{button, div, li, p, ul},
+        ElementEvents,
+    },
+    infinite_scroll::{InfiniteScroll, Page},
+    mount,
+    node::element::{ParentElement, TextParentElement},
+    task::spawn_local,
+    value::Sig,
+};
+
+pub fn module_example() {
+    async fn fetch_stories(page: usize) -> Result<Page<String, usize>, String> {
+        let stories = (0..20)
+            .map(|index| format!("Story {}", page * 20 + index))
+            .collect();
+
+        Ok(Page::new(stories, (page < 10).then_some(page + 1)))
+    }
+
+    let feed = InfiniteScroll::new(MutableVec::new(), 0, fetch_stories);
+    let stories = feed.render(ul(), |story| li().text(story));
+
+    let error = feed.error().map({
+        let feed = feed.clone();
+        move |error| {
+            error.map(|error| {
+                let feed = feed.clone();
+
+                div()
+                    .text(error)
+                    .child(button().text("Retry").on_click(move |_, _| {
+                        let feed = feed.clone();
+                        spawn_local(async move { feed.load_more().await })
+                    }))
+            })
+        }
+    });
+
+    mount(
+        "app",
+        div()
+            .child(stories)
+            .child(feed.sentinel(div()))
+            .optional_child(Sig(feed
+                .is_loading()
+                .map(|loading| loading.then(|| p().text("Loading...")))))
+            .optional_child(Sig(error))
+            .optional_child(Sig(feed
+                .is_done()
+                .map(|done| done.then(|| p().text("That's everything!"))))),
+    );
+}
@@ -1,6 +1,21 @@
 pub mod animation;
+pub mod context;
+pub mod document;
 pub mod dom;
 pub mod elements;
+pub mod event;
+pub mod form;
+pub mod gesture;
+pub mod hotkey;
 pub mod hydration;
+pub mod infinite_scroll;
 pub mod node;
+pub mod observer;
+pub mod query;
 pub mod router;
+pub mod serialize;
+pub mod sortable;
+pub mod state_transfer;
+pub mod streaming;
+pub mod virtual_list;
+pub mod window;
@@ -0,0 +1,37 @@
+use futures_signals::signal::{Mutable, SignalExt};
+use silkenweb::{
+    clone,
+    elements::html::{div, img},
+    mount,
+    node::element::{Element, ParentElement},
+    observer::{IntersectionOptions, ResizeBox},
+    value::Sig,
+};
+
+pub fn module_example() {
+    // Load the image just before it scrolls into view.
+    let load = Mutable::new(false);
+    let image = img()
+        .src(Sig(load.signal().map(|load| load.then_some("photo.jpg"))))
+        .on_intersection(IntersectionOptions::new().root_margin("200px"), {
+            clone!(load);
+            move |intersection| {
+                if intersection.is_intersecting {
+                    load.set(true)
+                }
+            }
+        });
+
+    // Use a compact layout when the panel is narrow.
+    let panel = div();
+    let size = panel.size_signal(ResizeBox::Content);
+    let panel = panel.class(Sig(size.map(|size| {
+        if size.width < 600.0 {
+            "compact"
+        } else {
+            "wide"
+        }
+    })));
+
+    mount("app", div().child(image).child(panel));
+}
@@ -0,0 +1,49 @@
+use silkenweb::{
+    dom::Dry,
+    elements::{
+        html::{a, h1, h2, main, nav, p},
+        HtmlElement,
+    },
+    node::{
+        element::{ParentElement, TextParentElement},
+        Node,
+    },
+    query::QuerySelector,
+};
+
+pub fn module_example() {
+    let page: Node<Dry> = main()
+        .child(h1().text("Title"))
+        .child(h2().id("intro").text("Introduction"))
+        .child(p().text("Welcome"))
+        .child(h2().id("usage").text("Usage"))
+        .child(nav().child(a().href("http://example.com/docs").text("Docs")))
+        .into();
+
+    // Extract headings for a table of contents.
+    let headings: Vec<_> = page
+        .query_selector_all("h2[id]")
+        .unwrap()
+        .into_iter()
+        .map(|heading| (heading.attribute("id").unwrap(), heading.text()))
+        .collect();
+    assert_eq!(
+        headings,
+        [
+            ("intro".to_string(), "Introduction".to_string()),
+            ("usage".to_string(), "Usage".to_string())
+        ]
+    );
+
+    // Rewrite links.
+    for link in page.query_selector_all(r#"a[href^="http:"]"#).unwrap() {
+        let href = link.attribute("href").unwrap();
+        link.set_attribute("href", href.replacen("http:", "https:", 1));
+    }
+
+    let link = page.query_selector("nav > a").unwrap().unwrap();
+    assert_eq!(
+        link.to_string(),
+        r#"<a href="https://example.com/docs">Docs</a>"#
+    );
+}
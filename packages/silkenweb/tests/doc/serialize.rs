@@ -0,0 +1,52 @@
+use silkenweb::{
+    dom::Dry,
+    elements::{
+        html::{li, ul},
+        svg::{attributes::Presentation, circle, svg},
+    },
+    node::{
+        element::{ParentElement, TextParentElement},
+        Node,
+    },
+    serialize::{FmtWrite, HtmlSerializer, PrettyPrinter, Syntax, ToHtml, XML_DECLARATION},
+};
+
+pub fn module_example() {
+    let list: Node<Dry> = ul()
+        .child(li().text("Bread & butter"))
+        .child(li().text("Jam"))
+        .into();
+    let html = "<ul><li>Bread &amp; butter</li><li>Jam</li></ul>";
+
+    // Write into an `io::Write`, reusing the serializer's buffer.
+    let mut serializer = HtmlSerializer::new();
+    let mut output = Vec::new();
+    serializer.write(&list, &mut output).unwrap();
+    assert_eq!(output, html.as_bytes());
+
+    // Write into a `bytes::BufMut`.
+    let mut buffer = Vec::new();
+    list.write_html(&mut buffer).unwrap();
+    assert_eq!(buffer, html.as_bytes());
+
+    // Write into a `fmt::Write`.
+    let mut text = String::new();
+    list.write_html(&mut FmtWrite(&mut text)).unwrap();
+    assert_eq!(text, html);
+
+    // Pretty print, for snapshot tests.
+    assert_eq!(
+        PrettyPrinter::new().print(&list),
+        "<ul>\n  <li>Bread &amp; butter</li>\n  <li>Jam</li>\n</ul>"
+    );
+
+    // Export a standalone SVG file.
+    let chart: Node<Dry> = svg().child(circle().fill("blue")).into();
+    let mut serializer = HtmlSerializer::new().syntax(Syntax::Xml);
+    let mut file = XML_DECLARATION.as_bytes().to_vec();
+    serializer.write(&chart, &mut file).unwrap();
+    assert_eq!(
+        file,
+        br#"<?xml version="1.0" encoding="UTF-8"?><svg xmlns="http://www.w3.org/2000/svg"><circle fill="blue"/></svg>"#
+    );
+}
@@ -0,0 +1,24 @@
+use futures_signals::signal_vec::MutableVec;
+use silkenweb::{
+    elements::html::{div, li, ul},
+    mount,
+    node::element::{Element, ParentElement, TextParentElement},
+    sortable::{Group, Sortable},
+};
+
+pub fn module_example() {
+    let todo = MutableVec::new_with_values(vec!["Write docs", "Fix bugs"]);
+    let done = MutableVec::new_with_values(vec!["Add tests"]);
+    let group = Group::new();
+
+    let list = |items: &MutableVec<&'static str>| {
+        Sortable::new(items.clone())
+            .group(&group)
+            .label(|item| item.to_string())
+            .render(ul(), |item| {
+                li().style_property("touch-action", "none").text(item)
+            })
+    };
+
+    mount("app", div().child(list(&todo)).child(list(&done)));
+}
@@ -0,0 +1,32 @@
+#![cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+
+use silkenweb::{
+    document::HtmlDocument,
+    elements::html::{li, ul},
+    node::element::{ParentElement, TextParentElement},
+    state_transfer::StateKey,
+    task,
+};
+
+pub fn module_example() {
+    const TODOS: StateKey<Vec<String>> = StateKey::new("todos");
+
+    let page = task::sync_scope(|| {
+        // On the server, this runs the query. When the client hydrates, it uses
+        // the server's value instead.
+        let todos = TODOS.get_or_insert_with(|| vec!["Write docs".to_string()]);
+        let app = ul().children(todos.iter().map(|todo| li().text(todo)));
+
+        HtmlDocument::new().render(&app.freeze())
+    });
+
+    assert_eq!(
+        page,
+        [
+            r#"<!DOCTYPE html><html><head><meta charset="utf-8">"#,
+            r#"<script type="application/json" id="silkenweb-state">{"todos":["Write docs"]}</script>"#,
+            r#"</head><body><ul><li>Write docs</li></ul></body></html>"#,
+        ]
+        .concat()
+    );
+}
@@ -0,0 +1,37 @@
+#![cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use silkenweb::{
+    elements::html::{div, h1, p},
+    node::element::{ParentElement, TextParentElement},
+    streaming::{deferred, HtmlStream},
+    time::sleep,
+};
+use tokio_util::task::LocalPoolHandle;
+
+pub fn module_example() {
+    let local_pool = LocalPoolHandle::new(4);
+    let html = HtmlStream::new(|| {
+        let comments = async {
+            // Pretend to fetch some comments.
+            sleep(Duration::from_millis(500)).await;
+            div().child(p().text("First!"))
+        };
+
+        div()
+            .child(h1().text("My Blog Post"))
+            .child(deferred(p().text("Loading comments..."), comments))
+    })
+    .shell(
+        "<!DOCTYPE html><html><head><title>Blog</title></head><body>",
+        "</body></html>",
+    )
+    .into_stream(&local_pool);
+
+    // The stream is `Send`, so it can be used as a response body.
+    tokio::spawn(html.for_each(|chunk| async move {
+        print!("{}", String::from_utf8_lossy(&chunk));
+    }));
+}
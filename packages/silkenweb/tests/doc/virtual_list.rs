@@ -0,0 +1,35 @@
+use futures_signals::signal::always;
+use silkenweb::{
+    elements::{
+        html::{button, div},
+        ElementEvents,
+    },
+    mount,
+    node::element::{Element, ParentElement, TextParentElement},
+    value::Sig,
+    virtual_list::{RowHeight, VirtualList},
+};
+
+pub fn module_example() {
+    let list = VirtualList::from_fn(
+        always(100_000),
+        |index| format!("Row {index}"),
+        RowHeight::Fixed(24.0),
+    );
+    let scroller = list.scroller();
+
+    let rows = list.render(div().style_property("height", "400px"), |row| {
+        div().text(Sig(row.item()))
+    });
+
+    mount(
+        "app",
+        div()
+            .child(
+                button()
+                    .text("Go to row 50,000")
+                    .on_click(move |_, _| scroller.scroll_to_index(50_000)),
+            )
+            .child(rows),
+    );
+}
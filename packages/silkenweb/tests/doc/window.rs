@@ -0,0 +1,37 @@
+use futures_signals::{map_ref, signal::SignalExt};
+use silkenweb::{
+    elements::html::{div, p},
+    mount,
+    node::element::{Element, ParentElement, TextParentElement},
+    value::Sig,
+    window::{self, ColorScheme},
+};
+
+pub fn module_example() {
+    let theme = window::prefers_color_scheme().map(|scheme| match scheme {
+        ColorScheme::Light => "theme-light",
+        ColorScheme::Dark => "theme-dark",
+    });
+    let layout = window::media_query("(min-width: 800px)").map(|wide| {
+        if wide {
+            "layout-wide"
+        } else {
+            "layout-narrow"
+        }
+    });
+    let size = map_ref! {
+        let width = window::inner_width(),
+        let height = window::inner_height() =>
+        format!("The window is {width}x{height}")
+    };
+    let offline = window::is_online().map(|online| (!online).then(|| p().text("Offline")));
+
+    mount(
+        "app",
+        div()
+            .class(Sig(theme))
+            .class(Sig(layout))
+            .child(p().text(Sig(size)))
+            .optional_child(Sig(offline)),
+    );
+}
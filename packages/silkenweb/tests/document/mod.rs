@@ -0,0 +1,52 @@
+use silkenweb::{
+    document::{HtmlDocument, WasmBootstrap},
+    elements::html::{meta, p},
+    node::element::TextParentElement,
+};
+
+isomorphic_test! {
+    async fn render_document() {
+        let document = HtmlDocument::new()
+            .head_child(meta().name("description").content("A \"quoted\" page"))
+            .body_class("light")
+            .body_class("wide")
+            .body_attribute("data-theme", "<dark>");
+
+        assert_eq!(
+            document.render(&p().text("Body").freeze()),
+            [
+                r#"<!DOCTYPE html><html><head><meta charset="utf-8">"#,
+                r#"<meta name="description" content="A &quot;quoted&quot; page"></head>"#,
+                r#"<body class="light wide" data-theme="&lt;dark&gt;"><p>Body</p></body></html>"#,
+            ]
+            .concat()
+        );
+    }
+}
+
+isomorphic_test! {
+    async fn body_attributes() {
+        let document = HtmlDocument::new()
+            .body_class("light")
+            .body_attribute("class", "wide")
+            .body_attribute("data-theme", "light")
+            .body_attribute("data-theme", "dark");
+
+        assert!(document
+            .render(&p().freeze())
+            .contains(r#"<body class="light wide" data-theme="dark">"#));
+    }
+}
+
+isomorphic_test! {
+    async fn bootstrap_script() {
+        let document = HtmlDocument::new().bootstrap(
+            WasmBootstrap::new("/pkg/app.js").wasm("/pkg/</script>.wasm"),
+        );
+        let html = document.render(&p().freeze());
+
+        assert!(html.contains(
+            r#"<script type="module">import init, * as client from "/pkg/app.js";init({module_or_path: "/pkg/\u003c/script\u003e.wasm"});</script>"#
+        ));
+    }
+}
@@ -0,0 +1,388 @@
+use std::cell::RefCell;
+
+use futures::channel::oneshot;
+use futures_signals::signal::Mutable;
+use silkenweb::{
+    dom::{DefaultDom, Dry},
+    elements::html::{details, div, input, option, select, textarea},
+    form::{Field, FormState},
+    node::{
+        element::{ParentElement, TextParentElement},
+        Node,
+    },
+    task::render_now,
+};
+
+isomorphic_test! {
+    async fn bind_value() {
+        let value = Mutable::new(42);
+        let node: Node<DefaultDom> = input().bind_value(value.clone()).into();
+
+        render_now().await;
+        assert_eq!(node.to_string(), r#"<input value="42">"#);
+
+        value.set(7);
+        render_now().await;
+        assert_eq!(node.to_string(), r#"<input value="7">"#);
+    }
+}
+
+isomorphic_test! {
+    async fn bind_checked() {
+        let checked = Mutable::new(true);
+        let node: Node<DefaultDom> = input().r#type("checkbox").bind_checked(checked.clone()).into();
+
+        render_now().await;
+        assert_eq!(node.to_string(), r#"<input type="checkbox" checked="">"#);
+
+        checked.set(false);
+        render_now().await;
+        assert_eq!(node.to_string(), r#"<input type="checkbox">"#);
+    }
+}
+
+isomorphic_test! {
+    async fn bind_group() {
+        let group = Mutable::new(1);
+        let node: Node<DefaultDom> = div()
+            .child(input().r#type("radio").bind_group(1, group.clone()))
+            .child(input().r#type("radio").bind_group(2, group.clone()))
+            .into();
+
+        render_now().await;
+        assert_eq!(
+            node.to_string(),
+            r#"<div><input type="radio" value="1" checked=""><input type="radio" value="2"></div>"#
+        );
+
+        group.set(2);
+        render_now().await;
+        assert_eq!(
+            node.to_string(),
+            r#"<div><input type="radio" value="1"><input type="radio" value="2" checked=""></div>"#
+        );
+    }
+}
+
+isomorphic_test! {
+    async fn bind_textarea_value() {
+        let value = Mutable::new("Some <text>".to_string());
+        let node: Node<Dry> = textarea().bind_value(value.clone()).into();
+
+        render_now().await;
+        assert_eq!(node.to_string(), "<textarea>Some &lt;text></textarea>");
+
+        // Only the initial value is rendered as text.
+        value.set("Some other text".to_string());
+        render_now().await;
+        assert_eq!(node.to_string(), "<textarea>Some &lt;text></textarea>");
+    }
+}
+
+isomorphic_test! {
+    async fn bind_selected() {
+        let selected = Mutable::new(2);
+        let node: Node<Dry> = select()
+            .bind_selected(selected.clone())
+            .child(option().value("1").text("One"))
+            .child(option().value("2").text("Two"))
+            .into();
+
+        render_now().await;
+        assert_eq!(
+            node.to_string(),
+            r#"<select><option value="1">One</option><option value="2" selected="">Two</option></select>"#
+        );
+
+        selected.set(1);
+        render_now().await;
+        assert_eq!(
+            node.to_string(),
+            r#"<select><option value="1" selected="">One</option><option value="2">Two</option></select>"#
+        );
+    }
+}
+
+isomorphic_test! {
+    async fn bind_open() {
+        let open = Mutable::new(false);
+        let node: Node<DefaultDom> = details().bind_open(open.clone()).into();
+
+        render_now().await;
+        assert_eq!(node.to_string(), "<details></details>");
+
+        open.set(true);
+        render_now().await;
+        assert_eq!(node.to_string(), r#"<details open=""></details>"#);
+    }
+}
+
+isomorphic_test! {
+    async fn field_validation() {
+        let field = Field::new("name", String::new()).validator(|name| {
+            if name.is_empty() {
+                Err("Required".to_string())
+            } else {
+                Ok(())
+            }
+        });
+        let state = FormState::new().field(&field);
+        let node: Node<DefaultDom> = field.input(input()).into();
+
+        render_now().await;
+        assert!(!field.is_valid());
+        assert!(!state.is_valid());
+        assert_eq!(node.to_string(), r#"<input id="name" value="">"#);
+
+        state.touch_all();
+        render_now().await;
+        assert_eq!(
+            node.to_string(),
+            r#"<input id="name" value="" aria-invalid="true" aria-describedby="name-error">"#
+        );
+
+        field.value().set("Alice".to_string());
+        render_now().await;
+        assert!(field.is_valid());
+        assert!(state.is_valid());
+        assert_eq!(node.to_string(), r#"<input id="name" value="Alice">"#);
+    }
+}
+
+isomorphic_test! {
+    async fn async_field_validation() {
+        let (send, receive) = oneshot::channel();
+        let receive = RefCell::new(Some(receive));
+        let field = Field::new("name", 0).async_validator(move |_| {
+            let receive = receive.borrow_mut().take();
+
+            async move {
+                match receive {
+                    Some(receive) => receive.await.unwrap(),
+                    None => Ok(()),
+                }
+            }
+        });
+
+        render_now().await;
+        assert!(!field.is_valid());
+
+        send.send(Err("Invalid".to_string())).unwrap();
+        render_now().await;
+        assert!(!field.is_valid());
+
+        field.value().set(1);
+        render_now().await;
+        assert!(field.is_valid());
+    }
+}
+
+#[silkenweb::cfg_browser(true)]
+mod browser_tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use futures::channel::oneshot;
+    use futures_signals::signal::Mutable;
+    use silkenweb::{
+        elements::html::{div, form, input, option, select, textarea},
+        form::{Field, FormState},
+        mount,
+        node::element::{Element, ParentElement, TextParentElement},
+        task::render_now,
+    };
+    use silkenweb_test::BrowserTest;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::APP_ID;
+
+    const CONTROL_ID: &str = "control";
+
+    fn control<T: JsCast>() -> T {
+        silkenweb_base::document::get_element_by_id(CONTROL_ID)
+            .unwrap()
+            .dyn_into()
+            .unwrap()
+    }
+
+    fn dispatch(name: &str) {
+        control::<web_sys::Element>()
+            .dispatch_event(&web_sys::Event::new(name).unwrap())
+            .unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn input_to_mutable() {
+        let _test = BrowserTest::new(APP_ID).await;
+        let value = Mutable::new(1);
+        mount(
+            APP_ID,
+            div().child(input().id(CONTROL_ID).bind_value(value.clone())),
+        );
+        render_now().await;
+
+        let elem = control::<web_sys::HtmlInputElement>();
+        elem.set_value("2");
+        dispatch("input");
+        assert_eq!(value.get(), 2);
+
+        // Text that doesn't parse is left alone
+        elem.set_value("2x");
+        dispatch("input");
+        assert_eq!(value.get(), 2);
+        render_now().await;
+        assert_eq!(elem.value(), "2x");
+    }
+
+    #[wasm_bindgen_test]
+    async fn textarea_to_mutable() {
+        let _test = BrowserTest::new(APP_ID).await;
+        let value = Mutable::new("initial".to_string());
+        mount(
+            APP_ID,
+            div().child(textarea().id(CONTROL_ID).bind_value(value.clone())),
+        );
+        render_now().await;
+
+        let elem = control::<web_sys::HtmlTextAreaElement>();
+        assert_eq!(elem.value(), "initial");
+
+        elem.set_value("edited");
+        dispatch("input");
+        assert_eq!(value.get_cloned(), "edited");
+
+        value.set("updated".to_string());
+        render_now().await;
+        assert_eq!(elem.value(), "updated");
+        assert_eq!(elem.text_content().unwrap(), "");
+    }
+
+    #[wasm_bindgen_test]
+    async fn radio_group_to_mutable() {
+        let _test = BrowserTest::new(APP_ID).await;
+        let group = Mutable::new(0);
+        let radio = |value: i32| {
+            input()
+                .id(format!("radio-{value}"))
+                .r#type("radio")
+                .name("group")
+                .bind_group(value, group.clone())
+        };
+        mount(APP_ID, div().child(radio(1)).child(radio(2)));
+        render_now().await;
+
+        let radio_elem = |value: i32| -> web_sys::HtmlInputElement {
+            silkenweb_base::document::get_element_by_id(&format!("radio-{value}"))
+                .unwrap()
+                .dyn_into()
+                .unwrap()
+        };
+        let check = |value: i32| {
+            let elem = radio_elem(value);
+            elem.set_checked(true);
+            elem.dispatch_event(&web_sys::Event::new("change").unwrap())
+                .unwrap();
+        };
+
+        check(1);
+        assert_eq!(group.get(), 1);
+
+        check(2);
+        assert_eq!(group.get(), 2);
+        render_now().await;
+        assert!(!radio_elem(1).checked());
+        assert!(radio_elem(2).checked());
+
+        group.set(1);
+        render_now().await;
+        assert!(radio_elem(1).checked());
+        assert!(!radio_elem(2).checked());
+    }
+
+    #[wasm_bindgen_test]
+    async fn select_to_mutable() {
+        let _test = BrowserTest::new(APP_ID).await;
+        let selected = Mutable::new(1);
+        mount(
+            APP_ID,
+            div().child(
+                select()
+                    .id(CONTROL_ID)
+                    .child(option().value("1").text("One"))
+                    .child(option().value("2").text("Two"))
+                    .bind_selected(selected.clone()),
+            ),
+        );
+        render_now().await;
+
+        let elem = control::<web_sys::HtmlSelectElement>();
+        assert_eq!(elem.value(), "1");
+
+        elem.set_value("2");
+        dispatch("change");
+        assert_eq!(selected.get(), 2);
+
+        selected.set(1);
+        render_now().await;
+        assert_eq!(elem.value(), "1");
+    }
+
+    #[wasm_bindgen_test]
+    async fn submit_waits_for_validation() {
+        let _test = BrowserTest::new(APP_ID).await;
+        let (send, receive) = oneshot::channel();
+        let receive = Rc::new(Cell::new(Some(receive)));
+        let field = Field::new("name", 0).async_validator(move |_| {
+            let receive = receive.take();
+
+            async move {
+                match receive {
+                    Some(receive) => receive.await.unwrap(),
+                    None => Ok(()),
+                }
+            }
+        });
+        let state = FormState::new().field(&field);
+        let submitted = Rc::new(Cell::new(0));
+        let app = state.on_submit(form().id(CONTROL_ID).child(field.input(input())), {
+            let submitted = submitted.clone();
+            move |_| submitted.set(submitted.get() + 1)
+        });
+        mount(APP_ID, div().child(app));
+        render_now().await;
+        assert!(state.is_validating());
+
+        dispatch("submit");
+        render_now().await;
+        assert_eq!(submitted.get(), 0, "Submit waits for validation");
+
+        send.send(Ok(())).unwrap();
+        render_now().await;
+        assert_eq!(submitted.get(), 1, "Submitted once validation passed");
+
+        dispatch("submit");
+        assert_eq!(submitted.get(), 2, "Submitted straight away when valid");
+    }
+
+    #[wasm_bindgen_test]
+    async fn form_values_as() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Values {
+            name: String,
+            age: u32,
+        }
+
+        let form = form()
+            .child(input().name("name").value("Alice"))
+            .child(input().name("age").value("42"));
+        render_now().await;
+
+        assert_eq!(
+            form.values_as::<Values>().unwrap(),
+            Values {
+                name: "Alice".to_string(),
+                age: 42
+            }
+        );
+    }
+}
@@ -0,0 +1,86 @@
+#[silkenweb::cfg_browser(true)]
+mod browser_tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use silkenweb::{
+        elements::html::{button, div},
+        gesture::{ElementGestures, Phase, Point},
+        mount,
+        node::element::{Element, ParentElement},
+        task::render_now,
+    };
+    use silkenweb_test::{html_element, BrowserTest};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::APP_ID;
+
+    const TARGET_ID: &str = "target";
+    const BUTTON_ID: &str = "button";
+    const POINTER_ID: i32 = 1;
+
+    fn dispatch(id: &str, name: &str, x: i32, y: i32) {
+        let init = web_sys::PointerEventInit::new();
+        init.set_bubbles(true);
+        init.set_pointer_id(POINTER_ID);
+        init.set_buttons(1);
+        init.set_client_x(x);
+        init.set_client_y(y);
+
+        html_element(id)
+            .dispatch_event(&web_sys::PointerEvent::new_with_event_init_dict(name, &init).unwrap())
+            .unwrap();
+    }
+
+    async fn mount_draggable() -> Rc<RefCell<Vec<(Phase, Point)>>> {
+        let drags = Rc::new(RefCell::new(Vec::new()));
+
+        mount(
+            APP_ID,
+            div()
+                .id(TARGET_ID)
+                .on_drag({
+                    let drags = drags.clone();
+                    move |drag, _| drags.borrow_mut().push((drag.phase, drag.offset))
+                })
+                .child(button().id(BUTTON_ID)),
+        );
+        render_now().await;
+
+        drags
+    }
+
+    #[wasm_bindgen_test]
+    async fn drag_threshold() {
+        let _test = BrowserTest::new(APP_ID).await;
+        let drags = mount_draggable().await;
+
+        dispatch(TARGET_ID, "pointerdown", 10, 10);
+        dispatch(TARGET_ID, "pointermove", 11, 10);
+        assert!(drags.borrow().is_empty(), "Below threshold");
+
+        dispatch(TARGET_ID, "pointermove", 20, 10);
+        dispatch(TARGET_ID, "pointerup", 20, 10);
+        assert_eq!(
+            *drags.borrow(),
+            [
+                (Phase::Start, Point::new(10.0, 0.0)),
+                (Phase::End, Point::new(10.0, 0.0))
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn no_capture_on_pointer_down() {
+        let _test = BrowserTest::new(APP_ID).await;
+        let drags = mount_draggable().await;
+
+        dispatch(BUTTON_ID, "pointerdown", 10, 10);
+        assert!(
+            !html_element(TARGET_ID).has_pointer_capture(POINTER_ID),
+            "Clicks on children aren't retargeted"
+        );
+
+        dispatch(BUTTON_ID, "pointerup", 10, 10);
+        assert!(drags.borrow().is_empty(), "A click isn't a drag");
+    }
+}
@@ -0,0 +1,42 @@
+use silkenweb::{
+    dom::DefaultDom,
+    elements::html::div,
+    hotkey::{self, ElementHotkeys},
+    node::Node,
+    task::render_now,
+};
+
+fn binding_descriptions() -> Vec<(String, String, bool)> {
+    hotkey::bindings()
+        .into_iter()
+        .map(|binding| {
+            (
+                binding.keys().to_string(),
+                binding.description().to_string(),
+                binding.is_scoped(),
+            )
+        })
+        .collect()
+}
+
+isomorphic_test! {
+    async fn bindings() {
+        let global = hotkey::bind("ctrl+shift+k", "Delete line", |_| ());
+        let element: Node<DefaultDom> = div().hotkey("g i", "Go to inbox", |_| ()).into();
+
+        assert_eq!(
+            binding_descriptions(),
+            [
+                ("Ctrl+Shift+K".to_string(), "Delete line".to_string(), false),
+                ("G I".to_string(), "Go to inbox".to_string(), true)
+            ]
+        );
+
+        drop(element);
+        render_now().await;
+        assert_eq!(binding_descriptions().len(), 1, "Element binding removed");
+
+        drop(global);
+        assert!(binding_descriptions().is_empty(), "Global binding removed");
+    }
+}
@@ -1,24 +1,68 @@
+use serde::{Deserialize, Serialize};
+use silkenweb::{
+    dom::{Dry, InstantiableDom},
+    elements::html::{p, P},
+    hydration::island,
+    node::element::TextParentElement,
+};
+
+#[derive(Serialize, Deserialize)]
+struct GreetingProps {
+    name: String,
+}
+
+fn greeting<D: InstantiableDom>(props: GreetingProps) -> P<D> {
+    p().text(format!("Hello, {}!", props.name))
+}
+
+isomorphic_test! {
+    async fn island_markup() {
+        let app: P<Dry> = island(
+            "greeting",
+            GreetingProps {
+                name: "<world>".to_string(),
+            },
+            greeting,
+        );
+
+        assert_eq!(
+            app.freeze().to_string(),
+            r#"<p data-silkenweb-island="greeting" data-silkenweb-island-props="{&quot;name&quot;:&quot;&lt;world&gt;&quot;}">Hello, &lt;world>!</p>"#
+        );
+    }
+}
+
 #[silkenweb::cfg_browser(true)]
 mod browser_tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use futures::future::join;
     use futures_signals::signal::Mutable;
     use silkenweb::{
+        clone,
         dom::Hydro,
         elements::{
             html::{self, button, div, p},
             ElementEvents, HtmlElement,
         },
-        hydration::hydrate,
+        event::ListenerOptions,
+        hydration::{
+            hydrate, hydrate_lazy, island, set_diagnostics, Diagnostics, Islands, LazyHydration,
+            MismatchKind,
+        },
         node::element::{
             Const, Element, GenericElement, ParentElement, ShadowRootParent, TextParentElement,
         },
         task::render_now,
         value::Sig,
     };
-    use silkenweb_base::document::create_element;
+    use silkenweb_base::document::{self, create_element};
     use silkenweb_test::{html_element, BrowserTest};
+    use wasm_bindgen::{prelude::Closure, JsCast};
     use wasm_bindgen_test::wasm_bindgen_test;
     use web_sys::{ShadowRootInit, ShadowRootMode};
 
+    use super::{greeting, GreetingProps};
     use crate::APP_ID;
 
     #[wasm_bindgen_test]
@@ -198,6 +242,46 @@ mod browser_tests {
         );
     }
 
+    #[wasm_bindgen_test]
+    async fn event_once() {
+        const BUTTON_ID: &str = "increment";
+
+        let test = app_container(
+            APP_ID,
+            r#"<button id="increment" data-silkenweb="button-data">+</button>"#,
+        )
+        .await;
+
+        let count = Mutable::new(0);
+
+        hydrate(
+            APP_ID,
+            div().id(APP_ID).child(
+                button()
+                    .id(BUTTON_ID)
+                    .on_click_with_options(ListenerOptions::new().once(true), {
+                        let count = count.clone();
+                        move |_, _| {
+                            count.replace_with(|i| *i + 1);
+                        }
+                    })
+                    .text("+"),
+            ),
+        )
+        .await;
+
+        render_now().await;
+        html_element(BUTTON_ID).click();
+        html_element(BUTTON_ID).click();
+        render_now().await;
+        assert_eq!(count.get(), 1, "Listener only called once");
+
+        assert_eq!(
+            test.html(),
+            r#"<div id="app"><button id="increment" data-silkenweb="button-data">+</button></div>"#
+        );
+    }
+
     #[wasm_bindgen_test]
     async fn style_property() {
         let html = r#"<div data-silkenweb="1" style="--test0: value0; --test1: value1;"></div>"#;
@@ -313,6 +397,222 @@ mod browser_tests {
         );
     }
 
+    #[wasm_bindgen_test]
+    async fn diagnostics() {
+        let _test = app_container(
+            APP_ID,
+            r#"<ul><li class="old">One</li><li>2</li><li>Three</li><span></span></ul>"#,
+        )
+        .await;
+        let app = div().id(APP_ID).child(
+            html::ul()
+                .child(html::li().text("One"))
+                .child(html::li().text("Two"))
+                .child(html::li().text("Three")),
+        );
+
+        render_now().await;
+        set_diagnostics(Diagnostics::Record);
+        let stats = hydrate(APP_ID, app).await;
+        set_diagnostics(Diagnostics::Off);
+
+        let mismatches: Vec<_> = stats
+            .mismatches()
+            .iter()
+            .map(|mismatch| (mismatch.path(), mismatch.kind().clone()))
+            .collect();
+
+        assert_eq!(
+            mismatches,
+            [
+                (
+                    "div#app > ul > li:nth-child(1)",
+                    MismatchKind::AttributeRemoved {
+                        name: "class".to_string(),
+                        actual: "old".to_string()
+                    }
+                ),
+                (
+                    "div#app > ul > li:nth-child(2)",
+                    MismatchKind::Text {
+                        expected: r#""Two""#.to_string(),
+                        actual: r#""2""#.to_string()
+                    }
+                ),
+                (
+                    "div#app > ul > li:nth-child(2)",
+                    MismatchKind::NodeRemoved {
+                        actual: r#""2""#.to_string()
+                    }
+                ),
+                (
+                    "div#app > ul > span:nth-child(4)",
+                    MismatchKind::NodeRemoved {
+                        actual: "<span>".to_string()
+                    }
+                ),
+            ]
+        );
+        assert_eq!(stats.attributes_removed(), 1);
+        assert_eq!(stats.nodes_added(), 1);
+        assert_eq!(stats.nodes_removed(), 2);
+    }
+
+    #[wasm_bindgen_test]
+    async fn diagnostics_off() {
+        let _test = app_container(APP_ID, r#"<p class="old"></p>"#).await;
+        let app = div().id(APP_ID).child(p());
+
+        render_now().await;
+        let stats = hydrate(APP_ID, app).await;
+
+        assert_eq!(stats.attributes_removed(), 1);
+        assert!(stats.mismatches().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    async fn islands() {
+        let test = app_container(APP_ID, "").await;
+        let server_html = |name: &str| {
+            island(
+                name,
+                GreetingProps {
+                    name: "world".to_string(),
+                },
+                greeting::<silkenweb::dom::Dry>,
+            )
+            .freeze()
+            .to_string()
+        };
+        let registered = server_html("greeting");
+        let unregistered = server_html("other");
+        let page = format!(
+            r#"<p id="static">Static</p>{registered}<div>{unregistered}</div>{registered}"#
+        );
+        html_element(APP_ID).set_inner_html(&page);
+
+        render_now().await;
+        let stats = Islands::new()
+            .register("greeting", |props| {
+                greeting::<Hydro>(props).on_click(|_, _| ())
+            })
+            .hydrate()
+            .await;
+
+        assert!(stats.exact_match());
+        assert_eq!(format!(r#"<div id="app">{page}</div>"#), test.html());
+    }
+
+    #[wasm_bindgen_test]
+    async fn nested_islands() {
+        let test = app_container(
+            APP_ID,
+            r#"<div data-silkenweb-island="outer"><p data-silkenweb-island="greeting" data-silkenweb-island-props='{"name":"world"}'>Hello, world!</p></div>"#,
+        )
+        .await;
+
+        render_now().await;
+        let stats = Islands::new()
+            .register("greeting", greeting::<Hydro>)
+            .register("outer", |()| -> html::Div<Hydro> { div() })
+            .hydrate()
+            .await;
+
+        assert_eq!(stats.nodes_removed(), 1);
+        assert_eq!(
+            r#"<div id="app"><div data-silkenweb-island="outer"></div></div>"#,
+            test.html()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn lazy_on_interaction() {
+        let test = app_container(APP_ID, r#"<p class="old">Text</p>"#).await;
+        let pointer_downs = Mutable::new(0);
+        let app = div()
+            .id(APP_ID)
+            .on_pointerdown({
+                clone!(pointer_downs);
+                move |_, _| pointer_downs.replace_with(|count| *count + 1)
+            })
+            .child(p().text("Text"));
+
+        render_now().await;
+        let hydration = hydrate_lazy(APP_ID, app, LazyHydration::on_interaction());
+        let interaction = async {
+            render_now().await;
+            assert_eq!(
+                r#"<div id="app"><p class="old">Text</p></div>"#,
+                test.html(),
+                "Not hydrated before interaction"
+            );
+            html_element(APP_ID)
+                .dispatch_event(&web_sys::Event::new("pointerdown").unwrap())
+                .unwrap();
+        };
+
+        join(hydration, interaction).await;
+
+        assert_eq!(r#"<div id="app"><p>Text</p></div>"#, test.html());
+        assert_eq!(pointer_downs.get(), 1, "Event is replayed after hydration");
+    }
+
+    #[wasm_bindgen_test]
+    async fn lazy_replay_stays_in_root() {
+        let test = app_container(APP_ID, r#"<span>Text</span>"#).await;
+        let ancestor_pointer_downs = Rc::new(Cell::new(0));
+        let ancestor_listener = Closure::<dyn FnMut(web_sys::Event)>::new({
+            clone!(ancestor_pointer_downs);
+            move |_| ancestor_pointer_downs.set(ancestor_pointer_downs.get() + 1)
+        });
+        let body = document::body().unwrap();
+        body.add_event_listener_with_callback(
+            "pointerdown",
+            ancestor_listener.as_ref().unchecked_ref(),
+        )
+        .unwrap();
+
+        let pointer_downs = Mutable::new(0);
+        let app = div().id(APP_ID).child(p().text("Text").on_pointerdown({
+            clone!(pointer_downs);
+            move |_, _| pointer_downs.replace_with(|count| *count + 1)
+        }));
+
+        render_now().await;
+        let hydration = hydrate_lazy(APP_ID, app, LazyHydration::on_interaction());
+        let interaction = async {
+            render_now().await;
+            let init = web_sys::CustomEventInit::new();
+            init.set_bubbles(true);
+            html_element(APP_ID)
+                .first_element_child()
+                .unwrap()
+                .dispatch_event(
+                    &web_sys::CustomEvent::new_with_event_init_dict("pointerdown", &init).unwrap(),
+                )
+                .unwrap();
+        };
+
+        join(hydration, interaction).await;
+
+        body.remove_event_listener_with_callback(
+            "pointerdown",
+            ancestor_listener.as_ref().unchecked_ref(),
+        )
+        .unwrap();
+        assert_eq!(r#"<div id="app"><p>Text</p></div>"#, test.html());
+        assert_eq!(
+            pointer_downs.get(),
+            1,
+            "Event is replayed on the element that replaced the target"
+        );
+        assert_eq!(
+            ancestor_pointer_downs.get(),
+            1,
+            "Replayed event doesn't propagate beyond the root"
+        );
+    }
+
     async fn app_container(id: &str, inner_html: &str) -> BrowserTest {
         let test = BrowserTest::new(id).await;
         html_element(id).set_inner_html(inner_html);
@@ -0,0 +1,123 @@
+use futures::StreamExt;
+use futures_signals::{signal::SignalExt, signal_vec::MutableVec};
+use silkenweb::{
+    dom::DefaultDom,
+    elements::html::{div, li, ul},
+    infinite_scroll::{InfiniteScroll, Page, Status},
+    node::{
+        element::{ParentElement, TextParentElement},
+        Node,
+    },
+    task::render_now,
+};
+
+async fn load_page(page: usize) -> Result<Page<String, usize>, String> {
+    match page {
+        0 | 1 => Ok(Page::new(
+            vec![format!("{page}a"), format!("{page}b")],
+            Some(page + 1),
+        )),
+        2 => Ok(Page::new(vec!["2a".to_string()], None)),
+        _ => Err("No such page".to_string()),
+    }
+}
+
+async fn status<T, C, E>(feed: &InfiniteScroll<T, C, E>) -> Status<E>
+where
+    T: Clone + 'static,
+    C: Clone + 'static,
+    E: Clone + 'static,
+{
+    feed.status().to_stream().next().await.unwrap()
+}
+
+isomorphic_test! {
+    async fn load_pages() {
+        let items = MutableVec::new();
+        let feed = InfiniteScroll::new(items.clone(), 0, load_page);
+
+        feed.load_more().await;
+        assert_eq!(*items.lock_ref(), ["0a", "0b"]);
+        assert_eq!(status(&feed).await, Status::Idle);
+
+        feed.load_more().await;
+        feed.load_more().await;
+        assert_eq!(*items.lock_ref(), ["0a", "0b", "1a", "1b", "2a"]);
+        assert_eq!(status(&feed).await, Status::Done);
+
+        feed.load_more().await;
+        assert_eq!(items.lock_ref().len(), 5, "Nothing is loaded once done");
+    }
+}
+
+isomorphic_test! {
+    async fn load_error() {
+        let items = MutableVec::new();
+        let feed = InfiniteScroll::new(items.clone(), 3, load_page);
+
+        feed.load_more().await;
+        assert!(items.lock_ref().is_empty());
+        assert_eq!(status(&feed).await, Status::Error("No such page".to_string()));
+    }
+}
+
+isomorphic_test! {
+    async fn render_first_page() {
+        let feed = InfiniteScroll::new(MutableVec::new(), 0, load_page);
+        feed.load_more().await;
+        let node: Node<DefaultDom> = div()
+            .child(feed.render(ul(), |item| li().text(item)))
+            .child(feed.sentinel(div()))
+            .into();
+
+        render_now().await;
+        assert_eq!(
+            node.to_string(),
+            "<div><ul><li>0a</li><li>0b</li></ul><div></div></div>"
+        );
+    }
+}
+
+#[silkenweb::cfg_browser(true)]
+mod browser_tests {
+    use std::time::Duration;
+
+    use futures_signals::signal_vec::MutableVec;
+    use silkenweb::{
+        elements::html::{div, li, ul},
+        infinite_scroll::{InfiniteScroll, Status},
+        mount,
+        node::element::{Element, ParentElement, TextParentElement},
+        time::sleep,
+    };
+    use silkenweb_test::BrowserTest;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{load_page, status};
+    use crate::APP_ID;
+
+    #[wasm_bindgen_test]
+    async fn page_hides_sentinel() {
+        let _test = BrowserTest::new(APP_ID).await;
+        let items = MutableVec::new();
+        let feed = InfiniteScroll::new(items.clone(), 0, load_page).root_margin("0px");
+
+        mount(
+            APP_ID,
+            div()
+                .child(feed.render(ul(), |item| {
+                    li().style_property("height", "200vh").text(item)
+                }))
+                .child(feed.sentinel(div().style_property("height", "1px"))),
+        );
+
+        // Give the observer a few frames to report the sentinel's new position.
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            *items.lock_ref(),
+            ["0a", "0b"],
+            "Only one page is loaded once the sentinel is out of view"
+        );
+        assert_eq!(status(&feed).await, Status::Idle);
+    }
+}
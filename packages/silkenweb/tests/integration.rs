@@ -16,15 +16,30 @@ macro_rules! isomorphic_test {
 
 mod children;
 mod component;
+mod context;
 mod css;
+mod document;
 mod element;
+mod form;
+mod gesture;
 mod head;
+mod hotkey;
 mod hydration;
+mod infinite_scroll;
+mod observer;
+mod query;
+mod serialize;
+mod sortable;
+mod state_transfer;
+mod streaming;
 mod template;
+mod virtual_list;
+mod window;
 
 #[silkenweb::cfg_browser(true)]
 mod browser_tests {
     use futures_signals::signal::Mutable;
+    use serde::{Deserialize, Serialize};
     use silkenweb::{
         document::{Document, DocumentHead},
         dom::DefaultDom,
@@ -32,8 +47,12 @@ mod browser_tests {
             html::{button, div, p, P},
             ElementEvents, HtmlElement,
         },
+        event::{DispatchOptions, TypedCustomEvent, TypedEvent},
         mount,
-        node::element::{ParentElement, TextParentElement},
+        node::{
+            element::{Element, ParentElement, TextParentElement},
+            Component,
+        },
         task::render_now,
         value::Sig,
     };
@@ -158,6 +177,190 @@ mod browser_tests {
         );
     }
 
+    #[wasm_bindgen_test]
+    async fn mount_and_unmount() {
+        let _test = BrowserTest::new(APP_ID).await;
+
+        let show = Mutable::new(true);
+        let events = Mutable::new(Vec::new());
+
+        mount(
+            APP_ID,
+            div().optional_child(Sig(show.signal_ref({
+                let events = events.clone();
+                move |&show| {
+                    show.then(|| {
+                        p().id(TEXT_ID)
+                            .on_mount({
+                                let events = events.clone();
+                                move |elem| {
+                                    assert!(elem.is_connected());
+                                    events.lock_mut().push("mount")
+                                }
+                            })
+                            .on_unmount({
+                                let events = events.clone();
+                                move |elem| {
+                                    assert!(!elem.is_connected());
+                                    events.lock_mut().push("unmount")
+                                }
+                            })
+                    })
+                }
+            }))),
+        );
+
+        render_now().await;
+        assert_eq!(*events.lock_ref(), ["mount"]);
+        show.set(false);
+        render_now().await;
+        assert_eq!(*events.lock_ref(), ["mount", "unmount"]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn mount_when_attached() {
+        let _test = BrowserTest::new(APP_ID).await;
+
+        let events = Mutable::new(Vec::new());
+        let app = div().child(
+            p().on_mount({
+                let events = events.clone();
+                move |_| events.lock_mut().push("mount")
+            })
+            .on_unmount({
+                let events = events.clone();
+                move |_| events.lock_mut().push("unmount")
+            }),
+        );
+
+        render_now().await;
+        assert!(events.lock_ref().is_empty(), "Not mounted while detached");
+
+        mount(APP_ID, app);
+        render_now().await;
+        assert_eq!(*events.lock_ref(), ["mount"]);
+
+        DefaultDom::unmount_all();
+        render_now().await;
+        assert_eq!(*events.lock_ref(), ["mount", "unmount"]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn unmount_when_dropped_while_attached() {
+        let _test = BrowserTest::new(APP_ID).await;
+
+        let events = Mutable::new(Vec::new());
+        let paragraph = p()
+            .on_mount({
+                let events = events.clone();
+                move |_| events.lock_mut().push("mount")
+            })
+            .on_unmount({
+                let events = events.clone();
+                move |_| events.lock_mut().push("unmount")
+            });
+        html_element(APP_ID)
+            .append_child(&paragraph.handle().dom_element())
+            .unwrap();
+
+        render_now().await;
+        assert_eq!(*events.lock_ref(), ["mount"]);
+
+        drop(paragraph);
+        assert_eq!(*events.lock_ref(), ["mount", "unmount"]);
+    }
+
+    #[cfg(feature = "delegated-events")]
+    #[wasm_bindgen_test]
+    async fn delegated_events() {
+        const BUTTON_ID: &str = "button";
+
+        let _test = BrowserTest::new(APP_ID).await;
+        let clicks = Mutable::new(Vec::new());
+        let on_click = |name: &'static str, stop: bool| {
+            let clicks = clicks.clone();
+            move |ev: web_sys::MouseEvent, elem: web_sys::HtmlDivElement| {
+                clicks.lock_mut().push((name, elem.id()));
+
+                if stop {
+                    ev.stop_propagation();
+                }
+            }
+        };
+
+        mount(
+            APP_ID,
+            div()
+                .id("outer")
+                .on_click(on_click("outer", false))
+                .child(
+                    div()
+                        .id("middle")
+                        .on_click(on_click("middle-stop", true))
+                        .child(div().id("inner").on_click(on_click("inner", false))),
+                )
+                .child(button().id(BUTTON_ID).text("Click")),
+        );
+
+        render_now().await;
+        html_element("inner").click();
+        html_element(BUTTON_ID).click();
+
+        assert_eq!(
+            *clicks.lock_ref(),
+            [
+                ("inner", "inner".to_string()),
+                ("middle-stop", "middle".to_string()),
+                ("outer", "outer".to_string())
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn typed_event_crosses_shadow_root() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Selected {
+            index: usize,
+            name: String,
+        }
+
+        impl TypedEvent for Selected {
+            const NAME: &'static str = "selected";
+        }
+
+        let _test = BrowserTest::new(APP_ID).await;
+        let received = Mutable::new(Vec::new());
+        let inner = div();
+        let inner_handle = inner.handle();
+
+        mount(
+            APP_ID,
+            div()
+                .on_custom({
+                    let received = received.clone();
+                    move |event: TypedCustomEvent<Selected>, _| {
+                        received.lock_mut().push(event.into_detail())
+                    }
+                })
+                .child(Component::<DefaultDom>::new().child(inner)),
+        );
+
+        render_now().await;
+        let selected = || Selected {
+            index: 1,
+            name: "one".to_string(),
+        };
+
+        assert!(inner_handle.dispatch(&selected()));
+        assert!(
+            received.lock_ref().is_empty(),
+            "Event shouldn't leave the shadow root"
+        );
+
+        inner_handle.dispatch_with_options(&selected(), DispatchOptions::new().composed(true));
+        assert_eq!(*received.lock_ref(), [selected()]);
+    }
+
     async fn verify_reactive_text(paragraph: P, text_id: &str, text: &mut Mutable<&'static str>) {
         mount(APP_ID, paragraph);
         render_now().await;
@@ -0,0 +1,57 @@
+use futures::StreamExt;
+use futures_signals::signal::SignalExt;
+use silkenweb::{
+    dom::Dry,
+    elements::html::{div, Div},
+    node::element::Element,
+    observer::{Intersection, IntersectionOptions, ResizeBox, Size},
+};
+
+isomorphic_test! {
+    async fn dry_defaults() {
+        let element: Div<Dry> = div();
+        let mut intersection = element
+            .intersection_signal(IntersectionOptions::new())
+            .to_stream();
+        let mut size = element.size_signal(ResizeBox::Content).to_stream();
+
+        assert_eq!(intersection.next().await, Some(Intersection::default()));
+        assert_eq!(size.next().await, Some(Size::default()));
+    }
+}
+
+#[silkenweb::cfg_browser(true)]
+mod browser_tests {
+    use futures_signals::signal::SignalExt;
+    use silkenweb::{
+        dom::Hydro,
+        elements::html::{div, p, P},
+        hydration::hydrate,
+        node::element::{Element, ParentElement},
+        observer::ResizeBox,
+        task::render_now,
+    };
+    use silkenweb_test::{html_element, BrowserTest};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::APP_ID;
+
+    #[wasm_bindgen_test]
+    async fn observe_after_hydration() {
+        const STYLE: &str = "width: 100px";
+
+        let _test = BrowserTest::new(APP_ID).await;
+        html_element(APP_ID).set_inner_html(&format!(r#"<p style="{STYLE}"></p>"#));
+
+        let observed: P<Hydro> = p().attribute("style", STYLE);
+        let width = observed
+            .size_signal(ResizeBox::Content)
+            .map(|size| size.width);
+        render_now().await;
+
+        hydrate(APP_ID, div().id(APP_ID).child(observed)).await;
+        render_now().await;
+
+        width.wait_for(100.0).await;
+    }
+}
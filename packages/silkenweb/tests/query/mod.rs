@@ -0,0 +1,108 @@
+use silkenweb::{
+    dom::Dry,
+    elements::{
+        html::{div, em, li, p, span, ul},
+        svg::{self, rect},
+        HtmlElement,
+    },
+    node::{
+        element::{Element, ParentElement, TextParentElement},
+        Node,
+    },
+    query::{NodeRef, QuerySelector},
+};
+
+fn list() -> Node<Dry> {
+    div()
+        .class("root")
+        .child(
+            ul().id("items")
+                .child(li().class("item").text("One"))
+                .child(li().classes(["item", "selected"]).text("Two"))
+                .child(li().class("item").text("Three")),
+        )
+        .child(
+            p().text("Some ")
+                .child(em().text("emphasized"))
+                .text(" text"),
+        )
+        .child(svg::svg().child(rect().attribute("data-size", "large")))
+        .into()
+}
+
+fn texts(selectors: &str) -> Vec<String> {
+    list()
+        .query_selector_all(selectors)
+        .unwrap()
+        .into_iter()
+        .map(|element| element.text())
+        .collect()
+}
+
+isomorphic_test! {
+    async fn combinators() {
+        assert_eq!(texts("#items > .item"), ["One", "Two", "Three"]);
+        assert_eq!(texts("div li.selected"), ["Two"]);
+        assert_eq!(texts(".selected + li"), ["Three"]);
+        assert_eq!(texts(".selected ~ li, li:first-child"), ["One", "Three"]);
+        assert_eq!(texts("li:nth-child(2n + 1):not(:last-child)"), ["One"]);
+        assert_eq!(texts("p > em"), ["emphasized"]);
+    }
+}
+
+isomorphic_test! {
+    async fn attributes() {
+        assert_eq!(texts(r#"[class~="selected"]"#), ["Two"]);
+        assert_eq!(texts(r#"[id^="it"] li:last-of-type"#), ["Three"]);
+        assert_eq!(list().query_selector_all(r#"rect[data-size="large"]"#).unwrap().len(), 1);
+        assert!(list().query_selector("[data-size=small]").unwrap().is_none());
+    }
+}
+
+isomorphic_test! {
+    async fn scope() {
+        let list = list();
+        let items = list.query_selector("#items").unwrap().unwrap();
+
+        assert!(list.query_selector(".root").unwrap().is_none());
+        assert_eq!(items.query_selector_all("li").unwrap().len(), 3);
+        // Like the browser, ancestors outside the scope still take part in matching.
+        assert!(items.query_selector("div li").unwrap().is_some());
+        assert!(items.matches("div > ul").unwrap());
+        assert_eq!(items.parent().unwrap().tag(), "div");
+    }
+}
+
+isomorphic_test! {
+    async fn read_element() {
+        let paragraph = list().query_selector("p").unwrap().unwrap();
+        let children = paragraph.children();
+
+        assert_eq!(paragraph.text(), "Some emphasized text");
+        assert_eq!(children.len(), 3);
+        assert!(matches!(&children[0], NodeRef::Text(text) if text == "Some "));
+        assert!(matches!(&children[1], NodeRef::Element(element) if element.tag() == "em"));
+
+        let styled: Node<Dry> = div()
+            .child(span().id("styled").style_property("color", "red"))
+            .into();
+        let span = styled.query_selector("#styled").unwrap().unwrap();
+
+        assert_eq!(
+            span.attributes(),
+            [
+                ("id".to_string(), "styled".to_string()),
+                ("style".to_string(), "color: red;".to_string())
+            ]
+        );
+        assert_eq!(span.attribute("style").unwrap(), "color: red;");
+        assert!(span.matches(r#"[style*="red"]"#).unwrap());
+    }
+}
+
+isomorphic_test! {
+    async fn invalid_selector() {
+        assert!(list().query_selector("li[").is_err());
+        assert!(list().query_selector_all(":hover").is_err());
+    }
+}
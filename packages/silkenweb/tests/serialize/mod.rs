@@ -0,0 +1,138 @@
+use silkenweb::{
+    dom::Dry,
+    elements::{
+        html::{a, br, div, li, p, pre, section, span, ul},
+        svg::{self, attributes::Presentation, rect, text},
+        HtmlElement,
+    },
+    node::{
+        element::{Element, ParentElement, TextParentElement},
+        Node,
+    },
+    serialize::{HtmlSerializer, PrettyPrinter, Syntax, ToHtml},
+};
+
+isomorphic_test! {
+    async fn pretty_blocks() {
+        let app: Node<Dry> = section()
+            .child(ul().child(li().text("One")).child(li().text("Two")))
+            .child(div())
+            .into();
+
+        assert_eq!(
+            PrettyPrinter::new().print(&app),
+            "<section>\n  \
+                <ul>\n    \
+                    <li>One</li>\n    \
+                    <li>Two</li>\n  \
+                </ul>\n  \
+                <div></div>\n\
+            </section>"
+        );
+    }
+}
+
+isomorphic_test! {
+    async fn pretty_indent() {
+        let app: Node<Dry> = div().child(div().child(br())).into();
+
+        assert_eq!(
+            PrettyPrinter::new().indent(4).print(&app),
+            "<div>\n    <div><br></div>\n</div>"
+        );
+    }
+}
+
+isomorphic_test! {
+    async fn pretty_preserves_whitespace() {
+        let app: Node<Dry> = div()
+            .child(div().text("Hello, ").child(span().text("world")))
+            .child(div().child(a().text("link")).child(span().text("!")))
+            .child(pre().child(div().text("  preformatted  ")))
+            .into();
+
+        assert_eq!(
+            PrettyPrinter::new().print(&app),
+            "<div>\n  \
+                <div>Hello, <span>world</span></div>\n  \
+                <div><a>link</a><span>!</span></div>\n  \
+                <pre><div>  preformatted  </div></pre>\n\
+            </div>"
+        );
+    }
+}
+
+isomorphic_test! {
+    async fn pretty_sorted() {
+        let app = || -> Node<Dry> {
+            div()
+                .id("app")
+                .classes(["b", "c", "a"])
+                .attribute("aria-label", "App")
+                .style_property("color", "red")
+                .into()
+        };
+
+        assert_eq!(
+            PrettyPrinter::new().print(&app()),
+            r#"<div id="app" class="b c a" aria-label="App" style="color: red;"></div>"#
+        );
+        assert_eq!(
+            PrettyPrinter::new()
+                .sort_attributes(true)
+                .sort_classes(true)
+                .print(&app()),
+            r#"<div aria-label="App" class="a b c" id="app" style="color: red;"></div>"#
+        );
+    }
+}
+
+isomorphic_test! {
+    async fn compact_unchanged() {
+        let app: Node<Dry> = div().child(div().child(div())).into();
+
+        assert_eq!(app.to_string(), "<div><div><div></div></div></div>");
+    }
+}
+
+fn to_xml(node: &impl ToHtml) -> String {
+    let mut xml = Vec::new();
+    node.write_xml(None, &mut xml).unwrap();
+    String::from_utf8(xml).unwrap()
+}
+
+isomorphic_test! {
+    async fn xml_svg() {
+        let chart: Node<Dry> = svg::svg()
+            .attribute("viewBox", "0 0 10 10")
+            .child(rect().fill("red"))
+            .child(text().text("1 < 2 > 0"))
+            .into();
+
+        assert_eq!(
+            to_xml(&chart),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">"#.to_string()
+                + r#"<rect fill="red"/><text>1 &lt; 2 &gt; 0</text></svg>"#
+        );
+    }
+}
+
+isomorphic_test! {
+    async fn xml_namespaces() {
+        let page: Node<Dry> = div()
+            .child(br())
+            .child(svg::svg().child(rect().attribute("xlink:title", "Box")))
+            .child(p().text("Text"))
+            .into();
+
+        assert_eq!(
+            HtmlSerializer::new()
+                .syntax(Syntax::Xml)
+                .to_bytes(&page),
+            r#"<div xmlns="http://www.w3.org/1999/xhtml"><br/>"#.to_string()
+                + r#"<svg xmlns="http://www.w3.org/2000/svg">"#
+                + r#"<rect xmlns:xlink="http://www.w3.org/1999/xlink" xlink:title="Box"/></svg>"#
+                + "<p>Text</p></div>"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use futures_signals::signal_vec::MutableVec;
+use silkenweb::{
+    dom::DefaultDom,
+    elements::html::{li, ul},
+    node::{element::TextParentElement, Node},
+    sortable::Sortable,
+    task::render_now,
+};
+
+fn item(text: &str, index: usize) -> String {
+    format!(
+        r#"<li tabindex="0" aria-roledescription="sortable item" data-sortable-index="{index}">{text}</li>"#
+    )
+}
+
+isomorphic_test! {
+    async fn render_items() {
+        let items = MutableVec::new_with_values(vec!["a", "b", "c"]);
+        let node: Node<DefaultDom> = Sortable::new(items.clone())
+            .render(ul(), |text| li().text(text))
+            .into();
+
+        render_now().await;
+        assert_eq!(
+            node.to_string(),
+            format!(
+                r#"<ul data-sortable-list="">{}{}{}</ul>"#,
+                item("a", 0),
+                item("b", 1),
+                item("c", 2)
+            )
+        );
+
+        items.lock_mut().move_from_to(2, 0);
+        render_now().await;
+        assert_eq!(
+            node.to_string(),
+            format!(
+                r#"<ul data-sortable-list="">{}{}{}</ul>"#,
+                item("c", 0),
+                item("a", 1),
+                item("b", 2)
+            ),
+            "Items are reordered and reindexed"
+        );
+    }
+}
+
+#[silkenweb::cfg_browser(true)]
+mod browser_tests {
+    use futures_signals::signal_vec::MutableVec;
+    use silkenweb::{
+        elements::html::{li, ul},
+        mount,
+        node::element::{Element, TextParentElement},
+        sortable::Sortable,
+        task::render_now,
+    };
+    use silkenweb_test::{html_element, BrowserTest};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::APP_ID;
+
+    fn key_down(id: &str, key: &str) {
+        let init = web_sys::KeyboardEventInit::new();
+        init.set_key(key);
+
+        html_element(id)
+            .dispatch_event(
+                &web_sys::KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init)
+                    .unwrap(),
+            )
+            .unwrap();
+    }
+
+    fn is_dragging(id: &str) -> bool {
+        html_element(id).has_attribute("data-sortable-dragging")
+    }
+
+    #[wasm_bindgen_test]
+    async fn focus_loss_drops_item() {
+        let _test = BrowserTest::new(APP_ID).await;
+        let items = MutableVec::new_with_values(vec!["a", "b"]);
+
+        mount(
+            APP_ID,
+            Sortable::new(items.clone()).render(ul(), |text| li().id(text).text(text)),
+        );
+        render_now().await;
+
+        key_down("a", " ");
+        render_now().await;
+        assert!(is_dragging("a"), "Picked up");
+
+        html_element("a")
+            .dispatch_event(&web_sys::FocusEvent::new("focusout").unwrap())
+            .unwrap();
+        render_now().await;
+        assert!(!is_dragging("a"), "Dropped on focus loss");
+
+        key_down("a", "ArrowDown");
+        render_now().await;
+        assert_eq!(
+            *items.lock_ref(),
+            ["a", "b"],
+            "Arrow keys don't move the item"
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use silkenweb::{document::HtmlDocument, elements::html::p, state_transfer::StateKey};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct User {
+    name: String,
+    admin: bool,
+}
+
+const USER: StateKey<User> = StateKey::new("state-transfer-user");
+const COUNT: StateKey<u32> = StateKey::new("state-transfer-count");
+const MISSING: StateKey<u32> = StateKey::new("state-transfer-missing");
+const HTML: StateKey<String> = StateKey::new("state-transfer-html");
+
+isomorphic_test! {
+    async fn set_and_get() {
+        let user = User {
+            name: "Alice".to_string(),
+            admin: true,
+        };
+
+        USER.set(&user);
+        assert_eq!(USER.get(), Some(user));
+        assert_eq!(MISSING.get(), None);
+    }
+}
+
+isomorphic_test! {
+    async fn get_or_insert_with() {
+        assert_eq!(COUNT.get_or_insert_with(|| 1), 1);
+        assert_eq!(COUNT.get_or_insert_with(|| panic!("Already registered")), 1);
+        assert_eq!(COUNT.get(), Some(1));
+    }
+}
+
+isomorphic_test! {
+    async fn document_script() {
+        HTML.set(&"</script><script>alert(1)</script>".to_string());
+        let html = HtmlDocument::new().render(&p().freeze());
+
+        assert!(html.contains(r#"<script type="application/json" id="silkenweb-state">"#));
+        assert!(html.contains(
+            r#""state-transfer-html":"\u003c/script\u003e\u003cscript\u003ealert(1)\u003c/script\u003e""#
+        ));
+    }
+}
+
+#[silkenweb::cfg_browser(false)]
+#[test]
+fn scoped_per_task() {
+    use silkenweb::task;
+
+    task::sync_scope(|| COUNT.set(&1));
+    assert_eq!(task::sync_scope(|| COUNT.get()), None);
+}
@@ -0,0 +1,178 @@
+use futures::{channel::oneshot, Stream, StreamExt};
+use silkenweb::{
+    document::{Document, DocumentHead, HtmlDocument},
+    dom::Dry,
+    elements::html::{div, p, title},
+    node::{
+        element::{ParentElement, TextParentElement},
+        Node,
+    },
+    streaming::{deferred, HtmlStream},
+    task::render_now,
+};
+
+async fn next_chunk(html: &mut (impl Stream<Item = bytes::Bytes> + Unpin)) -> Option<String> {
+    let chunk = html.next().await?;
+    Some(String::from_utf8(chunk.to_vec()).unwrap())
+}
+
+isomorphic_test! {
+    async fn stream_out_of_order() {
+        let (send_first, first) = oneshot::channel::<&str>();
+        let (send_second, second) = oneshot::channel::<&str>();
+        let html = HtmlStream::new(move || {
+            div()
+                .child(deferred(p().text("Loading first"), async {
+                    p().text(first.await.unwrap())
+                }))
+                .child(deferred(p().text("Loading second"), async {
+                    p().text(second.await.unwrap())
+                }))
+        })
+        .shell("<body>", "</body>")
+        .into_local_stream();
+        let mut html = Box::pin(html);
+
+        let shell = next_chunk(&mut html).await.unwrap();
+        assert!(shell.starts_with("<body><div>"));
+        assert!(shell.contains(r#"data-silkenweb-deferred="0""#));
+        assert!(shell.contains("<p>Loading first</p>"));
+        assert!(shell.contains(r#"data-silkenweb-deferred="1""#));
+        assert!(shell.contains("<p>Loading second</p>"));
+        assert!(shell.ends_with("</script>"));
+
+        send_second.send("Second").unwrap();
+        assert_eq!(
+            next_chunk(&mut html).await.unwrap(),
+            r#"<template data-silkenweb-fill="1"><p>Second</p></template><script>__silkenwebFill(1)</script>"#
+        );
+
+        send_first.send("First").unwrap();
+        assert_eq!(
+            next_chunk(&mut html).await.unwrap(),
+            r#"<template data-silkenweb-fill="0"><p>First</p></template><script>__silkenwebFill(0)</script>"#
+        );
+
+        assert_eq!(next_chunk(&mut html).await.unwrap(), "</body>");
+        assert_eq!(next_chunk(&mut html).await, None);
+    }
+}
+
+isomorphic_test! {
+    async fn nested_deferred() {
+        let html = HtmlStream::new(|| {
+            deferred::<Dry, _>(p().text("Loading outer"), async {
+                div().child(deferred(p().text("Loading inner"), async { p().text("Inner") }))
+            })
+        })
+        .into_local_stream();
+        let chunks: Vec<_> = html
+            .map(|chunk| String::from_utf8(chunk.to_vec()).unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(chunks.len(), 4);
+        assert!(chunks[1].starts_with(r#"<template data-silkenweb-fill="0"><div>"#));
+        assert!(chunks[1].contains(r#"data-silkenweb-deferred="1""#));
+        assert!(chunks[1].contains("<p>Loading inner</p>"));
+        assert!(chunks[2].starts_with(r#"<template data-silkenweb-fill="1"><p>Inner</p>"#));
+        assert_eq!(chunks[3], "");
+    }
+}
+
+isomorphic_test! {
+    async fn without_stream() {
+        let node: Node<Dry> = div()
+            .child(deferred(p().text("Loading"), async { p().text("Ready") }))
+            .into();
+
+        render_now().await;
+        let html = node.to_string();
+        assert!(html.contains("<p>Ready</p>"));
+        assert!(!html.contains("data-silkenweb-deferred"));
+    }
+}
+
+isomorphic_test! {
+    async fn stream_document() {
+        let html = HtmlStream::new(|| {
+            Dry::mount_in_head("head", DocumentHead::new().child(title().text("Streamed")));
+            deferred::<Dry, _>(p().text("Loading"), async { p().text("Ready") })
+        })
+        .document(HtmlDocument::new().lang("en"))
+        .into_local_stream();
+        let chunks: Vec<_> = html
+            .map(|chunk| String::from_utf8(chunk.to_vec()).unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[0].starts_with(
+            r#"<!DOCTYPE html><html lang="en"><head><meta charset="utf-8"><title data-silkenweb-head-id="head">Streamed</title></head><body><div"#
+        ));
+        assert!(chunks[1].starts_with(r#"<template data-silkenweb-fill="0"><p>Ready</p>"#));
+        assert_eq!(chunks[2], "</body></html>");
+    }
+}
+
+#[silkenweb::cfg_browser(false)]
+#[tokio::test]
+async fn send_stream() {
+    let html: String =
+        HtmlStream::new(|| deferred::<Dry, _>(p().text("Loading"), async { p().text("Ready") }))
+            .shell("<body>", "</body>")
+            .into_stream(&tokio_util::task::LocalPoolHandle::new(1))
+            .map(|chunk| String::from_utf8(chunk.to_vec()).unwrap())
+            .collect()
+            .await;
+
+    assert!(html.starts_with("<body>"));
+    assert!(html.contains(r#"<template data-silkenweb-fill="0"><p>Ready</p></template>"#));
+    assert!(html.ends_with("</body>"));
+}
+
+#[silkenweb::cfg_browser(true)]
+mod browser_tests {
+    use futures::channel::oneshot;
+    use silkenweb::{
+        dom::Hydro,
+        elements::{
+            html::{div, p},
+            HtmlElement,
+        },
+        hydration::hydrate,
+        node::element::{ParentElement, TextParentElement},
+        streaming::deferred,
+        task::render_now,
+    };
+    use silkenweb_test::{html_element, BrowserTest};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::APP_ID;
+
+    #[wasm_bindgen_test]
+    async fn hydrate_streamed_content() {
+        let test = BrowserTest::new(APP_ID).await;
+        html_element(APP_ID).set_inner_html(
+            r#"<div style="display: contents;" data-silkenweb-deferred="0"><p>Ready</p></div>"#,
+        );
+        let (send_content, content) = oneshot::channel::<&str>();
+        let app = div::<Hydro>()
+            .id(APP_ID)
+            .child(deferred(p().text("Loading"), async {
+                p().text(content.await.unwrap())
+            }));
+
+        hydrate(APP_ID, app).await;
+        let html = test.html();
+        assert!(html.contains(r#"data-silkenweb-deferred="0""#));
+        assert!(html.contains("<p>Loading</p>"), "The fallback is hydrated");
+        assert!(!html.contains("Ready"));
+
+        send_content.send("Ready").unwrap();
+        render_now().await;
+        let html = test.html();
+        assert!(html.contains("<p>Ready</p>"));
+        assert!(!html.contains("Loading"));
+    }
+}
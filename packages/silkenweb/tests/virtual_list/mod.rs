@@ -0,0 +1,56 @@
+use futures_signals::signal_vec::MutableVec;
+use silkenweb::{
+    dom::DefaultDom,
+    elements::html::{div, p},
+    node::{element::TextParentElement, Node},
+    task::render_now,
+    value::Sig,
+    virtual_list::{RowHeight, VirtualList},
+};
+
+fn list(height: usize, rows: &[(usize, &str)]) -> String {
+    let rows: String = rows
+        .iter()
+        .map(|(offset, text)| {
+            format!(
+                "<div style=\"position: absolute; top: 0; left: 0; width: 100%; height: 10px; \
+                 transform: translate(0px, {offset}px);\"><p>{text}</p></div>"
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<div style="overflow-y: auto;"><div style="position: relative; height: {height}px;">{rows}</div></div>"#
+    )
+}
+
+isomorphic_test! {
+    async fn render_visible_rows() {
+        let items = MutableVec::new_with_values((0..100).collect::<Vec<usize>>());
+        let node: Node<DefaultDom> = VirtualList::new(items.signal_vec(), RowHeight::Fixed(10.0))
+            .overscan(1)
+            .render(div(), |row| p().text(Sig(row.item_ref(|i| i.to_string()))))
+            .into();
+
+        render_now().await;
+        assert_eq!(
+            node.to_string(),
+            list(1000, &[(0, "0"), (10, "1")]),
+            "Only the first row and overscan are rendered"
+        );
+
+        items.lock_mut().set_cloned(1, 42);
+        render_now().await;
+        assert_eq!(
+            node.to_string(),
+            list(1000, &[(0, "0"), (10, "42")]),
+            "Visible rows are updated"
+        );
+
+        items.lock_mut().truncate(1);
+        render_now().await;
+        let html = node.to_string();
+        assert!(html.contains("height: 10px;\"><div"), "Scroll height is updated");
+        assert_eq!(html.matches(r#"hidden="""#).count(), 1, "Unused rows are hidden");
+    }
+}
@@ -0,0 +1,63 @@
+#[silkenweb::cfg_browser(false)]
+mod server_tests {
+    use futures::StreamExt;
+    use futures_signals::signal::{Signal, SignalExt};
+    use silkenweb::{
+        task::{self, server::block_on},
+        window::{self, ColorScheme, ServerDefaults},
+    };
+
+    async fn current<T>(signal: impl Signal<Item = T> + Unpin) -> T {
+        signal.to_stream().next().await.unwrap()
+    }
+
+    #[test]
+    fn server_defaults() {
+        block_on(task::scope(async {
+            assert_eq!(current(window::inner_width()).await, 1280.0);
+            assert_eq!(current(window::inner_height()).await, 720.0);
+            assert_eq!(current(window::device_pixel_ratio()).await, 1.0);
+            assert!(current(window::is_online()).await);
+            assert!(current(window::is_page_visible()).await);
+            assert_eq!(
+                current(window::prefers_color_scheme()).await,
+                ColorScheme::Light
+            );
+            assert!(!current(window::media_query("(min-width: 800px)")).await);
+        }));
+    }
+
+    #[test]
+    fn set_server_defaults() {
+        block_on(task::scope(async {
+            let mut width = window::inner_width().to_stream();
+            assert_eq!(width.next().await, Some(1280.0));
+
+            window::set_server_defaults(
+                ServerDefaults::new()
+                    .inner_size(390.0, 844.0)
+                    .scroll(0.0, 100.0)
+                    .color_scheme(ColorScheme::Dark)
+                    .reduced_motion(true)
+                    .media_query("(min-width: 800px)", false),
+            );
+
+            assert_eq!(width.next().await, Some(390.0), "Existing signals update");
+            assert_eq!(current(window::scroll_y()).await, 100.0);
+            assert_eq!(
+                current(window::prefers_color_scheme()).await,
+                ColorScheme::Dark
+            );
+            assert!(current(window::prefers_reduced_motion()).await);
+            assert!(!current(window::media_query("(min-width: 800px)")).await);
+        }));
+
+        block_on(task::scope(async {
+            assert_eq!(
+                current(window::inner_width()).await,
+                1280.0,
+                "Defaults are per scope"
+            );
+        }));
+    }
+}